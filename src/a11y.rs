@@ -0,0 +1,622 @@
+/*
+This source code file is distributed subject to the terms of the Mozilla Public License v2.0.
+A copy of this license can be found in the `licenses` directory at the root of this project.
+*/
+//! An accessibility linter, which finds common accessibility problems in a
+//! document (for example, images without any alternative text).
+//!
+//! Each [`Finding`] refers to the [WCAG](https://www.w3.org/TR/WCAG22/)
+//! success criterion which the problem fails, so the linter can be run in a
+//! test to catch regressions:
+//!
+//! ```
+//! # use malvolio::prelude::*;
+//! use malvolio::a11y::{lint, Finding, Rule};
+//!
+//! let document = Html::new().attribute(Lang::new("en")).body(
+//!     Body::new()
+//!         .child(H1::new("Cats"))
+//!         .child(Img::new().attribute(Src::new("/cat.png")))
+//!         .child(H4::new("Tabby cats")),
+//! );
+//! let findings = lint(&document);
+//! assert_eq!(
+//!     findings,
+//!     vec![
+//!         Finding {
+//!             path: vec![1],
+//!             rule: Rule::MissingAlt
+//!         },
+//!         Finding {
+//!             path: vec![2],
+//!             rule: Rule::SkippedHeadingLevel {
+//!                 previous: 1,
+//!                 level: 4
+//!             }
+//!         },
+//!     ]
+//! );
+//! assert_eq!(findings[0].rule.criterion(), "1.1.1 Non-text Content");
+//! ```
+//!
+//! Nodes are identified by their path, in the same way as in the
+//! [`diff`](crate::diff) module. `Shared` nodes are checked as if they were
+//! the node they contain, and `Lazy` nodes are constructed (and then checked);
+//! the nodes of a `Fragment` inside either are counted as children of the
+//! fragment's parent. The linter can not find every problem (many of the WCAG criteria can only be
+//! checked by a person), so it is not a replacement for testing a page by hand.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+
+use crate::{
+    attributes::{aria::Role, Attributes},
+    tags::{
+        body::{body_node::BodyNode, Body},
+        html::Html,
+    },
+    utils::flatten_children,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An accessibility problem, and where it was found.
+pub struct Finding {
+    /// The path of the node the problem was found at (the list of indices of
+    /// the children which need to be followed to get from the `<body>` to the
+    /// node). This is empty for problems with the `<html>` tag.
+    pub path: Vec<usize>,
+    /// The rule which the node breaks.
+    pub rule: Rule,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The problems which the linter finds.
+pub enum Rule {
+    /// An `<img>` without an `alt` attribute (the attribute should be empty
+    /// for images which are only decorative).
+    MissingAlt,
+    /// An `<input>` or `<select>` without a label (a `<label>` whose `for`
+    /// attribute is the control's id, or an `aria-label`, `aria-labelledby` or
+    /// `title` attribute).
+    MissingLabel,
+    /// A heading which is more than one level below the previous heading (for
+    /// example, an `<h4>` after an `<h1>`).
+    SkippedHeadingLevel {
+        /// The level of the previous heading.
+        previous: u8,
+        /// The level of this heading.
+        level: u8,
+    },
+    /// A link without any text (or an `aria-label`, `aria-labelledby` or
+    /// `title` attribute).
+    EmptyLink,
+    /// The `<html>` tag does not have a `lang` attribute.
+    MissingLang,
+    /// A landmark role which is used more than once, where it should only be
+    /// used once (`banner`, `contentinfo` and `main`), or where the landmarks
+    /// can not be told apart (because they do not have different labels).
+    DuplicateLandmark {
+        /// The role.
+        role: Role,
+    },
+}
+
+impl Rule {
+    /// Returns the WCAG success criterion which this rule checks (e.g. `"1.1.1
+    /// Non-text Content"`).
+    pub fn criterion(&self) -> &'static str {
+        match self {
+            Rule::MissingAlt => "1.1.1 Non-text Content",
+            Rule::MissingLabel => "4.1.2 Name, Role, Value",
+            Rule::SkippedHeadingLevel { .. } | Rule::DuplicateLandmark { .. } => {
+                "1.3.1 Info and Relationships"
+            }
+            Rule::EmptyLink => "2.4.4 Link Purpose (In Context)",
+            Rule::MissingLang => "3.1.1 Language of Page",
+        }
+    }
+
+    /// Returns a link to the explanation of the WCAG success criterion which
+    /// this rule checks.
+    pub fn url(&self) -> &'static str {
+        match self {
+            Rule::MissingAlt => "https://www.w3.org/WAI/WCAG22/Understanding/non-text-content",
+            Rule::MissingLabel => "https://www.w3.org/WAI/WCAG22/Understanding/name-role-value",
+            Rule::SkippedHeadingLevel { .. } | Rule::DuplicateLandmark { .. } => {
+                "https://www.w3.org/WAI/WCAG22/Understanding/info-and-relationships"
+            }
+            Rule::EmptyLink => {
+                "https://www.w3.org/WAI/WCAG22/Understanding/link-purpose-in-context"
+            }
+            Rule::MissingLang => "https://www.w3.org/WAI/WCAG22/Understanding/language-of-page",
+        }
+    }
+}
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.rule {
+            Rule::MissingAlt => f.write_str("`<img>` does not have an `alt` attribute")?,
+            Rule::MissingLabel => f.write_str("form control does not have a label")?,
+            Rule::SkippedHeadingLevel { previous, level } => write!(
+                f,
+                "`<h{}>` follows `<h{}>` (skipping a heading level)",
+                level, previous
+            )?,
+            Rule::EmptyLink => f.write_str("link does not have any text")?,
+            Rule::MissingLang => f.write_str("`<html>` does not have a `lang` attribute")?,
+            Rule::DuplicateLandmark { role } => {
+                write!(f, "the `{}` landmark is used more than once", role.as_str())?
+            }
+        }
+        write!(
+            f,
+            " (at {:?}, see WCAG {})",
+            self.path,
+            self.rule.criterion()
+        )
+    }
+}
+
+/// Checks the document for accessibility problems, returning them in document
+/// order.
+pub fn lint(html: &Html<'_>) -> Vec<Finding> {
+    let mut linter = Linter::default();
+    if html
+        .get_attribute("lang")
+        .filter(|lang| !lang.trim().is_empty())
+        .is_none()
+    {
+        linter.report(Rule::MissingLang);
+    }
+    linter.lint_children(html.body.child_nodes());
+    linter.finish()
+}
+
+/// Checks the contents of a `<body>` tag for accessibility problems (this is
+/// the same as [`lint`], except that the `<html>` tag is not checked).
+pub fn lint_body(body: &Body<'_>) -> Vec<Finding> {
+    let mut linter = Linter::default();
+    linter.lint_children(body.child_nodes());
+    linter.finish()
+}
+
+/// The landmark roles, of which the first three should only be used once.
+const LANDMARKS: &[Role] = &[
+    Role::Banner,
+    Role::ContentInfo,
+    Role::Main,
+    Role::Complementary,
+    Role::Form,
+    Role::Navigation,
+    Role::Region,
+    Role::Search,
+];
+
+#[derive(Default)]
+struct Linter {
+    path: Vec<usize>,
+    findings: Vec<Finding>,
+    previous_heading: Option<u8>,
+    /// The values of the `for` attributes of the labels in the document.
+    labels: HashSet<String>,
+    /// The paths (and ids) of the form controls which need a `<label>`
+    /// (which might come after the control in the document).
+    unlabelled: Vec<(Vec<usize>, Option<String>)>,
+    /// The labels of each of the landmarks which have been found.
+    landmarks: HashMap<&'static str, Vec<Option<String>>>,
+}
+
+impl Linter {
+    fn lint_children(&mut self, children: &[BodyNode<'_>]) {
+        for (index, child) in flatten_children(children).iter().enumerate() {
+            self.path.push(index);
+            self.lint_node(child);
+            self.path.pop();
+        }
+    }
+
+    fn lint_node(&mut self, node: &BodyNode<'_>) {
+        match node {
+            BodyNode::Shared(shared) => return self.lint_node(shared.get()),
+            BodyNode::Img(img) if !img.has_attribute("alt") => self.report(Rule::MissingAlt),
+            BodyNode::Input(_) | BodyNode::Select(_) => self.lint_control(node),
+            BodyNode::Label(label) => {
+                if let Some(control) = label.get_attribute("for") {
+                    self.labels.insert(control.to_string());
+                }
+            }
+            BodyNode::A(_) => {
+                let empty = node.text().filter(|text| !text.trim().is_empty()).is_none();
+                if empty && !has_name(node) {
+                    self.report(Rule::EmptyLink);
+                }
+            }
+            BodyNode::H1(_) => self.lint_heading(1),
+            BodyNode::H2(_) => self.lint_heading(2),
+            BodyNode::H3(_) => self.lint_heading(3),
+            BodyNode::H4(_) => self.lint_heading(4),
+            BodyNode::H5(_) => self.lint_heading(5),
+            BodyNode::H6(_) => self.lint_heading(6),
+            _ => {}
+        }
+        if let Some(role) = node.read::<Role>().filter(|role| LANDMARKS.contains(role)) {
+            self.lint_landmark(node, role);
+        }
+        self.lint_children(node.children());
+    }
+
+    fn lint_control(&mut self, node: &BodyNode<'_>) {
+        let kind = node.get_attribute("type").unwrap_or_default();
+        let named_by = |attribute| {
+            node.get_attribute(attribute)
+                .is_some_and(|value| !value.trim().is_empty())
+        };
+        let named = if kind.eq_ignore_ascii_case("hidden")
+            || kind.eq_ignore_ascii_case("submit")
+            || kind.eq_ignore_ascii_case("reset")
+        {
+            // these have a default label (or are not displayed at all)
+            true
+        } else if kind.eq_ignore_ascii_case("button") {
+            named_by("value")
+        } else if kind.eq_ignore_ascii_case("image") {
+            named_by("alt")
+        } else {
+            false
+        };
+        if !(named || has_name(node)) {
+            let id = node.get_attribute("id").map(str::to_string);
+            self.unlabelled.push((self.path.clone(), id));
+        }
+    }
+
+    fn lint_heading(&mut self, level: u8) {
+        if let Some(previous) = self.previous_heading {
+            if level > previous + 1 {
+                self.report(Rule::SkippedHeadingLevel { previous, level });
+            }
+        }
+        self.previous_heading = Some(level);
+    }
+
+    fn lint_landmark(&mut self, node: &BodyNode<'_>, role: Role) {
+        let label = node
+            .get_attribute("aria-label")
+            .or_else(|| node.get_attribute("aria-labelledby"))
+            .map(str::to_string);
+        let seen = self.landmarks.entry(role.as_str()).or_default();
+        let duplicate = !seen.is_empty()
+            && (LANDMARKS[..3].contains(&role) || label.is_none() || seen.contains(&label));
+        seen.push(label);
+        if duplicate {
+            self.report(Rule::DuplicateLandmark { role });
+        }
+    }
+
+    fn report(&mut self, rule: Rule) {
+        self.findings.push(Finding {
+            path: self.path.clone(),
+            rule,
+        });
+    }
+
+    fn finish(mut self) -> Vec<Finding> {
+        for (path, id) in std::mem::take(&mut self.unlabelled) {
+            if !id.is_some_and(|id| self.labels.contains(&id)) {
+                self.findings.push(Finding {
+                    path,
+                    rule: Rule::MissingLabel,
+                });
+            }
+        }
+        // the paths are in document order (the sort is stable, so findings for
+        // the same node stay in the order they were found in)
+        self.findings.sort_by(|a, b| a.path.cmp(&b.path));
+        self.findings
+    }
+}
+
+/// Returns true if the node has been given an accessible name with an
+/// attribute.
+fn has_name(node: &BodyNode<'_>) -> bool {
+    ["aria-label", "aria-labelledby", "title"]
+        .iter()
+        .any(|key| {
+            node.get_attribute(key)
+                .is_some_and(|value| !value.trim().is_empty())
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use std::borrow::Cow;
+
+    use super::{lint, lint_body, Finding, Rule};
+    use crate::{
+        attributes::{Attributes, IntoAttribute},
+        prelude::*,
+    };
+
+    #[test]
+    fn test_lint() {
+        let accessible = Html::new().attribute(Lang::new("en")).body(
+            Body::new()
+                .child(Div::new().attribute(Role::Banner).child(H1::new("Title")))
+                .child(
+                    Div::new()
+                        .attribute(Role::Navigation)
+                        .attribute(AriaLabel::new("Main")),
+                )
+                .child(
+                    Div::new()
+                        .attribute(Role::Navigation)
+                        .attribute(AriaLabel::new("Footer")),
+                )
+                .child(H2::new("Sign up"))
+                .child(
+                    Form::new()
+                        .child(Label::new("Email").raw_attribute("for", "email"))
+                        .child(
+                            Input::new()
+                                .attribute(Type::Email)
+                                .attribute(Id::new("email")),
+                        )
+                        .child(Input::new().attribute(AriaLabel::new("Name")))
+                        .child(Input::new().attribute(Type::Submit)),
+                )
+                .child(A::new().href("/").text("Home"))
+                .child(
+                    Img::new()
+                        .attribute(Src::new("/line.png"))
+                        .attribute(Alt::new("")),
+                ),
+        );
+        assert_eq!(lint(&accessible), vec![]);
+
+        let inaccessible = Html::new().body(
+            Body::new()
+                .child(Div::new().attribute(Role::Main))
+                .child(H2::new("Heading"))
+                .child(Select::new().attribute(Id::new("unlabelled")))
+                .child(
+                    Div::new()
+                        .attribute(Role::Main)
+                        .child(H5::new("Subheading")),
+                )
+                .child(A::new().href("/").text(" "))
+                .child(A::new().href("/").attribute(AriaLabel::new("Home"))),
+        );
+        let findings = lint(&inaccessible);
+        assert_eq!(
+            findings,
+            vec![
+                Finding {
+                    path: vec![],
+                    rule: Rule::MissingLang
+                },
+                Finding {
+                    path: vec![2],
+                    rule: Rule::MissingLabel
+                },
+                Finding {
+                    path: vec![3],
+                    rule: Rule::DuplicateLandmark { role: Role::Main }
+                },
+                Finding {
+                    path: vec![3, 0],
+                    rule: Rule::SkippedHeadingLevel {
+                        previous: 2,
+                        level: 5
+                    }
+                },
+                Finding {
+                    path: vec![4],
+                    rule: Rule::EmptyLink
+                },
+            ]
+        );
+        assert_eq!(
+            findings[3].to_string(),
+            "`<h5>` follows `<h2>` (skipping a heading level) (at [3, 0], see WCAG 1.3.1 Info \
+             and Relationships)"
+        );
+        assert_eq!(lint_body(&inaccessible.body), findings[1..]);
+    }
+
+    /// Lints the nodes inside a `<body>`, returning the rules which were
+    /// broken (and where).
+    fn rules<'a>(nodes: impl IntoIterator<Item = BodyNode<'a>>) -> Vec<(Vec<usize>, Rule)> {
+        lint_body(&Body::new().children(nodes))
+            .into_iter()
+            .map(|finding| (finding.path, finding.rule))
+            .collect()
+    }
+
+    #[test]
+    fn test_missing_alt() {
+        assert_eq!(
+            rules([
+                Img::new().into(),
+                Img::new().attribute(Alt::new("")).into(),
+                Shared::new(Img::new()).into(),
+            ]),
+            vec![(vec![0], Rule::MissingAlt), (vec![2], Rule::MissingAlt)]
+        );
+    }
+
+    /// An `<input>` with the provided (raw) attributes.
+    fn input(attributes: &[(&'static str, &'static str)]) -> BodyNode<'static> {
+        struct Raw(&'static str, &'static str);
+
+        impl<'a> IntoAttribute<'a> for Raw {
+            fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
+                (self.0.into(), self.1.into())
+            }
+        }
+
+        let mut node = BodyNode::from(Input::new());
+        for (key, value) in attributes {
+            node.set_attribute(Raw(key, value)).unwrap();
+        }
+        node
+    }
+
+    #[test]
+    fn test_missing_label() {
+        let unlabelled = rules([
+            Input::new().into(),
+            Input::new().attribute(AriaLabel::new(" ")).into(),
+            Select::new().attribute(Id::new("no-label")).into(),
+            input(&[("type", "button")]),
+            input(&[("type", "image")]),
+        ]);
+        assert_eq!(
+            unlabelled,
+            (0..5)
+                .map(|index| (vec![index], Rule::MissingLabel))
+                .collect::<Vec<_>>()
+        );
+
+        let labelled = rules([
+            // the label can come after the control
+            Input::new().attribute(Id::new("email")).into(),
+            Label::new("Email").raw_attribute("for", "email").into(),
+            Input::new().attribute(AriaLabel::new("Name")).into(),
+            Input::new()
+                .attribute(AriaLabelledBy::new_unchecked("email"))
+                .into(),
+            Select::new().attribute(TitleAttr::new("Country")).into(),
+            Input::new().attribute(Type::Hidden).into(),
+            Input::new().attribute(Type::Submit).into(),
+            input(&[("type", "reset")]),
+            input(&[("type", "button"), ("value", "Go")]),
+            input(&[("type", "image"), ("alt", "Search")]),
+        ]);
+        assert_eq!(labelled, vec![]);
+    }
+
+    #[test]
+    fn test_skipped_heading_level() {
+        assert_eq!(
+            rules([
+                H3::new("a").into(),
+                H1::new("b").into(),
+                H2::new("c").into(),
+                H4::new("d").into(),
+                H2::new("e").into(),
+                H3::new("f").into(),
+            ]),
+            vec![(
+                vec![3],
+                Rule::SkippedHeadingLevel {
+                    previous: 2,
+                    level: 4
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn test_empty_link() {
+        assert_eq!(
+            rules([
+                A::new().href("/").into(),
+                A::new().href("/").text(" ").into(),
+                A::new().href("/").text("Home").into(),
+                A::new().href("/").attribute(AriaLabel::new("Home")).into(),
+                A::new().href("/").attribute(TitleAttr::new("Home")).into(),
+            ]),
+            vec![(vec![0], Rule::EmptyLink), (vec![1], Rule::EmptyLink)]
+        );
+    }
+
+    #[test]
+    fn test_missing_lang() {
+        let missing = Finding {
+            path: vec![],
+            rule: Rule::MissingLang,
+        };
+        assert_eq!(lint(&Html::new()), vec![missing.clone()]);
+        assert_eq!(lint(&Html::new().attribute(Lang::new(" "))), vec![missing]);
+        assert_eq!(lint(&Html::new().attribute(Lang::new("en"))), vec![]);
+    }
+
+    #[test]
+    fn test_duplicate_landmark() {
+        let landmark = |role, label: Option<&'static str>| {
+            Div::new()
+                .attribute(role)
+                .map(|div| match label {
+                    Some(label) => div.attribute(AriaLabel::new(label)),
+                    None => div,
+                })
+                .into()
+        };
+        assert_eq!(
+            rules([
+                landmark(Role::Main, Some("a")),
+                landmark(Role::Main, Some("b")),
+                landmark(Role::Navigation, Some("a")),
+                landmark(Role::Navigation, Some("b")),
+                landmark(Role::Navigation, Some("a")),
+                landmark(Role::Region, Some("a")),
+                Shared::new(Div::new().attribute(Role::Region)).into(),
+                // not a landmark
+                landmark(Role::Button, None),
+                landmark(Role::Button, None),
+            ]),
+            vec![
+                (vec![1], Rule::DuplicateLandmark { role: Role::Main }),
+                (
+                    vec![4],
+                    Rule::DuplicateLandmark {
+                        role: Role::Navigation
+                    }
+                ),
+                (vec![6], Rule::DuplicateLandmark { role: Role::Region }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fragments_are_flattened() {
+        assert_eq!(
+            rules([
+                Div::new().into(),
+                lazy(|| Fragment::new().child(Div::new()).child(Img::new())).into(),
+                Img::new().into(),
+            ]),
+            vec![(vec![2], Rule::MissingAlt), (vec![3], Rule::MissingAlt)]
+        );
+    }
+
+    #[test]
+    fn test_criteria() {
+        let rules = [
+            Rule::MissingAlt,
+            Rule::MissingLabel,
+            Rule::SkippedHeadingLevel {
+                previous: 1,
+                level: 3,
+            },
+            Rule::EmptyLink,
+            Rule::MissingLang,
+            Rule::DuplicateLandmark { role: Role::Main },
+        ];
+        for rule in rules {
+            assert!(rule
+                .url()
+                .starts_with("https://www.w3.org/WAI/WCAG22/Understanding/"));
+            let finding = Finding {
+                path: vec![0],
+                rule,
+            };
+            assert!(finding
+                .to_string()
+                .ends_with(&format!("(at [0], see WCAG {})", finding.rule.criterion())));
+        }
+    }
+}
@@ -0,0 +1,1023 @@
+/*
+This source code file is distributed subject to the terms of the Mozilla Public License v2.0.
+A copy of this license can be found in the `licenses` directory at the root of this project.
+*/
+//! These attributes (`role` and `aria-*`) can be attached to any tag.
+//!
+//! ```
+//! # use malvolio::prelude::*;
+//! Div::new()
+//!     .attribute(Role::Navigation)
+//!     .attribute(AriaLabel::new("Main menu"))
+//!     .attribute(AriaExpanded::new(false))
+//!     .attribute(AriaControls::new_unchecked("menu-items"));
+//! ```
+//!
+//! The attributes which refer to other elements (such as `AriaControls`) take
+//! an [`IdRef`], which is returned when the id of the other element is set
+//! with `with_id`, so that a misspelled id is a compile error:
+//!
+//! ```
+//! # use malvolio::prelude::*;
+//! let (menu, menu_id) = Div::new().with_id("menu-items");
+//! let button = Div::new().attribute(AriaControls::new(&menu_id));
+//! assert_eq!(button.to_string(), "<div aria-controls=\"menu-items\"></div>");
+//! ```
+//!
+//! See the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/Accessibility/ARIA)
+//! for further information.
+use std::{borrow::Cow, num::NonZeroU32};
+
+use super::{common::IdRef, FromAttribute, IntoAttribute};
+
+/// The `role` attribute, which describes the purpose of an element to assistive
+/// technologies.
+///
+/// See the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/Accessibility/ARIA/Roles)
+/// for further information.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum Role {
+    Alert,
+    AlertDialog,
+    Application,
+    Article,
+    Banner,
+    Button,
+    Cell,
+    Checkbox,
+    ColumnHeader,
+    Combobox,
+    Complementary,
+    ContentInfo,
+    Definition,
+    Dialog,
+    Document,
+    Feed,
+    Figure,
+    Form,
+    Grid,
+    GridCell,
+    Group,
+    Heading,
+    Img,
+    Link,
+    List,
+    Listbox,
+    ListItem,
+    Log,
+    Main,
+    Marquee,
+    Math,
+    Menu,
+    Menubar,
+    MenuItem,
+    MenuItemCheckbox,
+    MenuItemRadio,
+    Navigation,
+    None,
+    Note,
+    Option,
+    Presentation,
+    ProgressBar,
+    Radio,
+    RadioGroup,
+    Region,
+    Row,
+    RowGroup,
+    RowHeader,
+    Scrollbar,
+    Search,
+    Searchbox,
+    Separator,
+    Slider,
+    SpinButton,
+    Status,
+    Switch,
+    Tab,
+    Table,
+    TabList,
+    TabPanel,
+    Term,
+    Textbox,
+    Timer,
+    Toolbar,
+    Tooltip,
+    Tree,
+    TreeGrid,
+    TreeItem,
+}
+
+const ROLES: &[Role] = &[
+    Role::Alert,
+    Role::AlertDialog,
+    Role::Application,
+    Role::Article,
+    Role::Banner,
+    Role::Button,
+    Role::Cell,
+    Role::Checkbox,
+    Role::ColumnHeader,
+    Role::Combobox,
+    Role::Complementary,
+    Role::ContentInfo,
+    Role::Definition,
+    Role::Dialog,
+    Role::Document,
+    Role::Feed,
+    Role::Figure,
+    Role::Form,
+    Role::Grid,
+    Role::GridCell,
+    Role::Group,
+    Role::Heading,
+    Role::Img,
+    Role::Link,
+    Role::List,
+    Role::Listbox,
+    Role::ListItem,
+    Role::Log,
+    Role::Main,
+    Role::Marquee,
+    Role::Math,
+    Role::Menu,
+    Role::Menubar,
+    Role::MenuItem,
+    Role::MenuItemCheckbox,
+    Role::MenuItemRadio,
+    Role::Navigation,
+    Role::None,
+    Role::Note,
+    Role::Option,
+    Role::Presentation,
+    Role::ProgressBar,
+    Role::Radio,
+    Role::RadioGroup,
+    Role::Region,
+    Role::Row,
+    Role::RowGroup,
+    Role::RowHeader,
+    Role::Scrollbar,
+    Role::Search,
+    Role::Searchbox,
+    Role::Separator,
+    Role::Slider,
+    Role::SpinButton,
+    Role::Status,
+    Role::Switch,
+    Role::Tab,
+    Role::Table,
+    Role::TabList,
+    Role::TabPanel,
+    Role::Term,
+    Role::Textbox,
+    Role::Timer,
+    Role::Toolbar,
+    Role::Tooltip,
+    Role::Tree,
+    Role::TreeGrid,
+    Role::TreeItem,
+];
+
+impl Role {
+    /// The value of the `role` attribute for this role.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Alert => "alert",
+            Role::AlertDialog => "alertdialog",
+            Role::Application => "application",
+            Role::Article => "article",
+            Role::Banner => "banner",
+            Role::Button => "button",
+            Role::Cell => "cell",
+            Role::Checkbox => "checkbox",
+            Role::ColumnHeader => "columnheader",
+            Role::Combobox => "combobox",
+            Role::Complementary => "complementary",
+            Role::ContentInfo => "contentinfo",
+            Role::Definition => "definition",
+            Role::Dialog => "dialog",
+            Role::Document => "document",
+            Role::Feed => "feed",
+            Role::Figure => "figure",
+            Role::Form => "form",
+            Role::Grid => "grid",
+            Role::GridCell => "gridcell",
+            Role::Group => "group",
+            Role::Heading => "heading",
+            Role::Img => "img",
+            Role::Link => "link",
+            Role::List => "list",
+            Role::Listbox => "listbox",
+            Role::ListItem => "listitem",
+            Role::Log => "log",
+            Role::Main => "main",
+            Role::Marquee => "marquee",
+            Role::Math => "math",
+            Role::Menu => "menu",
+            Role::Menubar => "menubar",
+            Role::MenuItem => "menuitem",
+            Role::MenuItemCheckbox => "menuitemcheckbox",
+            Role::MenuItemRadio => "menuitemradio",
+            Role::Navigation => "navigation",
+            Role::None => "none",
+            Role::Note => "note",
+            Role::Option => "option",
+            Role::Presentation => "presentation",
+            Role::ProgressBar => "progressbar",
+            Role::Radio => "radio",
+            Role::RadioGroup => "radiogroup",
+            Role::Region => "region",
+            Role::Row => "row",
+            Role::RowGroup => "rowgroup",
+            Role::RowHeader => "rowheader",
+            Role::Scrollbar => "scrollbar",
+            Role::Search => "search",
+            Role::Searchbox => "searchbox",
+            Role::Separator => "separator",
+            Role::Slider => "slider",
+            Role::SpinButton => "spinbutton",
+            Role::Status => "status",
+            Role::Switch => "switch",
+            Role::Tab => "tab",
+            Role::Table => "table",
+            Role::TabList => "tablist",
+            Role::TabPanel => "tabpanel",
+            Role::Term => "term",
+            Role::Textbox => "textbox",
+            Role::Timer => "timer",
+            Role::Toolbar => "toolbar",
+            Role::Tooltip => "tooltip",
+            Role::Tree => "tree",
+            Role::TreeGrid => "treegrid",
+            Role::TreeItem => "treeitem",
+        }
+    }
+}
+
+impl<'a> IntoAttribute<'a> for Role {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
+        ("role".into(), self.as_str().into())
+    }
+}
+
+impl FromAttribute for Role {
+    const NAME: &'static str = "role";
+
+    fn from_attribute(value: &str) -> Option<Self> {
+        // the `role` attribute can contain a list of fallback roles, of which
+        // the first one that is recognised is used
+        value
+            .split_whitespace()
+            .find_map(|role| ROLES.iter().find(|known| known.as_str() == role))
+            .copied()
+    }
+}
+
+/// The value of a "tristate" ARIA attribute (such as `aria-checked`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum Tristate {
+    True,
+    False,
+    Mixed,
+}
+
+impl From<bool> for Tristate {
+    fn from(value: bool) -> Self {
+        if value {
+            Tristate::True
+        } else {
+            Tristate::False
+        }
+    }
+}
+
+impl Tristate {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Tristate::True => "true",
+            Tristate::False => "false",
+            Tristate::Mixed => "mixed",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "true" => Some(Tristate::True),
+            "false" => Some(Tristate::False),
+            "mixed" => Some(Tristate::Mixed),
+            _ => None,
+        }
+    }
+}
+
+/// Defines an ARIA attribute which takes a string.
+macro_rules! aria_string {
+    ($(#[$meta:meta])* $name:ident, $key:literal) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone)]
+        pub struct $name<'a>(Cow<'a, str>);
+
+        impl<'a> $name<'a> {
+            /// Create a new instance of this attribute with the specified value.
+            pub fn new<C>(c: C) -> Self
+            where
+                C: Into<Cow<'a, str>>,
+            {
+                Self(c.into())
+            }
+
+            /// Returns the value of this attribute.
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl<'a> IntoAttribute<'a> for $name<'a> {
+            fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
+                ($key.into(), self.0)
+            }
+        }
+
+        impl FromAttribute for $name<'static> {
+            const NAME: &'static str = $key;
+
+            fn from_attribute(value: &str) -> Option<Self> {
+                Some(Self::new(value.to_string()))
+            }
+        }
+    };
+}
+
+/// Defines an ARIA attribute which takes `true` or `false`.
+macro_rules! aria_bool {
+    ($(#[$meta:meta])* $name:ident, $key:literal) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone)]
+        pub struct $name(bool);
+
+        impl $name {
+            /// Create a new instance of this attribute with the specified value.
+            pub fn new(value: bool) -> Self {
+                Self(value)
+            }
+
+            /// Returns the value of this attribute.
+            pub fn value(&self) -> bool {
+                self.0
+            }
+        }
+
+        impl<'a> IntoAttribute<'a> for $name {
+            fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
+                ($key.into(), if self.0 { "true" } else { "false" }.into())
+            }
+        }
+
+        impl FromAttribute for $name {
+            const NAME: &'static str = $key;
+
+            fn from_attribute(value: &str) -> Option<Self> {
+                match value {
+                    "true" => Some(Self(true)),
+                    "false" => Some(Self(false)),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+/// Defines an ARIA attribute which takes a `Tristate`.
+macro_rules! aria_tristate {
+    ($(#[$meta:meta])* $name:ident, $key:literal) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone)]
+        pub struct $name(Tristate);
+
+        impl $name {
+            /// Create a new instance of this attribute with the specified value
+            /// (either a `Tristate` or a `bool`).
+            pub fn new<T>(value: T) -> Self
+            where
+                T: Into<Tristate>,
+            {
+                Self(value.into())
+            }
+
+            /// Returns the value of this attribute.
+            pub fn value(&self) -> Tristate {
+                self.0
+            }
+        }
+
+        impl<'a> IntoAttribute<'a> for $name {
+            fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
+                ($key.into(), self.0.as_str().into())
+            }
+        }
+
+        impl FromAttribute for $name {
+            const NAME: &'static str = $key;
+
+            fn from_attribute(value: &str) -> Option<Self> {
+                Tristate::parse(value).map(Self)
+            }
+        }
+    };
+}
+
+/// Defines an ARIA attribute which takes a (space-separated) list of ids.
+macro_rules! aria_id_refs {
+    ($(#[$meta:meta])* $name:ident, $key:literal) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone)]
+        pub struct $name<'a>(Vec<Cow<'a, str>>);
+
+        impl<'a> $name<'a> {
+            /// Create a new instance of this attribute, referring to the element
+            /// with the provided id.
+            pub fn new(id: &IdRef<'a>) -> Self {
+                Self(vec![id.into()])
+            }
+
+            /// Create a new instance of this attribute, referring to the element
+            /// with the specified id **without an [`IdRef`]** (so a misspelled
+            /// id is only found when the document is checked with
+            /// [`IdRegistry`](crate::ids::IdRegistry)).
+            pub fn new_unchecked<C>(id: C) -> Self
+            where
+                C: Into<Cow<'a, str>>,
+            {
+                Self(vec![id.into()])
+            }
+
+            /// Add a reference to another element.
+            pub fn id(mut self, id: &IdRef<'a>) -> Self {
+                self.0.push(id.into());
+                self
+            }
+
+            /// Add a reference to another element **without an [`IdRef`]**.
+            pub fn id_unchecked<C>(mut self, id: C) -> Self
+            where
+                C: Into<Cow<'a, str>>,
+            {
+                self.0.push(id.into());
+                self
+            }
+
+            /// Returns an iterator over the ids referred to by this attribute.
+            pub fn ids(&self) -> impl Iterator<Item = &str> {
+                self.0.iter().map(AsRef::as_ref)
+            }
+        }
+
+        impl<'a> IntoAttribute<'a> for $name<'a> {
+            fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
+                ($key.into(), self.0.join(" ").into())
+            }
+        }
+
+        impl FromAttribute for $name<'static> {
+            const NAME: &'static str = $key;
+
+            fn from_attribute(value: &str) -> Option<Self> {
+                let ids = value
+                    .split_whitespace()
+                    .map(|id| Cow::Owned(id.to_string()))
+                    .collect::<Vec<_>>();
+                if ids.is_empty() {
+                    None
+                } else {
+                    Some(Self(ids))
+                }
+            }
+        }
+    };
+}
+
+/// Defines an ARIA attribute which takes a single id.
+macro_rules! aria_id_ref {
+    ($(#[$meta:meta])* $name:ident, $key:literal) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone)]
+        pub struct $name<'a>(IdRef<'a>);
+
+        impl<'a> $name<'a> {
+            /// Create a new instance of this attribute, referring to the element
+            /// with the provided id.
+            pub fn new(id: &IdRef<'a>) -> Self {
+                Self(id.clone())
+            }
+
+            /// Create a new instance of this attribute, referring to the element
+            /// with the specified id **without an [`IdRef`]** (so a misspelled
+            /// id is only found when the document is checked with
+            /// [`IdRegistry`](crate::ids::IdRegistry)).
+            pub fn new_unchecked<C>(id: C) -> Self
+            where
+                C: Into<Cow<'a, str>>,
+            {
+                Self(IdRef::new(id.into()))
+            }
+
+            /// Returns the id of the element.
+            pub fn id(&self) -> &IdRef<'a> {
+                &self.0
+            }
+        }
+
+        impl<'a> IntoAttribute<'a> for $name<'a> {
+            fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
+                ($key.into(), self.0.into())
+            }
+        }
+
+        impl FromAttribute for $name<'static> {
+            const NAME: &'static str = $key;
+
+            fn from_attribute(value: &str) -> Option<Self> {
+                Some(Self(IdRef::new(value.to_string().into())))
+            }
+        }
+    };
+}
+
+/// Defines an ARIA attribute which takes one of a fixed set of tokens.
+macro_rules! aria_token {
+    ($(#[$meta:meta])* $name:ident, $key:literal, { $($variant:ident => $value:literal),* $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #[allow(missing_docs)]
+        pub enum $name {
+            $($variant),*
+        }
+
+        impl<'a> IntoAttribute<'a> for $name {
+            fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
+                (
+                    $key.into(),
+                    match self {
+                        $($name::$variant => $value),*
+                    }
+                    .into(),
+                )
+            }
+        }
+
+        impl FromAttribute for $name {
+            const NAME: &'static str = $key;
+
+            fn from_attribute(value: &str) -> Option<Self> {
+                match value {
+                    $($value => Some($name::$variant),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+aria_string!(
+    /// The `aria-label` attribute, which provides an accessible name for an
+    /// element which has no visible text (e.g. an icon button).
+    AriaLabel,
+    "aria-label"
+);
+
+aria_string!(
+    /// The `aria-roledescription` attribute.
+    AriaRoleDescription,
+    "aria-roledescription"
+);
+
+aria_string!(
+    /// The `aria-placeholder` attribute.
+    AriaPlaceholder,
+    "aria-placeholder"
+);
+
+aria_string!(
+    /// The `aria-keyshortcuts` attribute.
+    AriaKeyShortcuts,
+    "aria-keyshortcuts"
+);
+
+aria_string!(
+    /// The `aria-valuetext` attribute (a human-readable version of
+    /// `aria-valuenow`).
+    AriaValueText,
+    "aria-valuetext"
+);
+
+aria_id_refs!(
+    /// The `aria-labelledby` attribute, which names an element using the text
+    /// of the element(s) with the given ids.
+    AriaLabelledBy,
+    "aria-labelledby"
+);
+
+aria_id_refs!(
+    /// The `aria-describedby` attribute, which describes an element using the
+    /// text of the element(s) with the given ids.
+    AriaDescribedBy,
+    "aria-describedby"
+);
+
+aria_id_refs!(
+    /// The `aria-controls` attribute, which identifies the element(s) whose
+    /// contents are controlled by this one.
+    AriaControls,
+    "aria-controls"
+);
+
+aria_id_refs!(
+    /// The `aria-owns` attribute.
+    AriaOwns,
+    "aria-owns"
+);
+
+aria_id_refs!(
+    /// The `aria-flowto` attribute.
+    AriaFlowTo,
+    "aria-flowto"
+);
+
+aria_id_refs!(
+    /// The `aria-errormessage` attribute, which identifies the element that
+    /// contains the error message for this one.
+    AriaErrorMessage,
+    "aria-errormessage"
+);
+
+aria_id_ref!(
+    /// The `aria-activedescendant` attribute, which contains the id of the
+    /// currently active descendant of a composite widget.
+    AriaActiveDescendant,
+    "aria-activedescendant"
+);
+
+aria_bool!(
+    /// The `aria-hidden` attribute, which hides an element (and its children)
+    /// from assistive technologies.
+    AriaHidden,
+    "aria-hidden"
+);
+
+aria_bool!(
+    /// The `aria-expanded` attribute.
+    AriaExpanded,
+    "aria-expanded"
+);
+
+aria_bool!(
+    /// The `aria-disabled` attribute.
+    AriaDisabled,
+    "aria-disabled"
+);
+
+aria_bool!(
+    /// The `aria-required` attribute.
+    AriaRequired,
+    "aria-required"
+);
+
+aria_bool!(
+    /// The `aria-readonly` attribute.
+    AriaReadOnly,
+    "aria-readonly"
+);
+
+aria_bool!(
+    /// The `aria-selected` attribute.
+    AriaSelected,
+    "aria-selected"
+);
+
+aria_bool!(
+    /// The `aria-modal` attribute.
+    AriaModal,
+    "aria-modal"
+);
+
+aria_bool!(
+    /// The `aria-multiline` attribute.
+    AriaMultiline,
+    "aria-multiline"
+);
+
+aria_bool!(
+    /// The `aria-multiselectable` attribute.
+    AriaMultiSelectable,
+    "aria-multiselectable"
+);
+
+aria_bool!(
+    /// The `aria-atomic` attribute, which indicates whether assistive
+    /// technologies should present all of a live region when it changes, or
+    /// only the parts which changed.
+    AriaAtomic,
+    "aria-atomic"
+);
+
+aria_bool!(
+    /// The `aria-busy` attribute.
+    AriaBusy,
+    "aria-busy"
+);
+
+aria_tristate!(
+    /// The `aria-checked` attribute.
+    AriaChecked,
+    "aria-checked"
+);
+
+aria_tristate!(
+    /// The `aria-pressed` attribute.
+    AriaPressed,
+    "aria-pressed"
+);
+
+aria_token!(
+    /// The `aria-live` attribute, which marks an element as a live region.
+    AriaLive,
+    "aria-live",
+    {
+        Off => "off",
+        Polite => "polite",
+        Assertive => "assertive",
+    }
+);
+
+aria_token!(
+    /// The `aria-current` attribute, which marks the current item in a set
+    /// (for example the link to the current page in a navigation bar).
+    AriaCurrent,
+    "aria-current",
+    {
+        Page => "page",
+        Step => "step",
+        Location => "location",
+        Date => "date",
+        Time => "time",
+        True => "true",
+        False => "false",
+    }
+);
+
+aria_token!(
+    /// The `aria-haspopup` attribute.
+    AriaHasPopup,
+    "aria-haspopup",
+    {
+        True => "true",
+        False => "false",
+        Menu => "menu",
+        Listbox => "listbox",
+        Tree => "tree",
+        Grid => "grid",
+        Dialog => "dialog",
+    }
+);
+
+aria_token!(
+    /// The `aria-invalid` attribute.
+    AriaInvalid,
+    "aria-invalid",
+    {
+        True => "true",
+        False => "false",
+        Grammar => "grammar",
+        Spelling => "spelling",
+    }
+);
+
+aria_token!(
+    /// The `aria-sort` attribute.
+    AriaSort,
+    "aria-sort",
+    {
+        Ascending => "ascending",
+        Descending => "descending",
+        None => "none",
+        Other => "other",
+    }
+);
+
+aria_token!(
+    /// The `aria-orientation` attribute.
+    AriaOrientation,
+    "aria-orientation",
+    {
+        Horizontal => "horizontal",
+        Vertical => "vertical",
+    }
+);
+
+aria_token!(
+    /// The `aria-autocomplete` attribute.
+    AriaAutocomplete,
+    "aria-autocomplete",
+    {
+        Inline => "inline",
+        List => "list",
+        Both => "both",
+        None => "none",
+    }
+);
+
+/// The `aria-level` attribute (for example, the level of a heading or of an
+/// item in a tree). Levels start at 1.
+///
+/// ```
+/// # use malvolio::prelude::*;
+/// use std::num::NonZeroU32;
+///
+/// let level = NonZeroU32::new(2).unwrap();
+/// let div = Div::new().attribute(AriaLevel::new(level));
+/// assert_eq!(div.to_string(), "<div aria-level=\"2\"></div>");
+/// ```
+#[derive(Debug, Clone)]
+pub struct AriaLevel(NonZeroU32);
+
+impl AriaLevel {
+    /// Create a new instance of this attribute with the specified level.
+    pub fn new(level: NonZeroU32) -> Self {
+        Self(level)
+    }
+
+    /// Returns the value of this attribute.
+    pub fn value(&self) -> NonZeroU32 {
+        self.0
+    }
+}
+
+impl<'a> IntoAttribute<'a> for AriaLevel {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
+        ("aria-level".into(), self.0.to_string().into())
+    }
+}
+
+impl FromAttribute for AriaLevel {
+    const NAME: &'static str = "aria-level";
+
+    fn from_attribute(value: &str) -> Option<Self> {
+        value.trim().parse().ok().map(Self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::num::NonZeroU32;
+
+    use super::{AriaCurrent, AriaExpanded, AriaSort, Tristate, ROLES};
+    use crate::{attributes::Attributes, prelude::*};
+
+    #[test]
+    fn test_aria_attributes() {
+        let document = Div::new()
+            .attribute(Role::Navigation)
+            .attribute(AriaLabel::new("Main menu"))
+            .attribute(AriaLabelledBy::new_unchecked("title").id_unchecked("subtitle"))
+            .attribute(AriaHidden::new(false))
+            .attribute(AriaChecked::new(Tristate::Mixed))
+            .attribute(AriaPressed::new(true))
+            .attribute(AriaLive::Polite)
+            .attribute(AriaCurrent::Page)
+            .attribute(AriaLevel::new(NonZeroU32::new(2).unwrap()))
+            .to_string();
+        let document = scraper::Html::parse_document(&document);
+        let div = scraper::Selector::parse("div").unwrap();
+        let div = document.select(&div).next().unwrap().value();
+        assert_eq!(div.attr("role"), Some("navigation"));
+        assert_eq!(div.attr("aria-label"), Some("Main menu"));
+        assert_eq!(div.attr("aria-labelledby"), Some("title subtitle"));
+        assert_eq!(div.attr("aria-hidden"), Some("false"));
+        assert_eq!(div.attr("aria-checked"), Some("mixed"));
+        assert_eq!(div.attr("aria-pressed"), Some("true"));
+        assert_eq!(div.attr("aria-live"), Some("polite"));
+        assert_eq!(div.attr("aria-current"), Some("page"));
+        assert_eq!(div.attr("aria-level"), Some("2"));
+    }
+
+    #[test]
+    fn test_roles() {
+        for role in ROLES {
+            let div = Div::new().attribute(*role);
+            assert_eq!(div.read::<Role>(), Some(*role));
+        }
+        let mut names = ROLES.iter().map(Role::as_str).collect::<Vec<_>>();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), ROLES.len());
+
+        // the first role which is recognised is used
+        let fallback = Div::new().raw_attribute("role", "switch-toggle  button link");
+        assert_eq!(fallback.read::<Role>(), Some(Role::Button));
+        assert_eq!(
+            Div::new().raw_attribute("role", "toggle").read::<Role>(),
+            None
+        );
+        assert_eq!(Div::new().raw_attribute("role", " ").read::<Role>(), None);
+    }
+
+    #[test]
+    fn test_strings() {
+        let div = Div::new().attribute(AriaLabel::new("Close \"dialog\""));
+        assert_eq!(
+            div.read::<AriaLabel>().unwrap().as_str(),
+            "Close \"dialog\""
+        );
+        assert!(div
+            .to_string()
+            .contains("aria-label=\"Close &quot;dialog&quot;\""));
+    }
+
+    #[test]
+    fn test_bools() {
+        let div = Div::new().attribute(AriaExpanded::new(true));
+        assert_eq!(div.get_attribute("aria-expanded"), Some("true"));
+        assert!(div.read::<AriaExpanded>().unwrap().value());
+        for invalid in ["", "yes", "True"] {
+            let div = Div::new().raw_attribute("aria-expanded", invalid);
+            assert!(div.read::<AriaExpanded>().is_none());
+        }
+    }
+
+    #[test]
+    fn test_tristates() {
+        for (value, expected) in [
+            (Tristate::True, "true"),
+            (Tristate::False, "false"),
+            (Tristate::Mixed, "mixed"),
+        ] {
+            let div = Div::new().attribute(AriaChecked::new(value));
+            assert_eq!(div.get_attribute("aria-checked"), Some(expected));
+            assert_eq!(div.read::<AriaChecked>().unwrap().value(), value);
+        }
+        assert_eq!(AriaPressed::new(false).value(), Tristate::False);
+        let div = Div::new().raw_attribute("aria-checked", "partly");
+        assert!(div.read::<AriaChecked>().is_none());
+    }
+
+    #[test]
+    fn test_id_refs() {
+        let (_, hint) = P::new("Hint").with_id("hint");
+        let attribute = AriaDescribedBy::new_unchecked("label").id(&hint);
+        assert_eq!(attribute.ids().collect::<Vec<_>>(), ["label", "hint"]);
+        let div = Div::new().attribute(attribute);
+        assert_eq!(div.get_attribute("aria-describedby"), Some("label hint"));
+
+        let div = Div::new().raw_attribute("aria-controls", " a \t b ");
+        assert_eq!(
+            div.read::<AriaControls>()
+                .unwrap()
+                .ids()
+                .collect::<Vec<_>>(),
+            ["a", "b"]
+        );
+        let div = Div::new().raw_attribute("aria-controls", "  ");
+        assert!(div.read::<AriaControls>().is_none());
+
+        let (_, option) = P::new("Option").with_id("option");
+        let div = Div::new().attribute(AriaActiveDescendant::new(&option));
+        assert_eq!(div.get_attribute("aria-activedescendant"), Some("option"));
+        assert_eq!(div.read::<AriaActiveDescendant>().unwrap().id(), &option);
+        let div = Div::new().attribute(AriaActiveDescendant::new_unchecked("other"));
+        assert_eq!(div.get_attribute("aria-activedescendant"), Some("other"));
+    }
+
+    #[test]
+    fn test_tokens() {
+        let div = Div::new()
+            .attribute(AriaCurrent::Step)
+            .attribute(AriaSort::None);
+        assert_eq!(div.read::<AriaCurrent>(), Some(AriaCurrent::Step));
+        assert_eq!(div.get_attribute("aria-sort"), Some("none"));
+        assert_eq!(div.read::<AriaSort>(), Some(AriaSort::None));
+        let div = Div::new().raw_attribute("aria-current", "PAGE");
+        assert_eq!(div.read::<AriaCurrent>(), None);
+    }
+
+    #[test]
+    fn test_level() {
+        let level = NonZeroU32::new(3).unwrap();
+        let div = Div::new().attribute(AriaLevel::new(level));
+        assert_eq!(div.read::<AriaLevel>().unwrap().value(), level);
+        assert_eq!(
+            Div::new()
+                .raw_attribute("aria-level", " 4 ")
+                .read::<AriaLevel>()
+                .unwrap()
+                .value()
+                .get(),
+            4
+        );
+        for invalid in ["", "0", "-1", "two"] {
+            let div = Div::new().raw_attribute("aria-level", invalid);
+            assert!(div.read::<AriaLevel>().is_none());
+        }
+    }
+}
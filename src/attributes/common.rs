@@ -1,43 +1,64 @@
-use std::{borrow::Cow, collections::HashSet};
+use std::borrow::Cow;
 
-use super::IntoAttribute;
+use super::{FromAttribute, IntoAttribute};
+use crate::{from_attribute_via_new, url::SafeUrl};
 
 #[derive(Debug, Default, Clone)]
 #[must_use]
-
 /// A builder for constructing values for the `class` attribute.
-pub struct Class(HashSet<Cow<'static, str>>);
+///
+/// When this attribute is applied to a tag which already has some classes, the
+/// new classes are added to the existing ones (use `replace_attribute` if you
+/// want to overwrite them instead).
+pub struct Class<'a>(Vec<Cow<'a, str>>);
 
-impl From<Cow<'static, str>> for Class {
-    fn from(str: Cow<'static, str>) -> Self {
-        let mut set = HashSet::new();
-        set.insert(str);
-        Self(set)
+impl<'a> From<Cow<'a, str>> for Class<'a> {
+    fn from(str: Cow<'a, str>) -> Self {
+        Self(vec![str])
     }
 }
 
-impl From<&'static str> for Class {
-    fn from(str: &'static str) -> Self {
-        let mut set = HashSet::new();
-        set.insert(str.into());
-        Self(set)
+impl<'a> From<&'a str> for Class<'a> {
+    fn from(str: &'a str) -> Self {
+        Self(vec![str.into()])
     }
 }
 
-impl Class {
+impl<'a> Class<'a> {
     /// Add a new class to this `Class` attribute.
-    pub fn class(mut self, class: Cow<'static, str>) -> Self {
-        self.0.insert(class);
+    pub fn class(mut self, class: Cow<'a, str>) -> Self {
+        if !self.0.contains(&class) {
+            self.0.push(class);
+        }
         self
     }
+
+    /// Returns an iterator over the classes in this attribute.
+    pub fn classes(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(AsRef::as_ref)
+    }
 }
 
-impl IntoAttribute for Class {
-    fn into_attribute(self) -> (Cow<'static, str>, Cow<'static, str>) {
-        (
-            "class".into(),
-            self.0.into_iter().collect::<Vec<_>>().join(" ").into(),
-        )
+impl<'a> IntoAttribute<'a> for Class<'a> {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
+        ("class".into(), self.0.join(" ").into())
+    }
+}
+
+impl FromAttribute for Class<'static> {
+    const NAME: &'static str = "class";
+
+    fn from_attribute(value: &str) -> Option<Self> {
+        let class = value
+            .split_whitespace()
+            .fold(Class::default(), |class, name| {
+                class.class(name.to_string().into())
+            });
+        if class.0.is_empty() {
+            None
+        } else {
+            Some(class)
+        }
     }
 }
 
@@ -45,41 +66,668 @@ impl IntoAttribute for Class {
 
 /// The "id" attribute.
 #[must_use]
-pub struct Id(Cow<'static, str>);
+pub struct Id<'a>(Cow<'a, str>);
 
-impl Id {
+impl<'a> Id<'a> {
     /// Create a new instance of this attribute with the specified value.
     pub fn new<C>(c: C) -> Self
     where
-        C: Into<Cow<'static, str>>,
+        C: Into<Cow<'a, str>>,
     {
         Self(c.into())
     }
+
+    /// Returns the value of this attribute.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns an [`IdRef`] which refers to the tag with this id.
+    pub fn id_ref(&self) -> IdRef<'a> {
+        IdRef(self.0.clone())
+    }
 }
 
-impl IntoAttribute for Id {
-    fn into_attribute(self) -> (Cow<'static, str>, Cow<'static, str>) {
+impl<'a> IntoAttribute<'a> for Id<'a> {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
         ("id".into(), self.0)
     }
 }
 
-#[derive(Debug, Default, Clone)]
+from_attribute_via_new!(Id, "id");
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// A reference to the id of a tag, which can be used in the attributes that
+/// refer to another tag (such as `For`, `List` and `AriaControls`).
+///
+/// An `IdRef` is returned when an id is set with `with_id` (which every tag
+/// has), so that the id is only written in one place:
+///
+/// ```
+/// # use malvolio::prelude::*;
+/// let (input, email) = Input::new().attribute(Type::Email).with_id("email");
+/// let form = Form::new()
+///     .child(Label::new("Email").attribute(For::new(&email)))
+///     .child(input)
+///     .child(A::new().href(email.url()).text("Skip to the form"));
+/// assert_eq!(
+///     form.to_string(),
+///     "<form><Label for=\"email\">Email</Label><input id=\"email\" type=\"email\"/>\
+///      <a href=\"#email\">Skip to the form</a></form>"
+/// );
+/// ```
+pub struct IdRef<'a>(Cow<'a, str>);
+
+impl<'a> IdRef<'a> {
+    pub(crate) fn new(id: Cow<'a, str>) -> Self {
+        Self(id)
+    }
+
+    /// Returns the id.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns a URL which links to the tag (e.g. `#email`).
+    pub fn url(&self) -> SafeUrl<'static> {
+        SafeUrl::sanitize(format!("#{}", self.0))
+    }
+}
+
+impl<'a> From<IdRef<'a>> for Cow<'a, str> {
+    fn from(id: IdRef<'a>) -> Self {
+        id.0
+    }
+}
+
+impl<'a> From<&IdRef<'a>> for Cow<'a, str> {
+    fn from(id: &IdRef<'a>) -> Self {
+        id.0.clone()
+    }
+}
+
+/// The "style" attribute (see the `css` module for more information).
+pub use crate::css::Style;
+
+/// The "title" attribute, which contains advisory information about the
+/// element (most browsers display this as a tooltip). This is called
+/// `TitleAttr` to disambiguate it from the `<title>` tag.
+///
+/// See the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/title)
+/// for further information.
+#[derive(Debug, Clone)]
+pub struct TitleAttr<'a>(Cow<'a, str>);
+
+impl<'a> TitleAttr<'a> {
+    /// Create a new instance of this attribute with the specified value.
+    pub fn new<C>(c: C) -> Self
+    where
+        C: Into<Cow<'a, str>>,
+    {
+        Self(c.into())
+    }
+
+    /// Returns the value of this attribute.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<'a> IntoAttribute<'a> for TitleAttr<'a> {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
+        ("title".into(), self.0)
+    }
+}
+
+from_attribute_via_new!(TitleAttr, "title");
+
+/// The "lang" attribute, which specifies the language of the element (for
+/// example `en` or `fr-CA`).
+///
+/// See the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/lang)
+/// for further information.
+#[derive(Debug, Clone)]
+pub struct Lang<'a>(Cow<'a, str>);
+
+impl<'a> Lang<'a> {
+    /// Create a new instance of this attribute with the specified language
+    /// tag.
+    pub fn new<C>(c: C) -> Self
+    where
+        C: Into<Cow<'a, str>>,
+    {
+        Self(c.into())
+    }
+
+    /// Returns the value of this attribute.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<'a> IntoAttribute<'a> for Lang<'a> {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
+        ("lang".into(), self.0)
+    }
+}
+
+from_attribute_via_new!(Lang, "lang");
+
+/// The "dir" attribute, which specifies the direction of the element's text.
+///
+/// See the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/dir)
+/// for further information.
+#[derive(Debug, Clone)]
+#[allow(missing_docs)]
+pub enum Dir {
+    Ltr,
+    Rtl,
+    Auto,
+}
+
+impl<'a> IntoAttribute<'a> for Dir {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
+        (
+            "dir".into(),
+            match self {
+                Dir::Ltr => "ltr",
+                Dir::Rtl => "rtl",
+                Dir::Auto => "auto",
+            }
+            .into(),
+        )
+    }
+}
+
+impl FromAttribute for Dir {
+    const NAME: &'static str = "dir";
+
+    fn from_attribute(value: &str) -> Option<Self> {
+        match value {
+            "ltr" => Some(Dir::Ltr),
+            "rtl" => Some(Dir::Rtl),
+            "auto" => Some(Dir::Auto),
+            _ => None,
+        }
+    }
+}
+
+/// The "hidden" attribute.
+///
+/// See the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/hidden)
+/// for further information.
+#[derive(Debug, Clone)]
+pub enum Hidden {
+    /// The element is not rendered.
+    Hidden,
+    /// The element is hidden, but its contents can be found (and revealed) by
+    /// the browser's "find in page" feature.
+    UntilFound,
+}
+
+impl<'a> IntoAttribute<'a> for Hidden {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
+        (
+            "hidden".into(),
+            match self {
+                Hidden::Hidden => "",
+                Hidden::UntilFound => "until-found",
+            }
+            .into(),
+        )
+    }
+}
+
+impl FromAttribute for Hidden {
+    const NAME: &'static str = "hidden";
+
+    fn from_attribute(value: &str) -> Option<Self> {
+        match value {
+            "until-found" => Some(Hidden::UntilFound),
+            _ => Some(Hidden::Hidden),
+        }
+    }
+}
+
+/// The "tabindex" attribute.
+///
+/// See the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/tabindex)
+/// for further information.
+#[derive(Debug, Clone)]
+pub struct TabIndex(i32);
+
+impl TabIndex {
+    /// Create a new instance of this attribute with the specified value.
+    pub fn new(index: i32) -> Self {
+        Self(index)
+    }
+
+    /// Returns the value of this attribute.
+    pub fn value(&self) -> i32 {
+        self.0
+    }
+}
+
+impl<'a> IntoAttribute<'a> for TabIndex {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
+        ("tabindex".into(), self.0.to_string().into())
+    }
+}
+
+impl FromAttribute for TabIndex {
+    const NAME: &'static str = "tabindex";
+
+    fn from_attribute(value: &str) -> Option<Self> {
+        value.trim().parse().ok().map(Self)
+    }
+}
+
+/// The "accesskey" attribute, which provides a keyboard shortcut for the
+/// element.
+///
+/// See the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/accesskey)
+/// for further information.
+#[derive(Debug, Clone)]
+pub struct AccessKey(char);
+
+impl AccessKey {
+    /// Create a new instance of this attribute with the specified key.
+    pub fn new(key: char) -> Self {
+        Self(key)
+    }
+
+    /// Returns the value of this attribute.
+    pub fn value(&self) -> char {
+        self.0
+    }
+}
+
+impl<'a> IntoAttribute<'a> for AccessKey {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
+        ("accesskey".into(), self.0.to_string().into())
+    }
+}
+
+impl FromAttribute for AccessKey {
+    const NAME: &'static str = "accesskey";
 
-/// The "style" attribute.
-pub struct Style(Cow<'static, str>);
+    fn from_attribute(value: &str) -> Option<Self> {
+        let mut chars = value.chars();
+        match (chars.next(), chars.next()) {
+            (Some(key), None) => Some(Self(key)),
+            _ => None,
+        }
+    }
+}
+
+/// The "contenteditable" attribute.
+///
+/// See the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/contenteditable)
+/// for further information.
+#[derive(Debug, Clone)]
+#[allow(missing_docs)]
+pub enum ContentEditable {
+    True,
+    False,
+    PlaintextOnly,
+}
+
+impl<'a> IntoAttribute<'a> for ContentEditable {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
+        (
+            "contenteditable".into(),
+            match self {
+                ContentEditable::True => "true",
+                ContentEditable::False => "false",
+                ContentEditable::PlaintextOnly => "plaintext-only",
+            }
+            .into(),
+        )
+    }
+}
+
+impl FromAttribute for ContentEditable {
+    const NAME: &'static str = "contenteditable";
+
+    fn from_attribute(value: &str) -> Option<Self> {
+        match value {
+            "true" => Some(ContentEditable::True),
+            "" => Some(ContentEditable::True),
+            "false" => Some(ContentEditable::False),
+            "plaintext-only" => Some(ContentEditable::PlaintextOnly),
+            _ => None,
+        }
+    }
+}
+
+/// The "draggable" attribute.
+///
+/// See the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/draggable)
+/// for further information.
+#[derive(Debug, Clone)]
+pub struct Draggable(bool);
+
+impl Draggable {
+    /// Create a new instance of this attribute with the specified value.
+    pub fn new(draggable: bool) -> Self {
+        Self(draggable)
+    }
+
+    /// Returns the value of this attribute.
+    pub fn value(&self) -> bool {
+        self.0
+    }
+}
+
+impl<'a> IntoAttribute<'a> for Draggable {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
+        (
+            "draggable".into(),
+            if self.0 { "true" } else { "false" }.into(),
+        )
+    }
+}
+
+impl FromAttribute for Draggable {
+    const NAME: &'static str = "draggable";
+
+    fn from_attribute(value: &str) -> Option<Self> {
+        match value {
+            "true" => Some(Self(true)),
+            "false" => Some(Self(false)),
+            _ => None,
+        }
+    }
+}
+
+/// The "spellcheck" attribute.
+///
+/// See the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/spellcheck)
+/// for further information.
+#[derive(Debug, Clone)]
+pub struct SpellCheck(bool);
+
+impl SpellCheck {
+    /// Create a new instance of this attribute with the specified value.
+    pub fn new(spellcheck: bool) -> Self {
+        Self(spellcheck)
+    }
+
+    /// Returns the value of this attribute.
+    pub fn value(&self) -> bool {
+        self.0
+    }
+}
+
+impl<'a> IntoAttribute<'a> for SpellCheck {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
+        (
+            "spellcheck".into(),
+            if self.0 { "true" } else { "false" }.into(),
+        )
+    }
+}
+
+impl FromAttribute for SpellCheck {
+    const NAME: &'static str = "spellcheck";
+
+    fn from_attribute(value: &str) -> Option<Self> {
+        match value {
+            "true" => Some(Self(true)),
+            "false" => Some(Self(false)),
+            _ => None,
+        }
+    }
+}
+
+/// The "translate" attribute, which specifies whether the contents of the
+/// element should be translated when the page is localised.
+///
+/// See the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/translate)
+/// for further information.
+#[derive(Debug, Clone)]
+pub struct Translate(bool);
+
+impl Translate {
+    /// Create a new instance of this attribute with the specified value.
+    pub fn new(translate: bool) -> Self {
+        Self(translate)
+    }
+
+    /// Returns the value of this attribute.
+    pub fn value(&self) -> bool {
+        self.0
+    }
+}
 
-impl Style {
+impl<'a> IntoAttribute<'a> for Translate {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
+        ("translate".into(), if self.0 { "yes" } else { "no" }.into())
+    }
+}
+
+impl FromAttribute for Translate {
+    const NAME: &'static str = "translate";
+
+    fn from_attribute(value: &str) -> Option<Self> {
+        match value {
+            "yes" => Some(Self(true)),
+            "no" => Some(Self(false)),
+            _ => None,
+        }
+    }
+}
+
+/// The "inert" attribute. Inert elements (and their children) can not be
+/// focused or interacted with.
+///
+/// See the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/inert)
+/// for further information.
+#[derive(Debug, Clone)]
+pub struct Inert;
+
+impl<'a> IntoAttribute<'a> for Inert {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
+        ("inert".into(), "".into())
+    }
+}
+
+impl FromAttribute for Inert {
+    const NAME: &'static str = "inert";
+
+    fn from_attribute(_: &str) -> Option<Self> {
+        Some(Inert)
+    }
+}
+
+/// The "popover" attribute.
+///
+/// See the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/popover)
+/// for further information.
+#[derive(Debug, Clone)]
+#[allow(missing_docs)]
+pub enum Popover {
+    Auto,
+    Manual,
+}
+
+impl<'a> IntoAttribute<'a> for Popover {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
+        (
+            "popover".into(),
+            match self {
+                Popover::Auto => "auto",
+                Popover::Manual => "manual",
+            }
+            .into(),
+        )
+    }
+}
+
+impl FromAttribute for Popover {
+    const NAME: &'static str = "popover";
+
+    fn from_attribute(value: &str) -> Option<Self> {
+        match value {
+            "auto" => Some(Popover::Auto),
+            "" => Some(Popover::Auto),
+            "manual" => Some(Popover::Manual),
+            _ => None,
+        }
+    }
+}
+
+/// The "autofocus" attribute.
+///
+/// See the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/autofocus)
+/// for further information.
+#[derive(Debug, Clone)]
+pub struct Autofocus;
+
+impl<'a> IntoAttribute<'a> for Autofocus {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
+        ("autofocus".into(), "".into())
+    }
+}
+
+impl FromAttribute for Autofocus {
+    const NAME: &'static str = "autofocus";
+
+    fn from_attribute(_: &str) -> Option<Self> {
+        Some(Autofocus)
+    }
+}
+
+/// The "nonce" attribute, used by Content Security Policy to allow specific
+/// inline elements.
+///
+/// See the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/nonce)
+/// for further information.
+#[derive(Debug, Clone)]
+pub struct Nonce<'a>(Cow<'a, str>);
+
+impl<'a> Nonce<'a> {
     /// Create a new instance of this attribute with the specified value.
-    pub fn new<C>(string: C) -> Self
+    pub fn new<C>(c: C) -> Self
     where
-        C: Into<Cow<'static, str>>,
+        C: Into<Cow<'a, str>>,
     {
-        Self(string.into())
+        Self(c.into())
+    }
+
+    /// Returns the value of this attribute.
+    pub fn as_str(&self) -> &str {
+        &self.0
     }
 }
 
-impl IntoAttribute for Style {
-    fn into_attribute(self) -> (Cow<'static, str>, Cow<'static, str>) {
-        ("style".into(), self.0)
+impl<'a> IntoAttribute<'a> for Nonce<'a> {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
+        ("nonce".into(), self.0)
+    }
+}
+
+from_attribute_via_new!(Nonce, "nonce");
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_class_merging() {
+        let div = Div::new()
+            .attribute(Class::from("a"))
+            .attribute(Class::from("b").class("a".into()))
+            .add_class("c");
+        assert_eq!(div.read_attribute("class").unwrap(), "a b c");
+        let div = div
+            .remove_class("b")
+            .toggle_class("d", true)
+            .toggle_class("a", false);
+        assert_eq!(div.read_attribute("class").unwrap(), "c d");
+        let div = div.replace_attribute(Class::from("e"));
+        assert_eq!(div.read_attribute("class").unwrap(), "e");
+        let div = div.remove_class("e");
+        assert_eq!(div.read_attribute("class"), None);
+
+        let h1 = H1::new("Heading")
+            .attribute(Style::new("color: red;"))
+            .replace_attribute(Style::new("margin: 0;"))
+            .add_class("title");
+        assert_eq!(h1.read_attribute("style").unwrap(), "margin: 0;");
+        assert_eq!(h1.read_attribute("class").unwrap(), "title");
+    }
+
+    #[test]
+    fn test_global_attributes() {
+        let document = Html::new()
+            .attribute(Lang::new("en"))
+            .head(Head::new().child(StyleTag::new("").attribute(Nonce::new("abc"))))
+            .body(
+                Body::new()
+                    .attribute(Class::from("page"))
+                    .child(
+                        Img::new()
+                            .attribute(Src::new("cat.jpeg"))
+                            .attribute(Id::new("cat"))
+                            .attribute(Hidden::UntilFound),
+                    )
+                    .child(
+                        A::new()
+                            .attribute(Class::from("link"))
+                            .attribute(TitleAttr::new("A link"))
+                            .attribute(TabIndex::new(-1)),
+                    )
+                    .child(br().attribute(Dir::Rtl))
+                    .child(Select::new().attribute(Style::new("color: red;"))),
+            )
+            .to_string();
+        let document = scraper::Html::parse_document(&document);
+        let select = |selector| {
+            let selector = scraper::Selector::parse(selector).unwrap();
+            document.select(&selector).next().unwrap().value().clone()
+        };
+        assert_eq!(select("html").attr("lang"), Some("en"));
+        assert_eq!(select("style").attr("nonce"), Some("abc"));
+        assert_eq!(select("body").attr("class"), Some("page"));
+        let img = select("img");
+        assert_eq!(img.attr("id"), Some("cat"));
+        assert_eq!(img.attr("hidden"), Some("until-found"));
+        let a = select("a");
+        assert_eq!(a.attr("class"), Some("link"));
+        assert_eq!(a.attr("title"), Some("A link"));
+        assert_eq!(a.attr("tabindex"), Some("-1"));
+        assert_eq!(select("br").attr("dir"), Some("rtl"));
+        assert_eq!(select("select").attr("style"), Some("color: red;"));
+    }
+
+    #[test]
+    fn test_id_ref() {
+        use crate::{attributes::Attributes, ids::IdRegistry};
+
+        let (menu, menu_id) = Div::new().with_id("menu");
+        let (suggestions, suggestions_id) = Div::new().with_id("suggestions");
+        let (input, input_id) = Input::new()
+            .attribute(List::new(&suggestions_id))
+            .attribute(AriaControls::new(&menu_id))
+            .with_id(String::from("search"));
+        let body = Body::new()
+            .child(Label::new("Search").attribute(For::new(&input_id)))
+            .child(input)
+            .child(menu)
+            .child(suggestions);
+        assert!(IdRegistry::from_body(&body).problems().is_empty());
+
+        let label = body.child_nodes()[0].as_label().unwrap();
+        assert_eq!(label.read::<For>().unwrap().id(), &input_id);
+        let input = body.child_nodes()[1].as_input().unwrap();
+        assert_eq!(input.get_attribute("list"), Some("suggestions"));
+        assert_eq!(input.get_attribute("aria-controls"), Some("menu"));
+        assert_eq!(Id::new("a b").id_ref().url().as_str(), "#a%20b");
+
+        let label = Label::new("Other").attribute(For::new_unchecked("other"));
+        assert_eq!(label.get_attribute("for"), Some("other"));
+        let input = Input::new().attribute(List::new_unchecked("other"));
+        assert_eq!(input.get_attribute("list"), Some("other"));
     }
 }
@@ -0,0 +1,313 @@
+/*
+This source code file is distributed subject to the terms of the Mozilla Public License v2.0.
+A copy of this license can be found in the `licenses` directory at the root of this project.
+*/
+use std::{borrow::Cow, collections::BTreeMap, fmt::Display};
+
+use super::IntoAttributes;
+
+/// A set of `data-*` attributes, which can be attached to any tag.
+///
+/// Keys are converted from the conventions commonly used in Rust (and
+/// Javascript) into kebab-case, so `user_id` and `userId` both become
+/// `data-user-id` (which Javascript can then read as `element.dataset.userId`).
+/// A run of capital letters is treated as a single word, so `userID` also
+/// becomes `data-user-id` and `HTMLParser` becomes `data-html-parser` (note
+/// that these are read back in Javascript as `dataset.userId` and
+/// `dataset.htmlParser`).
+///
+/// ```
+/// # use malvolio::prelude::*;
+/// Div::new().attribute(Data::new().entry("user_id", "42").entry("role", "admin"));
+/// ```
+///
+/// See the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/data-*)
+/// for further information.
+#[derive(Debug, Default, Clone)]
+#[must_use]
+pub struct Data<'a>(BTreeMap<Cow<'a, str>, Cow<'a, str>>);
+
+impl<'a> Data<'a> {
+    /// Create a new (empty) set of `data-*` attributes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a new `data-*` attribute. The key should be provided without the
+    /// `data-` prefix.
+    ///
+    /// # Panics
+    ///
+    /// If the key is not a valid name for a data attribute. If you are
+    /// constructing the key at runtime, you probably want to use
+    /// [`Data::try_entry`] instead.
+    pub fn entry<K, V>(self, key: K, value: V) -> Self
+    where
+        K: AsRef<str>,
+        V: Into<Cow<'a, str>>,
+    {
+        self.try_entry(key, value)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Add a new `data-*` attribute, returning an error if the key is not a
+    /// valid name for a data attribute.
+    pub fn try_entry<K, V>(mut self, key: K, value: V) -> Result<Self, DataError>
+    where
+        K: AsRef<str>,
+        V: Into<Cow<'a, str>>,
+    {
+        let name = attribute_name(key.as_ref())?;
+        self.0.insert(name.into(), value.into());
+        Ok(self)
+    }
+
+    /// Construct a set of `data-*` attributes from any iterator of key-value
+    /// pairs (for example a `HashMap` or a `BTreeMap`).
+    ///
+    /// ```
+    /// # use malvolio::prelude::*;
+    /// # use std::collections::HashMap;
+    /// let mut map = HashMap::new();
+    /// map.insert("postId", "1");
+    /// Data::from_map(map).unwrap();
+    /// ```
+    pub fn from_map<I, K, V>(map: I) -> Result<Self, DataError>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: Into<Cow<'a, str>>,
+    {
+        map.into_iter()
+            .try_fold(Self::new(), |data, (key, value)| data.try_entry(key, value))
+    }
+
+    /// Construct a set of `data-*` attributes from a struct (or map) which
+    /// implements `serde::Serialize` (this requires the `serde` feature). Each field becomes one attribute.
+    ///
+    /// Strings are inserted as-is, numbers and booleans are formatted as they
+    /// would be in JSON, fields which are `None` are skipped and nested
+    /// structures (e.g. lists) are encoded as JSON (so they can be read using
+    /// `JSON.parse`).
+    ///
+    /// ```
+    /// # use malvolio::prelude::*;
+    /// #[derive(serde::Serialize)]
+    /// struct Post {
+    ///     post_id: u32,
+    ///     tags: Vec<&'static str>,
+    /// }
+    ///
+    /// Div::new().attribute(
+    ///     Data::from_serialize(&Post {
+    ///         post_id: 1,
+    ///         tags: vec!["rust", "html"],
+    ///     })
+    ///     .unwrap(),
+    /// );
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn from_serialize<T>(value: &T) -> Result<Self, DataError>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        let fields = match serde_json::to_value(value).map_err(DataError::Serialize)? {
+            serde_json::Value::Object(fields) => fields,
+            _ => return Err(DataError::NotAStruct),
+        };
+        fields
+            .into_iter()
+            .try_fold(Self::new(), |data, (key, value)| match value {
+                serde_json::Value::Null => Ok(data),
+                serde_json::Value::String(string) => data.try_entry(key, string),
+                other => data.try_entry(key, other.to_string()),
+            })
+    }
+}
+
+impl<'a> IntoAttributes<'a> for Data<'a> {
+    fn into_attributes(self) -> Vec<(Cow<'a, str>, Cow<'a, str>)> {
+        self.0.into_iter().collect()
+    }
+}
+
+/// Converts the provided key into the name of a `data-*` attribute (including
+/// the `data-` prefix), checking that the resulting name is valid.
+fn attribute_name(key: &str) -> Result<String, DataError> {
+    if key.is_empty() {
+        return Err(DataError::InvalidName(key.to_string()));
+    }
+    let mut name = String::with_capacity(key.len() + 5);
+    name.push_str("data-");
+    let chars = key.chars().collect::<Vec<_>>();
+    for (index, &c) in chars.iter().enumerate() {
+        match c {
+            '_' => name.push('-'),
+            c if c.is_ascii_uppercase() => {
+                // a run of capitals (e.g. `ID` or `HTML`) is one word, but the
+                // last capital before a lowercase letter starts a new word
+                let previous = index.checked_sub(1).map(|index| chars[index]);
+                let next = chars.get(index + 1);
+                let starts_word = match previous {
+                    Some(previous) if previous.is_ascii_uppercase() => {
+                        next.is_some_and(char::is_ascii_lowercase)
+                    }
+                    Some(previous) => previous.is_ascii_alphanumeric(),
+                    None => false,
+                };
+                if starts_word {
+                    name.push('-');
+                }
+                name.push(c.to_ascii_lowercase());
+            }
+            c if c.is_ascii_lowercase()
+                || c.is_ascii_digit()
+                || c == '-'
+                || c == '.'
+                || (!c.is_ascii() && c.is_alphanumeric()) =>
+            {
+                name.push(c)
+            }
+            _ => return Err(DataError::InvalidName(key.to_string())),
+        }
+    }
+    Ok(name)
+}
+
+/// An error which can occur when constructing a [`Data`] attribute.
+#[derive(Debug)]
+pub enum DataError {
+    /// The provided key can not be used as the name of a `data-*` attribute.
+    InvalidName(String),
+    /// The value passed to `Data::from_serialize` did not serialize to a
+    /// struct or map.
+    #[cfg(feature = "serde")]
+    NotAStruct,
+    /// The value passed to `Data::from_serialize` could not be serialized.
+    #[cfg(feature = "serde")]
+    Serialize(serde_json::Error),
+}
+
+impl Display for DataError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataError::InvalidName(name) => {
+                write!(f, "`{}` is not a valid name for a data attribute", name)
+            }
+            #[cfg(feature = "serde")]
+            DataError::NotAStruct => {
+                f.write_str("data attributes can only be built from a struct or a map")
+            }
+            #[cfg(feature = "serde")]
+            DataError::Serialize(error) => {
+                write!(f, "could not serialize data attributes: {}", error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DataError {}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use crate::prelude::*;
+
+    use super::DataError;
+
+    #[test]
+    fn test_kebab_case() {
+        let document = Div::new()
+            .attribute(
+                Data::new()
+                    .entry("user_id", "1")
+                    .entry("postId", "2")
+                    .entry("already-kebab", "3")
+                    .entry("accountID", "4")
+                    .entry("HTMLParser", "5")
+                    .entry("Title", "6")
+                    .entry("item2Name", "7"),
+            )
+            .to_string();
+        let document = scraper::Html::parse_document(&document);
+        let div = scraper::Selector::parse("div").unwrap();
+        let div = document.select(&div).next().unwrap().value();
+        assert_eq!(div.attr("data-user-id"), Some("1"));
+        assert_eq!(div.attr("data-post-id"), Some("2"));
+        assert_eq!(div.attr("data-already-kebab"), Some("3"));
+        assert_eq!(div.attr("data-account-i-d"), None);
+        assert_eq!(div.attr("data-account-id"), Some("4"));
+        assert_eq!(div.attr("data-html-parser"), Some("5"));
+        assert_eq!(div.attr("data-title"), Some("6"));
+        assert_eq!(div.attr("data-item2-name"), Some("7"));
+    }
+
+    #[test]
+    fn test_invalid_names() {
+        for name in ["", "has space", "quote\"", "a=b", "colon:name", "<tag>"] {
+            assert!(matches!(
+                Data::new().try_entry(name, "value"),
+                Err(DataError::InvalidName(_))
+            ));
+        }
+        let mut map = HashMap::new();
+        map.insert("valid", "1");
+        map.insert("in valid", "2");
+        assert!(Data::from_map(map).is_err());
+    }
+
+    #[test]
+    fn test_borrowed_values() {
+        use crate::attributes::Attributes;
+
+        let values = [String::from("1"), String::from("2")];
+        let div = Div::new().attribute(
+            Data::from_map([
+                ("first", values[0].as_str()),
+                ("second", values[1].as_str()),
+            ])
+            .unwrap(),
+        );
+        assert_eq!(div.get_attribute("data-second"), Some("2"));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_from_serialize() {
+        #[derive(serde::Serialize)]
+        struct Post {
+            post_id: u32,
+            title: &'static str,
+            published: bool,
+            editor: Option<&'static str>,
+            tags: Vec<&'static str>,
+        }
+
+        let document = P::new("")
+            .attribute(
+                Data::from_serialize(&Post {
+                    post_id: 7,
+                    title: "Hello \"World\"",
+                    published: true,
+                    editor: None,
+                    tags: vec!["a", "b"],
+                })
+                .unwrap(),
+            )
+            .to_string();
+        let document = scraper::Html::parse_document(&document);
+        let p = scraper::Selector::parse("p").unwrap();
+        let p = document.select(&p).next().unwrap().value();
+        assert_eq!(p.attr("data-post-id"), Some("7"));
+        assert_eq!(p.attr("data-title"), Some("Hello \"World\""));
+        assert_eq!(p.attr("data-published"), Some("true"));
+        assert_eq!(p.attr("data-editor"), None);
+        assert_eq!(p.attr("data-tags"), Some("[\"a\",\"b\"]"));
+
+        assert!(matches!(
+            Data::from_serialize(&vec![1, 2, 3]),
+            Err(DataError::NotAStruct)
+        ));
+    }
+}
@@ -1,10 +1,208 @@
+/// The WAI-ARIA attributes.
+pub mod aria;
 /// Stores a number of common attributes.
 pub mod common;
+/// The `data-*` attributes.
+pub mod data;
 
-use std::borrow::Cow;
+use std::{borrow::Cow, collections::HashMap, fmt::Display, sync::OnceLock};
 
 /// Allows you to convert items into attributes.
-pub trait IntoAttribute {
+pub trait IntoAttribute<'a> {
     /// Convert the current item into an attribute.
-    fn into_attribute(self) -> (Cow<'static, str>, Cow<'static, str>);
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>);
+}
+
+/// Allows you to convert items into any number of attributes. This is
+/// implemented for every type which implements `IntoAttribute`, as well as for
+/// types (such as [`data::Data`]) which expand into several attributes at
+/// once.
+pub trait IntoAttributes<'a> {
+    /// Convert the current item into a list of attributes.
+    fn into_attributes(self) -> Vec<(Cow<'a, str>, Cow<'a, str>)>;
+}
+
+impl<'a, T> IntoAttributes<'a> for T
+where
+    T: IntoAttribute<'a>,
+{
+    fn into_attributes(self) -> Vec<(Cow<'a, str>, Cow<'a, str>)> {
+        vec![self.into_attribute()]
+    }
+}
+
+/// Allows attributes to be parsed back from the value stored on a tag (see
+/// [`Attributes::read`]).
+pub trait FromAttribute: Sized {
+    /// The name of the attribute (e.g. `"id"`).
+    const NAME: &'static str;
+
+    /// Parse the attribute from its value, returning `None` if the value is not
+    /// valid for this attribute.
+    fn from_attribute(value: &str) -> Option<Self>;
+}
+
+/// Methods to inspect and modify the attributes which have been set on a tag.
+/// This is implemented for every tag which can carry attributes.
+///
+/// ```
+/// # use malvolio::prelude::*;
+/// use malvolio::attributes::Attributes;
+///
+/// let mut div = Div::new().attribute(Id::new("main")).attribute(Class::from("a"));
+/// assert!(div.has_attribute("id"));
+/// assert_eq!(div.read::<Id>().unwrap().as_str(), "main");
+/// div.remove_attribute("class");
+/// assert_eq!(div.attributes().count(), 1);
+/// ```
+pub trait Attributes<'a> {
+    #[doc(hidden)]
+    fn attribute_map(&self) -> &HashMap<Cow<'a, str>, Cow<'a, str>>;
+
+    /// Returns `None` if the node can not have any attributes.
+    #[doc(hidden)]
+    fn attribute_map_mut(&mut self) -> Option<&mut HashMap<Cow<'a, str>, Cow<'a, str>>>;
+
+    /// Returns an iterator over the names and values of all the attributes
+    /// which have been set (in no particular order).
+    fn attributes<'s>(&'s self) -> impl Iterator<Item = (&'s str, &'s str)>
+    where
+        'a: 's,
+    {
+        self.attribute_map()
+            .iter()
+            .map(|(key, value)| (key.as_ref(), value.as_ref()))
+    }
+
+    /// Returns true if the attribute with the given name has been set.
+    fn has_attribute(&self, key: &str) -> bool {
+        self.attribute_map().contains_key(key)
+    }
+
+    /// Returns the value of the attribute with the given name, if it has been
+    /// set.
+    fn get_attribute<'s>(&'s self, key: &str) -> Option<&'s str>
+    where
+        'a: 's,
+    {
+        self.attribute_map().get(key).map(AsRef::as_ref)
+    }
+
+    /// Returns a mutable reference to the value of the attribute with the given
+    /// name, if it has been set.
+    fn attribute_mut<'s>(&'s mut self, key: &str) -> Option<&'s mut Cow<'a, str>>
+    where
+        'a: 's,
+    {
+        self.attribute_map_mut()?.get_mut(key)
+    }
+
+    /// Sets an attribute on the tag, in the same way as the tag's `attribute`
+    /// method (so `class` and `style` are merged with their existing values).
+    /// Note that, unlike `attribute`, this does not check that the attribute
+    /// is one which the tag accepts.
+    ///
+    /// Returns an error if the node can not have any attributes (this is only
+    /// possible for a `BodyNode` which is not rendered as a tag, such as a
+    /// `Fragment`).
+    fn set_attribute<A>(&mut self, attribute: A) -> Result<(), CannotHaveAttributes>
+    where
+        A: IntoAttributes<'a>,
+    {
+        let attributes = self.attribute_map_mut().ok_or(CannotHaveAttributes)?;
+        crate::utils::insert_attributes(attributes, attribute.into_attributes());
+        Ok(())
+    }
+
+    /// Removes the attribute with the given name, returning its value (if it
+    /// had been set).
+    fn remove_attribute(&mut self, key: &str) -> Option<Cow<'a, str>> {
+        self.attribute_map_mut()?.remove(key)
+    }
+
+    /// Reads an attribute, parsing it back into its typed form. Returns `None`
+    /// if the attribute has not been set (or if its value is not valid for the
+    /// attribute).
+    fn read<A>(&self) -> Option<A>
+    where
+        A: FromAttribute,
+    {
+        self.get_attribute(A::NAME).and_then(A::from_attribute)
+    }
+}
+
+/// The error returned when an attribute is set on a node which can not have
+/// any attributes (because it is not rendered as a tag).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CannotHaveAttributes;
+
+impl Display for CannotHaveAttributes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("this node can not have any attributes")
+    }
+}
+
+impl std::error::Error for CannotHaveAttributes {}
+
+/// Returns an empty set of attributes, for nodes which can not have any.
+pub(crate) fn empty_attribute_map<'s, 'a>() -> &'s HashMap<Cow<'a, str>, Cow<'a, str>> {
+    static EMPTY: OnceLock<HashMap<Cow<'static, str>, Cow<'static, str>>> = OnceLock::new();
+    EMPTY.get_or_init(HashMap::new)
+}
+
+#[cfg(test)]
+mod test {
+    use super::Attributes;
+    use crate::{css::Display, prelude::*, tags::a::Target};
+
+    #[test]
+    fn test_typed_reads() {
+        let a = A::new()
+            .attribute(Href::new("/home"))
+            .attribute(Target::Blank)
+            .attribute(Class::from("nav").class("link".into()))
+            .attribute(TabIndex::new(2))
+            .attribute(AriaLabelledBy::new_unchecked("a").id_unchecked("b"))
+            .attribute(Role::Link);
+        assert_eq!(a.read::<Href>().unwrap().as_str(), "/home");
+        assert!(matches!(a.read::<Target>(), Some(Target::Blank)));
+        assert_eq!(
+            a.read::<Class>().unwrap().classes().collect::<Vec<_>>(),
+            vec!["nav", "link"]
+        );
+        assert_eq!(a.read::<TabIndex>().unwrap().value(), 2);
+        assert_eq!(
+            a.read::<AriaLabelledBy>()
+                .unwrap()
+                .ids()
+                .collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+        assert_eq!(a.read::<Role>(), Some(Role::Link));
+        assert!(a.read::<Id>().is_none());
+
+        let input = Input::new().attribute(Type::Email).attribute(Inert);
+        assert!(matches!(input.read::<Type>(), Some(Type::Email)));
+        assert!(input.read::<Inert>().is_some());
+
+        let html = Html::new().attribute(Lang::new("en"));
+        assert_eq!(html.read::<Lang>().unwrap().as_str(), "en");
+
+        let style = StyleTag::new("").attribute(Style::default().display(Display::Flex));
+        assert_eq!(style.read::<Style>().unwrap().get("display"), Some("flex"));
+    }
+
+    #[test]
+    fn test_mutation() {
+        let mut h1 = H1::new("Heading")
+            .attribute(Id::new("heading"))
+            .attribute(AriaHidden::new(true));
+        assert!(h1.has_attribute("id"));
+        assert_eq!(h1.attributes().count(), 2);
+        *h1.attribute_mut("id").unwrap() = "renamed".into();
+        assert_eq!(h1.get_attribute("id"), Some("renamed"));
+        assert_eq!(h1.remove_attribute("aria-hidden").unwrap(), "true");
+        assert!(!h1.has_attribute("aria-hidden"));
+        assert!(h1.to_string().contains("id=\"renamed\""));
+    }
 }
@@ -0,0 +1,397 @@
+/*
+This source code file is distributed subject to the terms of the Mozilla Public License v2.0.
+A copy of this license can be found in the `licenses` directory at the root of this project.
+*/
+//! Support for a strict [Content Security Policy](https://developer.mozilla.org/en-US/docs/Web/HTTP/CSP).
+//!
+//! A strict policy blocks inline `<style>` tags unless they carry the nonce
+//! (a random value which changes on every response) named in the policy, or
+//! their contents match one of the hashes listed in it. Malvolio supports both.
+//! `style` attributes can not carry a nonce, so they are only allowed by their
+//! hashes (which requires the `'unsafe-hashes'` source).
+//!
+//! This module requires the `csp` feature.
+//!
+//! To use a nonce, render the document with a [`CspContext`], which adds the
+//! nonce to every inline `<style>` tag, and send the matching header:
+//!
+//! ```
+//! # use malvolio::prelude::*;
+//! use malvolio::csp::CspContext;
+//!
+//! let document = Html::new().head(Head::new().child(StyleTag::new("p { color: red; }")));
+//! // the nonce should be generated with a cryptographically secure random
+//! // number generator, and be different for every response
+//! let context = CspContext::from_bytes(b"not very random");
+//! let html = context.render(&document);
+//! assert!(html.contains("<style nonce=\"bm90IHZlcnkgcmFuZG9t\">"));
+//! assert_eq!(
+//!     context.header_value(),
+//!     "style-src 'self' 'nonce-bm90IHZlcnkgcmFuZG9t'"
+//! );
+//! ```
+//!
+//! Alternatively (for example, if the document is cached, so that the nonce
+//! can not change between responses, or if it contains `style` attributes),
+//! [`hashes`] computes the hashes of the inline blocks and `style` attributes
+//! in a document:
+//!
+//! ```
+//! # use malvolio::prelude::*;
+//! use malvolio::csp::hashes;
+//!
+//! let document = Html::new().head(Head::new().child(StyleTag::new("p { color: red; }")));
+//! assert_eq!(
+//!     hashes(&document).header_value(),
+//!     "style-src 'self' 'sha256-pckGv9YvNcB5xy+Y4fbqhyo+ib850wyiuWeNbZvLi00='"
+//! );
+//! ```
+//!
+//! Both headers also allow stylesheets from the same origin (`'self'`);
+//! stylesheets from other origins need to be added to the policy.
+//!
+//! Note that the contents of `Raw` nodes are opaque to Malvolio, so neither
+//! approach applies to any inline blocks inside them.
+
+use std::{borrow::Cow, cell::RefCell, collections::HashMap, fmt::Display};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    attributes::Attributes,
+    tags::body::{body_node::BodyNode, Body},
+    tags::{head::head_node::HeadNode, html::Html},
+    utils::{write_attributes, write_sorted_attributes},
+    visit::Visitor,
+};
+
+thread_local! {
+    static CURRENT: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[must_use]
+/// The context which a document is rendered in, carrying the nonce for the
+/// current response.
+///
+/// Every inline `<style>` tag which is rendered inside
+/// [`CspContext::render`] (or [`CspContext::scope`]) is given a `nonce`
+/// attribute, unless it already has one.
+pub struct CspContext {
+    nonce: String,
+}
+
+impl CspContext {
+    /// Creates a context with the provided nonce, which must be base64 (or
+    /// base64url) encoded. The nonce should be generated with a
+    /// cryptographically secure random number generator, and must be different
+    /// for every response.
+    pub fn new<S>(nonce: S) -> Result<Self, InvalidNonce>
+    where
+        S: Into<String>,
+    {
+        let nonce = nonce.into();
+        let valid = !nonce.is_empty()
+            && nonce
+                .trim_end_matches('=')
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '-' | '_'));
+        if valid {
+            Ok(Self { nonce })
+        } else {
+            Err(InvalidNonce(nonce))
+        }
+    }
+
+    /// Creates a context whose nonce is the provided (random) bytes, encoded
+    /// as base64. At least 16 bytes should be used.
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        Self {
+            nonce: STANDARD.encode(bytes),
+        }
+    }
+
+    /// Returns the nonce.
+    pub fn nonce(&self) -> &str {
+        &self.nonce
+    }
+
+    /// Renders the node (usually an [`Html`] document), adding the nonce to
+    /// every inline `<style>` tag.
+    pub fn render<T>(&self, node: &T) -> String
+    where
+        T: Display + ?Sized,
+    {
+        self.scope(|| node.to_string())
+    }
+
+    /// Calls the function, adding the nonce to every inline `<style>` tag
+    /// which is rendered on the current thread while the function runs.
+    pub fn scope<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        /// Restores the previous nonce (even if the function panics).
+        struct Reset(Option<String>);
+
+        impl Drop for Reset {
+            fn drop(&mut self) {
+                let previous = self.0.take();
+                CURRENT.with(|current| *current.borrow_mut() = previous);
+            }
+        }
+
+        let _reset = Reset(CURRENT.with(|current| current.replace(Some(self.nonce.clone()))));
+        f()
+    }
+
+    /// Returns the value of a `Content-Security-Policy` header which allows
+    /// the inline blocks carrying this nonce (and stylesheets from the same
+    /// origin).
+    ///
+    /// This does not allow `style` attributes (which can not carry a nonce);
+    /// see [`InlineHashes::style_attr_src`].
+    pub fn header_value(&self) -> String {
+        format!("style-src 'self' 'nonce-{}'", self.nonce)
+    }
+}
+
+/// The error returned when a nonce is not base64 encoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidNonce(String);
+
+impl Display for InvalidNonce {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "`{}` is not a valid (base64 encoded) nonce", self.0)
+    }
+}
+
+impl std::error::Error for InvalidNonce {}
+
+/// Writes the attributes of an inline block, adding the nonce set with
+/// [`CspContext::scope`] (if there is one, and the block does not already have
+/// a nonce).
+pub(crate) fn write_inline_attributes(
+    attrs: &HashMap<Cow<'_, str>, Cow<'_, str>>,
+    f: &mut std::fmt::Formatter<'_>,
+) -> std::fmt::Result {
+    CURRENT.with(|current| match &*current.borrow() {
+        Some(nonce) if !attrs.contains_key("nonce") => write_sorted_attributes(
+            attrs
+                .iter()
+                .map(|(key, value)| (key.as_ref(), value.as_ref()))
+                .chain([("nonce", nonce.as_str())]),
+            f,
+        ),
+        _ => write_attributes(attrs, f),
+    })
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// The SHA-256 hashes of the inline blocks and `style` attributes in a
+/// document (see [`hashes`]).
+pub struct InlineHashes {
+    styles: Vec<String>,
+    style_attributes: Vec<String>,
+}
+
+impl InlineHashes {
+    /// The hashes of the inline `<style>` tags (formatted as CSP sources,
+    /// e.g. `'sha256-…'`), in the order in which they appear in the document.
+    pub fn styles(&self) -> &[String] {
+        &self.styles
+    }
+
+    /// The hashes of the `style` attributes (formatted as CSP sources), in
+    /// the order in which they appear in the document.
+    pub fn style_attributes(&self) -> &[String] {
+        &self.style_attributes
+    }
+
+    /// Returns the sources for the inline `<style>` tags, separated by spaces
+    /// (so that other sources, such as `'self'`, can be added to them).
+    pub fn style_src(&self) -> String {
+        self.styles.join(" ")
+    }
+
+    /// Returns the sources for the `style` attributes (including the
+    /// `'unsafe-hashes'` source, without which the hashes do not apply to
+    /// attributes), separated by spaces. This is empty if the document does
+    /// not have any `style` attributes.
+    pub fn style_attr_src(&self) -> String {
+        if self.style_attributes.is_empty() {
+            String::new()
+        } else {
+            format!("'unsafe-hashes' {}", self.style_attributes.join(" "))
+        }
+    }
+
+    /// Returns the value of a `Content-Security-Policy` header which allows
+    /// exactly the inline blocks and `style` attributes in the document (and
+    /// no others), as well as stylesheets from the same origin.
+    pub fn header_value(&self) -> String {
+        let mut value = String::from("style-src 'self'");
+        for sources in [self.style_src(), self.style_attr_src()] {
+            if !sources.is_empty() {
+                value.push(' ');
+                value.push_str(&sources);
+            }
+        }
+        value
+    }
+}
+
+/// Computes the SHA-256 hashes of the inline blocks (currently the `<style>`
+/// tags) and of the `style` attributes in the document.
+pub fn hashes(html: &Html<'_>) -> InlineHashes {
+    struct Hasher(InlineHashes);
+
+    impl Hasher {
+        fn add_attributes<'a>(&mut self, node: &impl Attributes<'a>) {
+            if let Some(style) = node.get_attribute("style") {
+                add_source(&mut self.0.style_attributes, style);
+            }
+        }
+    }
+
+    impl<'a> Visitor<'a> for Hasher {
+        fn visit_html(&mut self, html: &Html<'a>) {
+            self.add_attributes(html);
+            crate::visit::walk_html(self, html)
+        }
+
+        fn visit_head(&mut self, head: &crate::tags::head::Head<'a>) {
+            self.add_attributes(head);
+            crate::visit::walk_head(self, head)
+        }
+
+        fn visit_head_node(&mut self, node: &HeadNode<'a>) {
+            self.add_attributes(node);
+            if let HeadNode::StyleTag(style) = node {
+                add_source(&mut self.0.styles, &style.contents());
+            }
+        }
+
+        fn visit_body(&mut self, body: &Body<'a>) {
+            self.add_attributes(body);
+            crate::visit::walk_body(self, body)
+        }
+
+        fn visit_body_node(&mut self, node: &BodyNode<'a>) {
+            self.add_attributes(node);
+            crate::visit::walk_body_node(self, node)
+        }
+
+        fn visit_select_option(&mut self, option: &crate::tags::option::SelectOption<'a>) {
+            self.add_attributes(option);
+        }
+    }
+
+    let mut hasher = Hasher(InlineHashes::default());
+    hasher.visit_html(html);
+    hasher.0
+}
+
+/// Adds the source which matches the contents to the list (unless it is
+/// already in it).
+fn add_source(sources: &mut Vec<String>, contents: &str) {
+    let source = hash_source(contents);
+    if !sources.contains(&source) {
+        sources.push(source);
+    }
+}
+
+/// Returns the CSP source (`'sha256-…'`) which matches the contents of an
+/// inline block.
+fn hash_source(contents: &str) -> String {
+    let mut source = String::from("'sha256-");
+    STANDARD.encode_string(Sha256::digest(contents.as_bytes()), &mut source);
+    source.push('\'');
+    source
+}
+
+#[cfg(test)]
+mod test {
+    use super::{hashes, CspContext};
+    use crate::prelude::*;
+
+    #[test]
+    fn test_nonce() {
+        assert!(CspContext::new("abc; script-src *").is_err());
+        assert!(CspContext::new("").is_err());
+        let context = CspContext::new("r4nd0m+/==").unwrap();
+
+        let document = Html::new().head(
+            Head::new()
+                .child(StyleTag::new("a {}"))
+                .child(StyleTag::new("b {}").attribute(Nonce::new("other"))),
+        );
+        let html = context.render(&document);
+        let html = scraper::Html::parse_document(&html);
+        let selector = scraper::Selector::parse("style").unwrap();
+        let nonces = html
+            .select(&selector)
+            .map(|style| style.value().attr("nonce").unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(nonces, vec!["r4nd0m+/==", "other"]);
+        assert!(!document.to_string().contains("r4nd0m"));
+    }
+
+    #[test]
+    fn test_hashes() {
+        let document = Html::new().head(
+            Head::new()
+                .child(StyleTag::new(""))
+                .child(StyleTag::new("p { color: red; }"))
+                .child(StyleTag::new("")),
+        );
+        let hashes = hashes(&document);
+        assert_eq!(
+            hashes.styles(),
+            [
+                "'sha256-47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU='",
+                "'sha256-pckGv9YvNcB5xy+Y4fbqhyo+ib850wyiuWeNbZvLi00='"
+            ]
+        );
+        assert!(hashes.style_attributes().is_empty());
+        assert_eq!(
+            hashes.header_value(),
+            format!("style-src 'self' {}", hashes.styles().join(" "))
+        );
+        assert_eq!(
+            super::hashes(&Html::new()).header_value(),
+            "style-src 'self'"
+        );
+    }
+
+    #[test]
+    fn test_style_attributes_are_hashed() {
+        let document = Html::new()
+            .head(Head::new().child(StyleTag::new("p {}")))
+            .body(
+                Body::new()
+                    .child(Div::new().attribute(Style::new("color: red;")))
+                    .child(Shared::new(
+                        P::with_text("a").attribute(Style::new("margin: 0; color: \"a&b\";")),
+                    ))
+                    .child(Div::new().attribute(Style::new("color: red;"))),
+            );
+        let hashes = hashes(&document);
+        assert_eq!(hashes.style_attributes().len(), 2);
+        let header = hashes.header_value();
+        assert!(header.starts_with("style-src 'self' 'sha256-"));
+        assert!(header.contains(&format!("'unsafe-hashes' {}", hashes.style_attributes()[0])));
+
+        // the hashes match the values of the attributes as they are parsed
+        let html = scraper::Html::parse_document(&document.to_string());
+        let selector = scraper::Selector::parse("[style]").unwrap();
+        let mut styled = 0;
+        for element in html.select(&selector) {
+            let source = super::hash_source(element.value().attr("style").unwrap());
+            assert!(hashes.style_attributes().contains(&source));
+            styled += 1;
+        }
+        assert_eq!(styled, 3);
+    }
+}
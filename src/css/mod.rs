@@ -0,0 +1,724 @@
+/*
+This source code file is distributed subject to the terms of the Mozilla Public License v2.0.
+A copy of this license can be found in the `licenses` directory at the root of this project.
+*/
+use std::{borrow::Cow, fmt::Display as FmtDisplay};
+
+use crate::attributes::{FromAttribute, IntoAttribute};
+
+pub use self::{
+    selector::Selector,
+    stylesheet::{CssRule, Stylesheet},
+};
+
+/// Selectors, which can be used to find nodes in a document.
+mod selector;
+/// Stylesheets, which can be rendered into `<style>` tags.
+mod stylesheet;
+
+/// A list of CSS declarations (for example `display: flex; margin: 4px;`).
+///
+/// This is used as the value of the `style` attribute, which can be attached to
+/// any tag.
+///
+/// ```
+/// # use malvolio::prelude::*;
+/// use malvolio::css::{Color, Display, Length};
+///
+/// Div::new().attribute(
+///     Style::default()
+///         .display(Display::Flex)
+///         .margin(Length::Px(4.0))
+///         .color(Color::Hex(0x333333))
+///         .width(Length::Percent(50.0)),
+/// );
+/// ```
+///
+/// If the `style` attribute is applied to a tag more than once, the
+/// declarations are merged (declarations which are applied later override
+/// earlier declarations of the same property).
+///
+/// Properties and values are checked before they are added; values which could
+/// be used to break out of the declaration (for example by containing a `;`, a
+/// `}` or the start of a comment) are rejected.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[must_use]
+pub struct Style<'a>(Vec<(Cow<'a, str>, Cow<'a, str>)>);
+
+impl<'a> Style<'a> {
+    /// Create a new instance of this attribute from a string of CSS
+    /// declarations (e.g. `"font-family: Arial; color: red;"`). If the string
+    /// is borrowed, the declarations borrow from it (rather than being copied).
+    ///
+    /// Any declarations which are not valid (or which could break out of the
+    /// `style` attribute) are discarded.
+    pub fn new<C>(string: C) -> Self
+    where
+        C: Into<Cow<'a, str>>,
+    {
+        match string.into() {
+            Cow::Borrowed(string) => Self::parse(string, Cow::Borrowed),
+            Cow::Owned(string) => Self::parse(&string, |part| Cow::Owned(part.to_string())),
+        }
+    }
+
+    fn parse<'s, F>(string: &'s str, convert: F) -> Self
+    where
+        F: Fn(&'s str) -> Cow<'a, str>,
+    {
+        let mut style = Self::default();
+        for declaration in split_outside_brackets(string, ';') {
+            if let Some((property, value)) = declaration.split_once(':') {
+                let (property, value) = (property.trim(), value.trim());
+                if validate_property(property).is_ok() && validate_value(value).is_ok() {
+                    style.set(convert(property), convert(value));
+                }
+            }
+        }
+        style
+    }
+
+    /// Set the value of a CSS property (overwriting any existing value for the
+    /// property), returning an error if the name of the property or the value
+    /// is not valid.
+    ///
+    /// ```
+    /// # use malvolio::prelude::*;
+    /// let style = Style::default().property("color", "red").unwrap();
+    /// assert!(style.property("color", "red; display: none").is_err());
+    /// ```
+    pub fn property<P, V>(mut self, property: P, value: V) -> Result<Self, CssError>
+    where
+        P: Into<Cow<'a, str>>,
+        V: Into<Cow<'a, str>>,
+    {
+        let property = property.into();
+        let value = value.into();
+        validate_property(&property)?;
+        validate_value(&value)?;
+        self.set(property, value);
+        Ok(self)
+    }
+
+    /// Read the value of a property, if it has been set.
+    pub fn get(&self, property: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(key, _)| key == property)
+            .map(|(_, value)| value.as_ref())
+    }
+
+    /// Returns an iterator over the declarations, in the order in which they
+    /// will be rendered.
+    pub fn declarations(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(key, value)| (key.as_ref(), value.as_ref()))
+    }
+
+    /// Returns true if no declarations have been added.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Merge the declarations from `other` into this one. Declarations in
+    /// `other` override declarations in `self` which have the same property.
+    pub fn merge(mut self, other: Style<'a>) -> Self {
+        for (property, value) in other.0 {
+            self.set(property, value);
+        }
+        self
+    }
+
+    fn set(&mut self, property: Cow<'a, str>, value: Cow<'a, str>) {
+        match self.0.iter_mut().find(|(key, _)| *key == property) {
+            Some(existing) => existing.1 = value,
+            None => self.0.push((property, value)),
+        }
+    }
+
+    /// Set a property to a value which is known to be valid.
+    fn set_checked(mut self, property: &'static str, value: impl FmtDisplay) -> Self {
+        self.set(property.into(), value.to_string().into());
+        self
+    }
+
+    /// Set a property to a length, unless the length is not finite (see
+    /// [`Length`]).
+    fn set_length(self, property: &'static str, length: Length) -> Self {
+        if length.is_finite() {
+            self.set_checked(property, length)
+        } else {
+            self
+        }
+    }
+
+    /// Set the `display` property.
+    pub fn display(self, display: Display) -> Self {
+        self.set_checked("display", display)
+    }
+
+    /// Set the `position` property.
+    pub fn position(self, position: Position) -> Self {
+        self.set_checked("position", position)
+    }
+
+    /// Set the `margin` property (on all four sides).
+    pub fn margin(self, margin: Length) -> Self {
+        self.set_length("margin", margin)
+    }
+
+    /// Set the `padding` property (on all four sides).
+    pub fn padding(self, padding: Length) -> Self {
+        self.set_length("padding", padding)
+    }
+
+    /// Set the `width` property.
+    pub fn width(self, width: Length) -> Self {
+        self.set_length("width", width)
+    }
+
+    /// Set the `height` property.
+    pub fn height(self, height: Length) -> Self {
+        self.set_length("height", height)
+    }
+
+    /// Set the `min-width` property.
+    pub fn min_width(self, width: Length) -> Self {
+        self.set_length("min-width", width)
+    }
+
+    /// Set the `max-width` property.
+    pub fn max_width(self, width: Length) -> Self {
+        self.set_length("max-width", width)
+    }
+
+    /// Set the `min-height` property.
+    pub fn min_height(self, height: Length) -> Self {
+        self.set_length("min-height", height)
+    }
+
+    /// Set the `max-height` property.
+    pub fn max_height(self, height: Length) -> Self {
+        self.set_length("max-height", height)
+    }
+
+    /// Set the `gap` property.
+    pub fn gap(self, gap: Length) -> Self {
+        self.set_length("gap", gap)
+    }
+
+    /// Set the `font-size` property.
+    pub fn font_size(self, size: Length) -> Self {
+        self.set_length("font-size", size)
+    }
+
+    /// Set the `color` property.
+    pub fn color(self, color: Color) -> Self {
+        self.set_checked("color", color)
+    }
+
+    /// Set the `background-color` property.
+    pub fn background_color(self, color: Color) -> Self {
+        self.set_checked("background-color", color)
+    }
+
+    /// Set the `text-align` property.
+    pub fn text_align(self, align: TextAlign) -> Self {
+        self.set_checked("text-align", align)
+    }
+}
+
+impl FmtDisplay for Style<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, (property, value)) in self.0.iter().enumerate() {
+            if index != 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}: {};", property, value)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> IntoAttribute<'a> for Style<'_> {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
+        ("style".into(), self.to_string().into())
+    }
+}
+
+impl FromAttribute for Style<'static> {
+    const NAME: &'static str = "style";
+
+    fn from_attribute(value: &str) -> Option<Self> {
+        Some(Self::new(value.to_string()))
+    }
+}
+
+/// A CSS length.
+///
+/// Lengths which are not finite (`NaN` or infinite) are not valid CSS, so the
+/// typed methods on [`Style`] (such as [`Style::width`]) do not set the
+/// property if they are given one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[allow(missing_docs)]
+pub enum Length {
+    Px(f32),
+    Em(f32),
+    Rem(f32),
+    Percent(f32),
+    Vw(f32),
+    Vh(f32),
+    Ch(f32),
+    Zero,
+    Auto,
+}
+
+impl Length {
+    /// Returns false if the length is `NaN` or infinite.
+    pub fn is_finite(&self) -> bool {
+        match self {
+            Length::Px(value)
+            | Length::Em(value)
+            | Length::Rem(value)
+            | Length::Percent(value)
+            | Length::Vw(value)
+            | Length::Vh(value)
+            | Length::Ch(value) => value.is_finite(),
+            Length::Zero | Length::Auto => true,
+        }
+    }
+}
+
+impl FmtDisplay for Length {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Length::Px(value) => write!(f, "{}px", value),
+            Length::Em(value) => write!(f, "{}em", value),
+            Length::Rem(value) => write!(f, "{}rem", value),
+            Length::Percent(value) => write!(f, "{}%", value),
+            Length::Vw(value) => write!(f, "{}vw", value),
+            Length::Vh(value) => write!(f, "{}vh", value),
+            Length::Ch(value) => write!(f, "{}ch", value),
+            Length::Zero => f.write_str("0"),
+            Length::Auto => f.write_str("auto"),
+        }
+    }
+}
+
+/// A CSS color.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Color {
+    /// A color with the provided red, green and blue components.
+    Rgb(u8, u8, u8),
+    /// A color with the provided red, green, blue and alpha components (the
+    /// alpha component is clamped to between 0 and 1, and `NaN` is treated as
+    /// 0).
+    Rgba(u8, u8, u8, f32),
+    /// A color written in hexadecimal (e.g. `Color::Hex(0xff0000)` for red).
+    Hex(u32),
+    /// A named color (e.g. `rebeccapurple`), created with [`Color::named`].
+    Named(ColorName),
+    /// The `currentcolor` keyword.
+    CurrentColor,
+    /// The `transparent` keyword.
+    Transparent,
+}
+
+impl Color {
+    /// Create a named color (e.g. `Color::named("rebeccapurple")`), returning
+    /// an error if the name is not made up of letters.
+    pub fn named<N>(name: N) -> Result<Self, CssError>
+    where
+        N: Into<Cow<'static, str>>,
+    {
+        let name = name.into();
+        if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphabetic()) {
+            Ok(Color::Named(ColorName(name)))
+        } else {
+            Err(CssError::InvalidValue(name.into_owned()))
+        }
+    }
+}
+
+/// The name of a color, which has been checked by [`Color::named`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColorName(Cow<'static, str>);
+
+impl ColorName {
+    /// Returns the name.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FmtDisplay for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Color::Rgb(r, g, b) => write!(f, "rgb({}, {}, {})", r, g, b),
+            Color::Rgba(r, g, b, a) => {
+                let a = if a.is_nan() { 0.0 } else { a.clamp(0.0, 1.0) };
+                write!(f, "rgba({}, {}, {}, {})", r, g, b, a)
+            }
+            Color::Hex(hex) => write!(f, "#{:06x}", hex & 0xffffff),
+            Color::Named(name) => f.write_str(name.as_str()),
+            Color::CurrentColor => f.write_str("currentcolor"),
+            Color::Transparent => f.write_str("transparent"),
+        }
+    }
+}
+
+/// Defines a CSS keyword type.
+macro_rules! css_keyword {
+    ($(#[$meta:meta])* $name:ident { $($variant:ident => $value:literal),* $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #[allow(missing_docs)]
+        pub enum $name {
+            $($variant),*
+        }
+
+        impl FmtDisplay for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(match self {
+                    $($name::$variant => $value),*
+                })
+            }
+        }
+    };
+}
+
+css_keyword!(
+    /// A value for the `display` property.
+    Display {
+        Block => "block",
+        Inline => "inline",
+        InlineBlock => "inline-block",
+        Flex => "flex",
+        InlineFlex => "inline-flex",
+        Grid => "grid",
+        InlineGrid => "inline-grid",
+        Contents => "contents",
+        None => "none",
+    }
+);
+
+css_keyword!(
+    /// A value for the `position` property.
+    Position {
+        Static => "static",
+        Relative => "relative",
+        Absolute => "absolute",
+        Fixed => "fixed",
+        Sticky => "sticky",
+    }
+);
+
+css_keyword!(
+    /// A value for the `text-align` property.
+    TextAlign {
+        Left => "left",
+        Right => "right",
+        Center => "center",
+        Justify => "justify",
+        Start => "start",
+        End => "end",
+    }
+);
+
+/// An error which occurs when invalid CSS is provided.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CssError {
+    /// The name of a property is not valid.
+    InvalidProperty(String),
+    /// The value of a property is not valid (or could be used to break out of
+    /// the declaration).
+    InvalidValue(String),
+    /// A selector is not valid (or could be used to break out of the rule, or
+    /// the `<style>` tag).
+    InvalidSelector(String),
+    /// The condition of an `@media` or `@supports` rule is not valid (or could
+    /// be used to break out of the rule, or the `<style>` tag).
+    InvalidCondition(String),
+}
+
+impl FmtDisplay for CssError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CssError::InvalidProperty(property) => {
+                write!(f, "`{}` is not a valid CSS property name", property)
+            }
+            CssError::InvalidValue(value) => write!(f, "`{}` is not a valid CSS value", value),
+            CssError::InvalidSelector(selector) => {
+                write!(f, "`{}` is not a valid CSS selector", selector)
+            }
+            CssError::InvalidCondition(condition) => {
+                write!(f, "`{}` is not a valid CSS condition", condition)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CssError {}
+
+/// Checks that the provided string is a valid CSS property name (including
+/// custom properties, such as `--main-color`).
+pub(crate) fn validate_property(property: &str) -> Result<(), CssError> {
+    let name = property
+        .strip_prefix("--")
+        .or_else(|| property.strip_prefix('-'))
+        .unwrap_or(property);
+    let valid = !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(CssError::InvalidProperty(property.to_string()))
+    }
+}
+
+/// Checks that the provided string is a CSS value which can not break out of
+/// the declaration it is part of.
+pub(crate) fn validate_value(value: &str) -> Result<(), CssError> {
+    let invalid = || Err(CssError::InvalidValue(value.to_string()));
+    if value.trim().is_empty() {
+        return invalid();
+    }
+    let mut quote = None;
+    let mut depth = 0usize;
+    // the previous character (if it was not inside quotes)
+    let mut previous = None;
+    for c in value.chars() {
+        if c.is_control() || matches!(c, '\\' | '<' | '>') {
+            return invalid();
+        }
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match (previous, c) {
+                // comments could hide the rest of the declarations
+                (Some('/'), '*') | (Some('*'), '/') => return invalid(),
+                (_, '"' | '\'') => quote = Some(c),
+                (_, '(') => depth += 1,
+                (_, ')') if depth == 0 => return invalid(),
+                (_, ')') => depth -= 1,
+                (_, ';' | '{' | '}') => return invalid(),
+                _ => {}
+            },
+        }
+        previous = if quote.is_none() { Some(c) } else { None };
+    }
+    if quote.is_some() || depth != 0 {
+        return invalid();
+    }
+    Ok(())
+}
+
+/// Splits the provided string on `separator`, ignoring any separators which
+/// appear inside quotes, brackets or comments (a comment which is not closed
+/// continues until the end of the string).
+pub(crate) fn split_outside_brackets(string: &str, separator: char) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut quote = None;
+    let mut comment = false;
+    let mut depth = 0usize;
+    let mut start = 0;
+    // the previous character (if it was not inside quotes, and did not start
+    // or end a comment)
+    let mut previous = None;
+    for (index, c) in string.char_indices() {
+        if comment {
+            if previous == Some('*') && c == '/' {
+                comment = false;
+                previous = None;
+            } else {
+                previous = Some(c);
+            }
+            continue;
+        }
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '*' if previous == Some('/') => {
+                    comment = true;
+                    previous = None;
+                    continue;
+                }
+                '"' | '\'' => quote = Some(c),
+                '(' | '[' => depth += 1,
+                ')' | ']' => depth = depth.saturating_sub(1),
+                c if c == separator && depth == 0 => {
+                    parts.push(&string[start..index]);
+                    start = index + c.len_utf8();
+                }
+                _ => {}
+            },
+        }
+        previous = if quote.is_none() { Some(c) } else { None };
+    }
+    parts.push(&string[start..]);
+    parts
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Color, CssError, Display, Length};
+    use crate::prelude::*;
+
+    #[test]
+    fn test_typed_properties() {
+        let style = Style::default()
+            .display(Display::Flex)
+            .margin(Length::Px(4.0))
+            .color(Color::Hex(0xff0000))
+            .width(Length::Percent(50.5));
+        assert_eq!(
+            style.to_string(),
+            "display: flex; margin: 4px; color: #ff0000; width: 50.5%;"
+        );
+    }
+
+    #[test]
+    fn test_merge_when_applied_twice() {
+        let document = Div::new()
+            .attribute(Style::new("color: red; margin: 0;"))
+            .attribute(Style::default().color(Color::named("blue").unwrap()))
+            .attribute(Style::default().display(Display::Block))
+            .to_string();
+        let document = scraper::Html::parse_document(&document);
+        let div = scraper::Selector::parse("div").unwrap();
+        let div = document.select(&div).next().unwrap().value();
+        assert_eq!(
+            div.attr("style"),
+            Some("color: blue; margin: 0; display: block;")
+        );
+    }
+
+    #[test]
+    fn test_rejects_breakout() {
+        for value in [
+            "red; background: url(evil)",
+            "red}",
+            "red{",
+            "\"unterminated",
+            "url(unbalanced",
+            "red)",
+            "</style>",
+            "\\3b",
+            "",
+        ] {
+            assert_eq!(
+                Style::default().property("color", value),
+                Err(CssError::InvalidValue(value.to_string()))
+            );
+        }
+        for property in ["", "col or", "color;", "1color", "--"] {
+            assert_eq!(
+                Style::default().property(property, "red"),
+                Err(CssError::InvalidProperty(property.to_string()))
+            );
+        }
+        assert_eq!(
+            Style::new(
+                "color: red; background: url(\"a;b\"); bad: {x}; font-family: \"Open Sans\""
+            )
+            .to_string(),
+            "color: red; background: url(\"a;b\"); font-family: \"Open Sans\";"
+        );
+    }
+
+    #[test]
+    fn test_rejects_comments() {
+        for value in ["red /*", "red */", "red/**/", "url(a/*b)", "'a'/*"] {
+            assert_eq!(
+                Style::default().property("color", value),
+                Err(CssError::InvalidValue(value.to_string()))
+            );
+        }
+        // everything inside a comment is discarded (along with the
+        // declaration which contains it)
+        assert_eq!(
+            Style::new("color: red /*; display: none; margin: 0 */").to_string(),
+            ""
+        );
+        assert_eq!(
+            Style::new("color: red; margin: 0 /* ; display: none */; width: 0").to_string(),
+            "color: red; width: 0;"
+        );
+        assert_eq!(
+            Style::new("color: red; margin: 0 /*; display: none; width: 0").to_string(),
+            "color: red;"
+        );
+        assert_eq!(
+            Style::new("content: \"/*\"; display: none").to_string(),
+            "content: \"/*\"; display: none;"
+        );
+        assert_eq!(
+            Style::default()
+                .property("font-family", "\"/* not a comment */\"")
+                .unwrap()
+                .to_string(),
+            "font-family: \"/* not a comment */\";"
+        );
+    }
+
+    #[test]
+    fn test_checked_values() {
+        for name in ["", "red;", "red blue", "</style>", "url(x)"] {
+            assert_eq!(
+                Color::named(name),
+                Err(CssError::InvalidValue(name.to_string()))
+            );
+        }
+        assert_eq!(
+            Style::default()
+                .color(Color::named("rebeccapurple").unwrap())
+                .background_color(Color::Rgba(0, 0, 0, 2.0))
+                .to_string(),
+            "color: rebeccapurple; background-color: rgba(0, 0, 0, 1);"
+        );
+        assert_eq!(
+            Style::default()
+                .background_color(Color::Rgba(0, 0, 0, f32::NAN))
+                .to_string(),
+            "background-color: rgba(0, 0, 0, 0);"
+        );
+        let style = Style::default()
+            .width(Length::Px(f32::NAN))
+            .height(Length::Em(f32::INFINITY))
+            .margin(Length::Rem(f32::NEG_INFINITY))
+            .padding(Length::Zero);
+        assert_eq!(style.to_string(), "padding: 0;");
+        assert!(!Length::Percent(f32::NAN).is_finite());
+    }
+
+    #[test]
+    fn test_borrows_declarations() {
+        use std::borrow::Cow;
+
+        let css = String::from("color: red; margin: 0;");
+        let style = Style::new(css.as_str());
+        assert!(style.0.iter().all(|(property, value)| matches!(
+            (property, value),
+            (Cow::Borrowed(_), Cow::Borrowed(_))
+        )));
+        assert_eq!(style.get("margin"), Some("0"));
+        assert_eq!(Style::new(css.clone()).to_string(), style.to_string());
+    }
+
+    #[test]
+    fn test_quotes_are_escaped_in_attribute() {
+        let document = Div::new()
+            .attribute(
+                Style::default()
+                    .property("font-family", "\"Open Sans\", sans-serif")
+                    .unwrap(),
+            )
+            .to_string();
+        assert!(document.contains("style=\"font-family: &quot;Open Sans&quot;, sans-serif;\""));
+    }
+}
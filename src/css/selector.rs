@@ -0,0 +1,599 @@
+/*
+This source code file is distributed subject to the terms of the Mozilla Public License v2.0.
+A copy of this license can be found in the `licenses` directory at the root of this project.
+*/
+use std::{iter::Peekable, str::Chars};
+
+use super::CssError;
+use crate::{
+    attributes::Attributes,
+    tags::{
+        body::{body_node::BodyNode, Body},
+        html::Html,
+    },
+};
+
+/// A CSS selector, which can be used to find nodes in a document (see
+/// [`Html::select`], [`Body::select`] and [`BodyNode::select`]).
+///
+/// The following selectors are supported:
+///
+/// - type selectors (`input`) and the universal selector (`*`)
+/// - id selectors (`#main`)
+/// - class selectors (`.button`)
+/// - attribute selectors (`[required]`, `[type=email]`, `[class~=a]`,
+///   `[href^=https]`, `[href$=".pdf"]` and `[href*=example]`)
+/// - the descendant (`form input`) and child (`form > input`) combinators
+/// - selector lists (`h1, h2`)
+///
+/// ```
+/// use malvolio::css::Selector;
+///
+/// assert!(Selector::parse("form > input[type=email]").is_ok());
+/// assert!(Selector::parse("form >").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selector {
+    alternatives: Vec<Complex>,
+}
+
+/// A sequence of compound selectors, separated by combinators.
+#[derive(Debug, Clone, PartialEq)]
+struct Complex {
+    compounds: Vec<Compound>,
+    /// The combinator between `compounds[i]` and `compounds[i + 1]`.
+    combinators: Vec<Combinator>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Combinator {
+    Descendant,
+    Child,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Compound {
+    tag: Option<String>,
+    ids: Vec<String>,
+    classes: Vec<String>,
+    attributes: Vec<AttributeSelector>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct AttributeSelector {
+    name: String,
+    matcher: Option<(Operator, String)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operator {
+    Equals,
+    Includes,
+    Prefix,
+    Suffix,
+    Substring,
+}
+
+impl Selector {
+    /// Parse a selector, returning an error if it is not valid (or uses a
+    /// feature which is not supported).
+    pub fn parse(selector: &str) -> Result<Self, CssError> {
+        Parser {
+            chars: selector.chars().peekable(),
+        }
+        .parse()
+        .ok_or_else(|| CssError::InvalidSelector(selector.to_string()))
+    }
+
+    /// Returns true if `node` matches this selector. `ancestors` should contain
+    /// the ancestors of the node, starting with the outermost one (the
+    /// `<body>` and `<html>` tags can not be passed here, so selectors which
+    /// mention them will not match).
+    pub fn matches(&self, node: &BodyNode, ancestors: &[&BodyNode]) -> bool {
+        let ancestors = ancestors
+            .iter()
+            .map(|ancestor| Element::Node(ancestor))
+            .collect::<Vec<_>>();
+        self.matches_element(Element::Node(node), &ancestors)
+    }
+
+    fn matches_element(&self, node: Element, ancestors: &[Element]) -> bool {
+        self.alternatives
+            .iter()
+            .any(|complex| complex.matches_at(complex.compounds.len() - 1, node, ancestors))
+    }
+
+    fn select_in<'r, 'a>(
+        &self,
+        nodes: &'r [BodyNode<'a>],
+        ancestors: &mut Vec<Element<'r, 'a>>,
+        found: &mut Vec<&'r BodyNode<'a>>,
+    ) {
+        for node in nodes {
+            // fragments and shared nodes do not appear in the rendered
+            // document, so their children are treated as children of their
+            // parent
+            if let BodyNode::Fragment(_) | BodyNode::Shared(_) = node {
+                self.select_in(node.children(), ancestors, found);
+                continue;
+            }
+            if self.matches_element(Element::Node(node), ancestors) {
+                found.push(node);
+            }
+            ancestors.push(Element::Node(node));
+            self.select_in(node.children(), ancestors, found);
+            ancestors.pop();
+        }
+    }
+}
+
+/// An element which a selector can be matched against (the `<html>` and
+/// `<body>` tags are not `BodyNode`s, but can be the ancestors of one).
+#[derive(Clone, Copy)]
+enum Element<'r, 'a> {
+    Html(&'r Html<'a>),
+    Body(&'r Body<'a>),
+    Node(&'r BodyNode<'a>),
+}
+
+impl<'r, 'a> Element<'r, 'a> {
+    fn tag_name(self) -> Option<&'static str> {
+        match self {
+            Element::Html(_) => Some("html"),
+            Element::Body(_) => Some("body"),
+            Element::Node(node) => node.tag_name(),
+        }
+    }
+
+    fn get_attribute(self, key: &str) -> Option<&'r str> {
+        match self {
+            Element::Html(html) => html.get_attribute(key),
+            Element::Body(body) => body.get_attribute(key),
+            Element::Node(node) => node.get_attribute(key),
+        }
+    }
+}
+
+impl Complex {
+    fn matches_at(&self, index: usize, node: Element, ancestors: &[Element]) -> bool {
+        if !self.compounds[index].matches(node) {
+            return false;
+        }
+        if index == 0 {
+            return true;
+        }
+        match self.combinators[index - 1] {
+            Combinator::Child => match ancestors.split_last() {
+                Some((parent, rest)) => self.matches_at(index - 1, *parent, rest),
+                None => false,
+            },
+            Combinator::Descendant => (0..ancestors.len())
+                .rev()
+                .any(|i| self.matches_at(index - 1, ancestors[i], &ancestors[..i])),
+        }
+    }
+}
+
+impl Compound {
+    fn is_empty(&self) -> bool {
+        self.tag.is_none()
+            && self.ids.is_empty()
+            && self.classes.is_empty()
+            && self.attributes.is_empty()
+    }
+
+    fn matches(&self, node: Element) -> bool {
+        let tag_name = match node.tag_name() {
+            Some(tag_name) => tag_name,
+            // text nodes are not elements, so can not be selected
+            None => return false,
+        };
+        if let Some(tag) = &self.tag {
+            if tag != "*" && !tag.eq_ignore_ascii_case(tag_name) {
+                return false;
+            }
+        }
+        let id = node.get_attribute("id");
+        if !self
+            .ids
+            .iter()
+            .all(|expected| id == Some(expected.as_str()))
+        {
+            return false;
+        }
+        let class = node.get_attribute("class").unwrap_or_default();
+        if !self
+            .classes
+            .iter()
+            .all(|expected| class.split_whitespace().any(|class| class == expected))
+        {
+            return false;
+        }
+        self.attributes.iter().all(|attribute| {
+            let value = match node.get_attribute(&attribute.name) {
+                Some(value) => value,
+                None => return false,
+            };
+            match &attribute.matcher {
+                None => true,
+                Some((Operator::Equals, expected)) => value == expected,
+                Some((Operator::Includes, expected)) => {
+                    value.split_whitespace().any(|word| word == expected)
+                }
+                Some((Operator::Prefix, expected)) => {
+                    !expected.is_empty() && value.starts_with(expected.as_str())
+                }
+                Some((Operator::Suffix, expected)) => {
+                    !expected.is_empty() && value.ends_with(expected.as_str())
+                }
+                Some((Operator::Substring, expected)) => {
+                    !expected.is_empty() && value.contains(expected.as_str())
+                }
+            }
+        })
+    }
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn parse(mut self) -> Option<Selector> {
+        let mut alternatives = vec![self.complex()?];
+        while self.chars.next_if_eq(&',').is_some() {
+            alternatives.push(self.complex()?);
+        }
+        if self.chars.peek().is_some() {
+            return None;
+        }
+        Some(Selector { alternatives })
+    }
+
+    fn complex(&mut self) -> Option<Complex> {
+        self.whitespace();
+        let mut compounds = vec![self.compound()?];
+        let mut combinators = vec![];
+        loop {
+            let had_whitespace = self.whitespace();
+            let combinator = if self.chars.next_if_eq(&'>').is_some() {
+                self.whitespace();
+                Combinator::Child
+            } else if had_whitespace && !matches!(self.chars.peek(), None | Some(',')) {
+                Combinator::Descendant
+            } else {
+                break;
+            };
+            combinators.push(combinator);
+            compounds.push(self.compound()?);
+        }
+        Some(Complex {
+            compounds,
+            combinators,
+        })
+    }
+
+    fn compound(&mut self) -> Option<Compound> {
+        let mut compound = Compound::default();
+        if self.chars.next_if_eq(&'*').is_some() {
+            compound.tag = Some("*".to_string());
+        } else if self.chars.peek().copied().is_some_and(is_ident_char) {
+            compound.tag = Some(self.ident()?);
+        }
+        loop {
+            match self.chars.peek() {
+                Some('#') => {
+                    self.chars.next();
+                    compound.ids.push(self.ident()?);
+                }
+                Some('.') => {
+                    self.chars.next();
+                    compound.classes.push(self.ident()?);
+                }
+                Some('[') => {
+                    self.chars.next();
+                    compound.attributes.push(self.attribute()?);
+                }
+                _ => break,
+            }
+        }
+        if compound.is_empty() {
+            None
+        } else {
+            Some(compound)
+        }
+    }
+
+    fn attribute(&mut self) -> Option<AttributeSelector> {
+        self.whitespace();
+        let name = self.ident()?;
+        self.whitespace();
+        if self.chars.next_if_eq(&']').is_some() {
+            return Some(AttributeSelector {
+                name,
+                matcher: None,
+            });
+        }
+        let operator = match self.chars.next()? {
+            '=' => Operator::Equals,
+            other => {
+                let operator = match other {
+                    '~' => Operator::Includes,
+                    '^' => Operator::Prefix,
+                    '$' => Operator::Suffix,
+                    '*' => Operator::Substring,
+                    _ => return None,
+                };
+                self.chars.next_if_eq(&'=')?;
+                operator
+            }
+        };
+        self.whitespace();
+        let value = match self.chars.peek()? {
+            quote @ ('"' | '\'') => {
+                let quote = *quote;
+                self.chars.next();
+                let mut value = String::new();
+                loop {
+                    match self.chars.next()? {
+                        c if c == quote => break,
+                        c => value.push(c),
+                    }
+                }
+                value
+            }
+            _ => self.ident()?,
+        };
+        self.whitespace();
+        self.chars.next_if_eq(&']')?;
+        Some(AttributeSelector {
+            name,
+            matcher: Some((operator, value)),
+        })
+    }
+
+    fn ident(&mut self) -> Option<String> {
+        let mut ident = String::new();
+        while let Some(c) = self.chars.next_if(|c| is_ident_char(*c)) {
+            ident.push(c);
+        }
+        if ident.is_empty() {
+            None
+        } else {
+            Some(ident)
+        }
+    }
+
+    /// Skips any whitespace, returning true if there was any.
+    fn whitespace(&mut self) -> bool {
+        let mut skipped = false;
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {
+            skipped = true;
+        }
+        skipped
+    }
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '-' || c == '_' || !c.is_ascii()
+}
+
+impl<'a> Html<'a> {
+    /// Returns every node in the body of this document which matches the
+    /// selector, in document order.
+    ///
+    /// ```
+    /// # use malvolio::prelude::*;
+    /// let document = Html::new().body(
+    ///     Body::new().child(
+    ///         Form::new()
+    ///             .child(Input::new().attribute(Type::Email))
+    ///             .child(Input::new().attribute(Type::Password)),
+    ///     ),
+    /// );
+    /// let inputs = document.select("form input[type=email]");
+    /// assert_eq!(inputs.len(), 1);
+    /// assert!(inputs[0].as_input().is_some());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the selector is not valid. Use
+    /// [`Html::try_select`] if the selector is not known in advance.
+    pub fn select(&self, selector: &str) -> Vec<&BodyNode<'a>> {
+        self.try_select(selector).unwrap()
+    }
+
+    /// Returns every node in the body of this document which matches the
+    /// selector, or an error if the selector is not valid.
+    pub fn try_select(&self, selector: &str) -> Result<Vec<&BodyNode<'a>>, CssError> {
+        let selector = Selector::parse(selector)?;
+        let mut found = vec![];
+        let mut ancestors = vec![Element::Html(self), Element::Body(&self.body)];
+        selector.select_in(self.body.child_nodes(), &mut ancestors, &mut found);
+        Ok(found)
+    }
+}
+
+impl<'a> Body<'a> {
+    /// Returns every node inside this tag which matches the selector, in
+    /// document order.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the selector is not valid. Use
+    /// [`Body::try_select`] if the selector is not known in advance.
+    pub fn select(&self, selector: &str) -> Vec<&BodyNode<'a>> {
+        self.try_select(selector).unwrap()
+    }
+
+    /// Returns every node inside this tag which matches the selector, or an
+    /// error if the selector is not valid. The `<body>` tag can be used in
+    /// the selector (e.g. `body > h1`), but the `<html>` tag can not (as it is
+    /// not known whether this tag is part of a document).
+    pub fn try_select(&self, selector: &str) -> Result<Vec<&BodyNode<'a>>, CssError> {
+        let selector = Selector::parse(selector)?;
+        let mut found = vec![];
+        selector.select_in(
+            self.child_nodes(),
+            &mut vec![Element::Body(self)],
+            &mut found,
+        );
+        Ok(found)
+    }
+}
+
+impl<'a> BodyNode<'a> {
+    /// Returns every node inside this node (not including the node itself)
+    /// which matches the selector, in document order.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the selector is not valid. Use
+    /// [`BodyNode::try_select`] if the selector is not known in advance.
+    pub fn select(&self, selector: &str) -> Vec<&BodyNode<'a>> {
+        self.try_select(selector).unwrap()
+    }
+
+    /// Returns every node inside this node (not including the node itself)
+    /// which matches the selector, or an error if the selector is not valid.
+    /// Only this node and its descendants are known, so a selector which
+    /// refers to this node's ancestors will not match anything.
+    ///
+    /// `Shared` nodes are transparent: calling this on a `Shared` node searches
+    /// inside the node which it contains (without including that node), and
+    /// matching nodes inside a `Shared` node are returned as references to the
+    /// contained node, rather than to the `Shared` node. Similarly, the nodes
+    /// inside a `Fragment` are treated as having the fragment's parent as their
+    /// parent.
+    pub fn try_select(&self, selector: &str) -> Result<Vec<&BodyNode<'a>>, CssError> {
+        let mut root = match self {
+            BodyNode::Shared(shared) => return shared.get().try_select(selector),
+            BodyNode::Fragment(_) => vec![],
+            node => vec![Element::Node(node)],
+        };
+        let selector = Selector::parse(selector)?;
+        let mut found = vec![];
+        selector.select_in(self.children(), &mut root, &mut found);
+        Ok(found)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Selector;
+    use crate::{attributes::Attributes, prelude::*};
+
+    fn document() -> Html<'static> {
+        Html::new().body(
+            Body::new()
+                .child(H1::new("Sign up").attribute(Id::new("title")))
+                .child(
+                    Form::new()
+                        .attribute(Class::from("signup wide"))
+                        .child(
+                            Div::new().attribute(Class::from("row")).child(
+                                Input::new()
+                                    .attribute(Type::Email)
+                                    .attribute(Name::new("email")),
+                            ),
+                        )
+                        .child(
+                            Input::new()
+                                .attribute(Type::Password)
+                                .attribute(Name::new("password")),
+                        )
+                        .child(Input::new().attribute(Type::Submit)),
+                )
+                .child(
+                    Div::new().child(
+                        Input::new()
+                            .attribute(Type::Email)
+                            .attribute(Name::new("other")),
+                    ),
+                ),
+        )
+    }
+
+    fn names<'r>(nodes: Vec<&'r BodyNode<'_>>) -> Vec<&'r str> {
+        nodes
+            .into_iter()
+            .map(|node| node.get_attribute("name").unwrap_or_default())
+            .collect()
+    }
+
+    #[test]
+    fn test_select() {
+        let document = document();
+        assert_eq!(
+            names(document.select("form input[type=email]")),
+            vec!["email"]
+        );
+        assert_eq!(
+            names(document.select("input[type=\"email\"]")),
+            vec!["email", "other"]
+        );
+        assert_eq!(names(document.select("form > input")), vec!["password", ""]);
+        assert_eq!(names(document.select("form > .row > input")), vec!["email"]);
+        assert_eq!(
+            names(document.select(".signup.wide [name^=pass]")),
+            vec!["password"]
+        );
+        assert_eq!(
+            names(document.select("form[class~=wide] input[name]")),
+            vec!["email", "password"]
+        );
+        assert_eq!(document.select("#title").len(), 1);
+        assert!(document.select("#title")[0].as_h1().is_some());
+        assert_eq!(document.select("h1, FORM").len(), 2);
+        assert_eq!(document.select("*").len(), 8);
+        assert_eq!(document.select("body > h1").len(), 1);
+        assert_eq!(document.select("html > body > h1").len(), 1);
+        assert_eq!(names(document.select("body > div input")), vec!["other"]);
+        assert!(document.select("html > h1").is_empty());
+        assert!(document.select("body").is_empty());
+        assert_eq!(document.body.select("body > form > input").len(), 2);
+        assert!(document.body.select("html h1").is_empty());
+        let document = document.attribute(Lang::new("en"));
+        assert_eq!(document.select("[lang=en] #title").len(), 1);
+        assert!(document.select("[lang=fr] #title").is_empty());
+
+        let form = document.select("form")[0];
+        assert_eq!(names(form.select("form input[type=email]")), vec!["email"]);
+        assert!(form.select("form").is_empty());
+    }
+
+    #[test]
+    fn test_select_in_shared() {
+        let shared = Shared::new(
+            Div::new()
+                .attribute(Class::from("card"))
+                .child(Input::new().attribute(Name::new("inner"))),
+        );
+        let root = BodyNode::from(shared.clone());
+        // the shared node is treated as the node it contains, which is not
+        // included in the results
+        assert!(root.select("div").is_empty());
+        assert!(root.select(".card").is_empty());
+        assert_eq!(names(root.select(".card > input")), vec!["inner"]);
+
+        let document = Body::new().child(Div::new().child(shared.clone()));
+        let found = document.select("div > .card");
+        assert_eq!(found.len(), 1);
+        assert!(std::ptr::eq(found[0], shared.get()));
+        assert_eq!(names(document.select("div > div > input")), vec!["inner"]);
+
+        let fragment = BodyNode::from(fragment().child(Input::new().attribute(Name::new("a"))));
+        assert_eq!(names(fragment.select("input")), vec!["a"]);
+    }
+
+    #[test]
+    fn test_invalid_selectors() {
+        for selector in [
+            "", "form >", "> a", "a,", "[type=", "a[=b]", "a..b", "#", "a $ b", "[a='b]",
+        ] {
+            assert!(Selector::parse(selector).is_err(), "{}", selector);
+        }
+        assert!(document().try_select("form >").is_err());
+    }
+}
@@ -0,0 +1,437 @@
+/*
+This source code file is distributed subject to the terms of the Mozilla Public License v2.0.
+A copy of this license can be found in the `licenses` directory at the root of this project.
+*/
+use std::{borrow::Cow, fmt::Display as FmtDisplay};
+
+use super::{split_outside_brackets, CssError, Style};
+use crate::tags::style::StyleTag;
+
+/// A CSS stylesheet, which can be rendered into a `<style>` tag.
+///
+/// ```
+/// # use malvolio::prelude::*;
+/// use malvolio::css::{Color, Display, Length, Stylesheet};
+///
+/// let stylesheet = Stylesheet::new()
+///     .rule("nav a", Style::default().color(Color::Hex(0x333333)))
+///     .media(
+///         "(max-width: 600px)",
+///         Stylesheet::new().rule("nav", Style::default().display(Display::None)),
+///     )
+///     .font_face(
+///         Style::default()
+///             .property("font-family", "\"Inter\"")?
+///             .property("src", "url(\"/inter.woff2\")")?,
+///     );
+/// Head::new().child(StyleTag::from(stylesheet.minify()));
+/// # Ok::<(), malvolio::css::CssError>(())
+/// ```
+///
+/// Selectors and conditions are checked when they are added, so that the
+/// rendered stylesheet can not end the `<style>` tag it is placed in early.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[must_use]
+pub struct Stylesheet<'a> {
+    rules: Vec<CssRule<'a>>,
+    minify: bool,
+}
+
+/// A single rule in a [`Stylesheet`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum CssRule<'a> {
+    /// A selector, and the declarations which apply to the elements it
+    /// matches.
+    Style {
+        /// The selector.
+        selector: Cow<'a, str>,
+        /// The declarations.
+        style: Style<'a>,
+    },
+    /// An `@media` rule.
+    Media {
+        /// The media query.
+        query: Cow<'a, str>,
+        /// The rules which apply when the media query matches.
+        rules: Vec<CssRule<'a>>,
+    },
+    /// An `@supports` rule.
+    Supports {
+        /// The condition.
+        condition: Cow<'a, str>,
+        /// The rules which apply when the condition is met.
+        rules: Vec<CssRule<'a>>,
+    },
+    /// An `@font-face` rule.
+    FontFace(Style<'a>),
+}
+
+impl<'a> Stylesheet<'a> {
+    /// Create a new (empty) stylesheet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Render this stylesheet without any unnecessary whitespace.
+    pub fn minify(mut self) -> Self {
+        self.minify = true;
+        self
+    }
+
+    /// Returns the rules in this stylesheet.
+    pub fn rules(&self) -> &[CssRule<'a>] {
+        &self.rules
+    }
+
+    /// Add a rule which applies the provided declarations to the elements
+    /// matched by the selector.
+    ///
+    /// # Panics
+    ///
+    /// If the selector is not valid (see [`Stylesheet::try_rule`] for a
+    /// non-panicking version of this method).
+    pub fn rule<S>(self, selector: S, style: Style<'a>) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.try_rule(selector, style)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Add a rule which applies the provided declarations to the elements
+    /// matched by the selector, returning an error if the selector is not
+    /// valid.
+    pub fn try_rule<S>(mut self, selector: S, style: Style<'a>) -> Result<Self, CssError>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let selector = selector.into();
+        validate_prelude(&selector).map_err(|_| CssError::InvalidSelector(selector.to_string()))?;
+        self.rules.push(CssRule::Style { selector, style });
+        Ok(self)
+    }
+
+    /// Add an `@media` rule, containing the rules in the provided stylesheet.
+    ///
+    /// # Panics
+    ///
+    /// If the media query is not valid (see [`Stylesheet::try_media`] for a
+    /// non-panicking version of this method).
+    pub fn media<Q>(self, query: Q, rules: Stylesheet<'a>) -> Self
+    where
+        Q: Into<Cow<'a, str>>,
+    {
+        self.try_media(query, rules)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Add an `@media` rule, containing the rules in the provided stylesheet,
+    /// returning an error if the media query is not valid.
+    pub fn try_media<Q>(mut self, query: Q, rules: Stylesheet<'a>) -> Result<Self, CssError>
+    where
+        Q: Into<Cow<'a, str>>,
+    {
+        let query = query.into();
+        validate_prelude(&query).map_err(|_| CssError::InvalidCondition(query.to_string()))?;
+        self.rules.push(CssRule::Media {
+            query,
+            rules: rules.rules,
+        });
+        Ok(self)
+    }
+
+    /// Add an `@supports` rule, containing the rules in the provided
+    /// stylesheet.
+    ///
+    /// # Panics
+    ///
+    /// If the condition is not valid (see [`Stylesheet::try_supports`] for a
+    /// non-panicking version of this method).
+    pub fn supports<C>(self, condition: C, rules: Stylesheet<'a>) -> Self
+    where
+        C: Into<Cow<'a, str>>,
+    {
+        self.try_supports(condition, rules)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Add an `@supports` rule, containing the rules in the provided
+    /// stylesheet, returning an error if the condition is not valid.
+    pub fn try_supports<C>(mut self, condition: C, rules: Stylesheet<'a>) -> Result<Self, CssError>
+    where
+        C: Into<Cow<'a, str>>,
+    {
+        let condition = condition.into();
+        validate_prelude(&condition)
+            .map_err(|_| CssError::InvalidCondition(condition.to_string()))?;
+        self.rules.push(CssRule::Supports {
+            condition,
+            rules: rules.rules,
+        });
+        Ok(self)
+    }
+
+    /// Add an `@font-face` rule with the provided descriptors.
+    pub fn font_face(mut self, descriptors: Style<'a>) -> Self {
+        self.rules.push(CssRule::FontFace(descriptors));
+        self
+    }
+}
+
+/// Checks the part of a rule before its block (i.e. the selector or the
+/// condition). This must not be empty, must not contain anything which would
+/// end (or start) a block early and must not contain anything which could end
+/// the `<style>` tag.
+fn validate_prelude(prelude: &str) -> Result<(), ()> {
+    let lowercase = prelude.to_ascii_lowercase();
+    if prelude.trim().is_empty()
+        || prelude
+            .chars()
+            .any(|c| c.is_control() && c != '\n' && c != '\t')
+        || lowercase.contains("</")
+        || lowercase.contains("<!--")
+        || lowercase.contains("-->")
+        || prelude.contains('\\')
+        || split_outside_brackets(prelude, '{').len() != 1
+        || split_outside_brackets(prelude, '}').len() != 1
+        || split_outside_brackets(prelude, ';').len() != 1
+    {
+        return Err(());
+    }
+    let mut quote = None;
+    let mut depth = 0isize;
+    // the previous character (if it was not inside quotes)
+    let mut previous = None;
+    for c in prelude.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match (previous, c) {
+                // comments could hide the rest of the stylesheet
+                (Some('/'), '*') | (Some('*'), '/') => return Err(()),
+                (_, '"' | '\'') => quote = Some(c),
+                (_, '(' | '[') => depth += 1,
+                (_, ')' | ']') => depth -= 1,
+                _ => {}
+            },
+        }
+        if depth < 0 {
+            return Err(());
+        }
+        previous = if quote.is_none() { Some(c) } else { None };
+    }
+    if quote.is_some() || depth != 0 {
+        return Err(());
+    }
+    Ok(())
+}
+
+impl Stylesheet<'_> {
+    fn write_rules(
+        &self,
+        rules: &[CssRule<'_>],
+        indent: usize,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        for rule in rules {
+            match rule {
+                CssRule::Style { selector, style } => {
+                    self.write_block(selector, indent, f, |f| {
+                        self.write_declarations(style, indent + 1, f)
+                    })?;
+                }
+                CssRule::Media { query, rules } => {
+                    self.write_block(&format!("@media {}", query), indent, f, |f| {
+                        self.write_rules(rules, indent + 1, f)
+                    })?;
+                }
+                CssRule::Supports { condition, rules } => {
+                    self.write_block(&format!("@supports {}", condition), indent, f, |f| {
+                        self.write_rules(rules, indent + 1, f)
+                    })?;
+                }
+                CssRule::FontFace(descriptors) => {
+                    self.write_block("@font-face", indent, f, |f| {
+                        self.write_declarations(descriptors, indent + 1, f)
+                    })?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn write_block<F>(
+        &self,
+        prelude: &str,
+        indent: usize,
+        f: &mut std::fmt::Formatter<'_>,
+        contents: F,
+    ) -> std::fmt::Result
+    where
+        F: FnOnce(&mut std::fmt::Formatter<'_>) -> std::fmt::Result,
+    {
+        if self.minify {
+            f.write_str(prelude.trim())?;
+            f.write_str("{")?;
+            contents(f)?;
+            f.write_str("}")
+        } else {
+            write_indent(indent, f)?;
+            f.write_str(prelude.trim())?;
+            f.write_str(" {\n")?;
+            contents(f)?;
+            write_indent(indent, f)?;
+            f.write_str("}\n")
+        }
+    }
+
+    fn write_declarations(
+        &self,
+        style: &Style<'_>,
+        indent: usize,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        for (index, (property, value)) in style.declarations().enumerate() {
+            if self.minify {
+                if index != 0 {
+                    f.write_str(";")?;
+                }
+                write!(f, "{}:{}", property, value)?;
+            } else {
+                write_indent(indent, f)?;
+                writeln!(f, "{}: {};", property, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_indent(indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    for _ in 0..indent {
+        f.write_str("  ")?;
+    }
+    Ok(())
+}
+
+impl FmtDisplay for Stylesheet<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write_rules(&self.rules, 0, f)
+    }
+}
+
+impl<'a> From<Stylesheet<'_>> for StyleTag<'a> {
+    fn from(stylesheet: Stylesheet<'_>) -> Self {
+        StyleTag::new(stylesheet.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Stylesheet;
+    use crate::{
+        css::{Color, CssError, Display, Length},
+        prelude::*,
+    };
+
+    fn stylesheet() -> Stylesheet<'static> {
+        Stylesheet::new()
+            .rule(
+                "nav a",
+                Style::default()
+                    .color(Color::Hex(0x333333))
+                    .margin(Length::Zero),
+            )
+            .media(
+                "(max-width: 600px)",
+                Stylesheet::new().rule("nav", Style::default().display(Display::None)),
+            )
+            .supports(
+                "(display: grid)",
+                Stylesheet::new().rule(".grid", Style::default().display(Display::Grid)),
+            )
+            .font_face(
+                Style::default()
+                    .property("font-family", "\"Inter\"")
+                    .unwrap()
+                    .property("src", "url(\"/inter.woff2\")")
+                    .unwrap(),
+            )
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            stylesheet().to_string(),
+            "nav a {
+  color: #333333;
+  margin: 0;
+}
+@media (max-width: 600px) {
+  nav {
+    display: none;
+  }
+}
+@supports (display: grid) {
+  .grid {
+    display: grid;
+  }
+}
+@font-face {
+  font-family: \"Inter\";
+  src: url(\"/inter.woff2\");
+}
+"
+        );
+    }
+
+    #[test]
+    fn test_minify() {
+        assert_eq!(
+            stylesheet().minify().to_string(),
+            "nav a{color:#333333;margin:0}@media (max-width: 600px){nav{display:none}}\
+             @supports (display: grid){.grid{display:grid}}\
+             @font-face{font-family:\"Inter\";src:url(\"/inter.woff2\")}"
+        );
+    }
+
+    #[test]
+    fn test_style_tag_can_not_be_terminated() {
+        for selector in [
+            "a</style><script>alert(1)</script>",
+            "a { color: red } b",
+            "a; b",
+            "a[title=\"unterminated]",
+            "a)",
+            "<!-- a",
+            "a\\3c/style",
+            "a /* b",
+            "a /* { */ b",
+            "",
+        ] {
+            assert_eq!(
+                Stylesheet::new().try_rule(selector, Style::default()),
+                Err(CssError::InvalidSelector(selector.to_string()))
+            );
+        }
+        assert!(Stylesheet::new()
+            .try_media("screen</STYLE>", Stylesheet::new())
+            .is_err());
+        assert!(Stylesheet::new()
+            .try_supports("(display: grid)) {", Stylesheet::new())
+            .is_err());
+
+        let document = Head::new()
+            .child(StyleTag::from(Stylesheet::new().rule(
+                "a[title=\"a > b\"]",
+                Style::default().display(Display::Block),
+            )))
+            .to_string();
+        let document = scraper::Html::parse_document(&document);
+        let style = scraper::Selector::parse("style").unwrap();
+        let style = document.select(&style).next().unwrap();
+        assert_eq!(
+            style.text().collect::<String>(),
+            "a[title=\"a > b\"] {\n  display: block;\n}\n"
+        );
+    }
+}
@@ -0,0 +1,929 @@
+/*
+This source code file is distributed subject to the terms of the Mozilla Public License v2.0.
+A copy of this license can be found in the `licenses` directory at the root of this project.
+*/
+//! Computes the changes needed to turn one tree into another.
+//!
+//! This is useful for live-updating pages: the server renders the page once,
+//! and afterwards only sends the [`Patch`]es between the old and the new
+//! version of the page to the browser.
+//!
+//! ```
+//! # use malvolio::prelude::*;
+//! use malvolio::diff::{diff, Patch};
+//!
+//! let old = Body::new().child(P::with_text("Loading..."));
+//! let new = Body::new().child(P::with_text("Done!").attribute(Class::from("done")));
+//! assert_eq!(
+//!     diff(&old, &new),
+//!     vec![
+//!         Patch::SetAttribute {
+//!             path: vec![0],
+//!             name: "class".to_string(),
+//!             value: "done".to_string()
+//!         },
+//!         Patch::SetText {
+//!             path: vec![0],
+//!             text: "Done!".to_string()
+//!         },
+//!     ]
+//! );
+//! ```
+//!
+//! # Keyed children
+//!
+//! By default children are compared by their position, so removing the first
+//! item of a list will result in every item of the list being changed. If all
+//! the children of a node have a `data-key` attribute (which should be unique
+//! among the children) then the children are instead matched up by their keys,
+//! so items which have been moved, inserted or removed are handled efficiently.
+//!
+//! ```
+//! # use malvolio::prelude::*;
+//! use malvolio::diff::{diff, Patch};
+//!
+//! fn item(key: &'static str) -> Div<'static> {
+//!     Div::new().attribute(Data::new().entry("key", key))
+//! }
+//!
+//! let old = Body::new().child(item("a")).child(item("b")).child(item("c"));
+//! let new = Body::new().child(item("c")).child(item("a"));
+//! assert_eq!(
+//!     diff(&old, &new),
+//!     vec![
+//!         Patch::Remove { path: vec![1] },
+//!         Patch::Move {
+//!             parent: vec![],
+//!             from: 1,
+//!             to: 0
+//!         },
+//!     ]
+//! );
+//! ```
+//!
+//! # Applying patches
+//!
+//! Patches are serializable (using `serde`, if the `serde` feature is
+//! enabled), and should be applied in order. A
+//! node is identified by its path: the list of indices of the children which
+//! need to be followed to get from the root (the `<body>`) to the node, so
+//! `[]` is the root itself and `[1, 0]` is the first child of the second child
+//! of the root. Indices refer to the tree as it is after all the previous
+//! patches have been applied.
+//!
+//! The indices count the children of the node as they appear in the browser:
+//! the nodes inside a `Fragment` (including one inside a `Shared` node, or
+//! returned by a `Lazy` node) are counted as children of the fragment's parent.
+//! The text of a node (which is rendered before its children) becomes an extra
+//! text node before the children, which is not counted. When the children of a
+//! node can not be counted reliably (because they include a `Raw` node, which
+//! can contain any number of nodes, or `Text` nodes which the browser merges
+//! with the text before them), the whole contents of the node are replaced
+//! with [`Patch::ReplaceChildren`].
+//!
+//! When serialized as JSON, each patch is an object with an `op` field (for
+//! example `{"op": "remove", "path": [1]}`); a JavaScript client could look
+//! something like this:
+//!
+//! ```js
+//! // the tags which render their own text before their children
+//! const OWN_TEXT = new Set(["P", "A", "LABEL", "NOSCRIPT", "H1", "H2", "H3", "H4", "H5", "H6"]);
+//! function children(node) {
+//!   const nodes = Array.from(node.childNodes);
+//!   const hasText = OWN_TEXT.has(node.nodeName) && nodes[0]?.nodeType === Node.TEXT_NODE;
+//!   return hasText ? nodes.slice(1) : nodes;
+//! }
+//! const find = (path) => path.reduce((node, i) => children(node)[i], document.body);
+//! const parse = (html) => document.createRange().createContextualFragment(html);
+//!
+//! function apply(patch) {
+//!   switch (patch.op) {
+//!     case "insert": {
+//!       const parent = find(patch.path.slice(0, -1));
+//!       parent.insertBefore(parse(patch.html), children(parent)[patch.path.at(-1)] ?? null);
+//!       break;
+//!     }
+//!     case "remove": find(patch.path).remove(); break;
+//!     case "replace": find(patch.path).replaceWith(parse(patch.html)); break;
+//!     case "move": {
+//!       const parent = find(patch.parent);
+//!       const node = children(parent)[patch.from];
+//!       node.remove();
+//!       parent.insertBefore(node, children(parent)[patch.to] ?? null);
+//!       break;
+//!     }
+//!     case "replace_children": find(patch.path).replaceChildren(parse(patch.html)); break;
+//!     case "set_attribute": find(patch.path).setAttribute(patch.name, patch.value); break;
+//!     case "remove_attribute": find(patch.path).removeAttribute(patch.name); break;
+//!     case "set_text": {
+//!       const node = find(patch.path);
+//!       if (node.nodeType === Node.TEXT_NODE) node.replaceWith(parse(patch.text));
+//!       else if (node.firstChild?.nodeType === Node.TEXT_NODE) node.firstChild.replaceWith(parse(patch.text));
+//!       else node.prepend(parse(patch.text));
+//!       break;
+//!     }
+//!   }
+//! }
+//! ```
+
+use std::{
+    borrow::Cow,
+    collections::{BTreeSet, HashSet},
+};
+
+use crate::{
+    attributes::Attributes,
+    tags::body::{body_node::BodyNode, Body},
+    utils::flatten_children,
+};
+
+/// A single change to a tree. See the [module-level documentation](self) for
+/// details of how patches should be applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "op", rename_all = "snake_case")
+)]
+pub enum Patch {
+    /// Insert a node so that it has the given path (the last index is the
+    /// position among its siblings at which the node should be inserted).
+    Insert {
+        /// The path the node will have once it has been inserted.
+        path: Vec<usize>,
+        /// The node, rendered as HTML.
+        html: String,
+    },
+    /// Remove the node with the given path.
+    Remove {
+        /// The path of the node to remove.
+        path: Vec<usize>,
+    },
+    /// Replace the node with the given path with a different node.
+    Replace {
+        /// The path of the node to replace.
+        path: Vec<usize>,
+        /// The new node, rendered as HTML.
+        html: String,
+    },
+    /// Move a child of a node to a different position among its siblings.
+    Move {
+        /// The path of the parent node.
+        parent: Vec<usize>,
+        /// The index of the child which should be moved.
+        from: usize,
+        /// The index the child should have after it has been moved.
+        to: usize,
+    },
+    /// Set (or change the value of) an attribute.
+    SetAttribute {
+        /// The path of the node.
+        path: Vec<usize>,
+        /// The name of the attribute.
+        name: String,
+        /// The new value of the attribute.
+        value: String,
+    },
+    /// Remove an attribute.
+    RemoveAttribute {
+        /// The path of the node.
+        path: Vec<usize>,
+        /// The name of the attribute.
+        name: String,
+    },
+    /// Replace the contents of a node (its text, and all of its children) with
+    /// different contents. This is used instead of patching the children one
+    /// by one when they can not be found by their index in the browser (for
+    /// example, when they include a `Raw` node, which can contain any number
+    /// of nodes, or two adjacent `Text` nodes, which the browser merges).
+    ReplaceChildren {
+        /// The path of the node.
+        path: Vec<usize>,
+        /// The new contents of the node, rendered as HTML.
+        html: String,
+    },
+    /// Change the text of a node (the text which is rendered before any of the
+    /// node's children). The text is HTML (with special characters escaped),
+    /// as it is rendered by Malvolio.
+    SetText {
+        /// The path of the node.
+        path: Vec<usize>,
+        /// The new text.
+        text: String,
+    },
+}
+
+/// Computes the patches which turn the `old` body into the `new` one.
+pub fn diff(old: &Body<'_>, new: &Body<'_>) -> Vec<Patch> {
+    let mut patches = vec![];
+    let mut path = vec![];
+    diff_attributes(old, new, &path, &mut patches);
+    diff_contents(
+        (None, old.child_nodes()),
+        (None, new.child_nodes()),
+        &mut path,
+        &mut patches,
+    );
+    patches
+}
+
+/// Computes the patches which turn the `old` node into the `new` one. Paths in
+/// the patches are relative to the node (so a patch with the path `[]` applies
+/// to the node itself).
+///
+/// Nodes which do not render as a single element (such as `Fragment`s and
+/// `Raw` nodes) are compared as a whole, and replaced if they are different.
+pub fn diff_nodes(old: &BodyNode<'_>, new: &BodyNode<'_>) -> Vec<Patch> {
+    let mut patches = vec![];
+    diff_node(old, new, &mut vec![], &mut patches);
+    patches
+}
+
+fn diff_node(
+    old: &BodyNode<'_>,
+    new: &BodyNode<'_>,
+    path: &mut Vec<usize>,
+    patches: &mut Vec<Patch>,
+) {
+    if let (BodyNode::Shared(old), BodyNode::Shared(new)) = (old, new) {
+        if old.ptr_eq(new) {
+            return;
+        }
+    }
+    // shared nodes are compared by the node they contain, and lazy nodes by
+    // what they render
+    if let BodyNode::Shared(old) = old {
+        return diff_node(old.get(), new, path, patches);
+    }
+    if let BodyNode::Shared(new) = new {
+        return diff_node(old, new.get(), path, patches);
+    }
+    if let BodyNode::Lazy(old) = old {
+        return diff_node(&old.evaluate(), new, path, patches);
+    }
+    if let BodyNode::Lazy(new) = new {
+        return diff_node(old, &new.evaluate(), path, patches);
+    }
+    // the options of a select (and the contents of fragments and raw nodes,
+    // which can contain any number of nodes) are compared as a whole
+    let same_contents = match (old, new) {
+        (BodyNode::Select(old), BodyNode::Select(new)) => {
+            old.child_nodes().len() == new.child_nodes().len()
+                && old
+                    .child_nodes()
+                    .iter()
+                    .zip(new.child_nodes())
+                    .all(|(old, new)| old.to_string() == new.to_string())
+        }
+        (BodyNode::Fragment(_), BodyNode::Fragment(_)) => old.to_string() == new.to_string(),
+        (BodyNode::Raw(old), BodyNode::Raw(new)) => old.html().as_str() == new.html().as_str(),
+        _ => true,
+    };
+    // (text nodes, fragments and raw nodes are the only remaining nodes
+    // without a tag name)
+    if old.tag_name() != new.tag_name()
+        || old.as_text().is_some() != new.as_text().is_some()
+        || old.as_fragment().is_some() != new.as_fragment().is_some()
+        || old.as_raw().is_some() != new.as_raw().is_some()
+        || !same_contents
+    {
+        patches.push(Patch::Replace {
+            path: path.clone(),
+            html: new.to_string(),
+        });
+        return;
+    }
+    if old.as_fragment().is_some() || old.as_raw().is_some() {
+        return;
+    }
+    // the attributes of text nodes are not rendered
+    if old.tag_name().is_some() {
+        diff_attributes(old, new, path, patches);
+    }
+    diff_contents(
+        (old.text(), old.children()),
+        (new.text(), new.children()),
+        path,
+        patches,
+    );
+}
+
+/// Computes the patches which turn the text and children of a node into the
+/// new text and children.
+fn diff_contents(
+    (old_text, old): (Option<&str>, &[BodyNode<'_>]),
+    (new_text, new): (Option<&str>, &[BodyNode<'_>]),
+    path: &mut Vec<usize>,
+    patches: &mut Vec<Patch>,
+) {
+    let (old, new) = (flatten_children(old), flatten_children(new));
+    if !can_index(old_text, &old) || !can_index(new_text, &new) {
+        let render = |text: Option<&str>, children: &[Cow<'_, BodyNode<'_>>]| {
+            let mut html = text.unwrap_or_default().to_string();
+            for child in children {
+                html.push_str(&child.to_string());
+            }
+            html
+        };
+        let (old, new) = (render(old_text, &old), render(new_text, &new));
+        if old != new {
+            patches.push(Patch::ReplaceChildren {
+                path: path.clone(),
+                html: new,
+            });
+        }
+        return;
+    }
+    if old_text != new_text {
+        patches.push(Patch::SetText {
+            path: path.clone(),
+            text: new_text.unwrap_or_default().to_string(),
+        });
+    }
+    match (keys(&old), keys(&new)) {
+        (Some(old_keys), Some(new_keys)) => {
+            diff_keyed_children(&old, &new, old_keys, new_keys, path, patches)
+        }
+        _ => diff_positional_children(&old, &new, path, patches),
+    }
+}
+
+/// Returns true if each of the (flattened) children of a node will be a single
+/// node in the browser, so that they can be found by their index.
+///
+/// This is not the case for raw nodes (which can contain any number of nodes),
+/// empty text nodes (which are not rendered at all) and adjacent text nodes
+/// (which are merged into one, as is a text node at the start of a node which
+/// has its own text).
+fn can_index(text: Option<&str>, children: &[Cow<'_, BodyNode<'_>>]) -> bool {
+    let is_text = |node: &BodyNode<'_>| match node {
+        BodyNode::Shared(shared) => shared.get().as_text().is_some(),
+        node => node.as_text().is_some(),
+    };
+    let mut previous_is_text = text.is_some();
+    for child in children {
+        let child = match &**child {
+            BodyNode::Shared(shared) => shared.get(),
+            child => child,
+        };
+        if child.as_raw().is_some()
+            || (is_text(child) && (previous_is_text || child.text() == Some("")))
+        {
+            return false;
+        }
+        previous_is_text = is_text(child);
+    }
+    true
+}
+
+fn diff_attributes<'a, 'b, A, B>(old: &A, new: &B, path: &[usize], patches: &mut Vec<Patch>)
+where
+    A: Attributes<'a>,
+    B: Attributes<'b>,
+{
+    // sorted, so that the output is deterministic
+    let names = old
+        .attributes()
+        .chain(new.attributes())
+        .map(|(name, _)| name)
+        .collect::<BTreeSet<_>>();
+    for name in names {
+        match (old.get_attribute(name), new.get_attribute(name)) {
+            (Some(_), None) => patches.push(Patch::RemoveAttribute {
+                path: path.to_vec(),
+                name: name.to_string(),
+            }),
+            (old, Some(new)) if old != Some(new) => patches.push(Patch::SetAttribute {
+                path: path.to_vec(),
+                name: name.to_string(),
+                value: new.to_string(),
+            }),
+            _ => {}
+        }
+    }
+}
+
+/// Returns the keys of the nodes, if every node has a unique key.
+fn keys<'r>(nodes: &'r [Cow<'_, BodyNode<'_>>]) -> Option<Vec<&'r str>> {
+    let keys = nodes
+        .iter()
+        .map(|node| node.get_attribute("data-key"))
+        .collect::<Option<Vec<_>>>()?;
+    let unique = keys.iter().collect::<HashSet<_>>().len() == keys.len();
+    unique.then_some(keys)
+}
+
+fn diff_positional_children(
+    old: &[Cow<'_, BodyNode<'_>>],
+    new: &[Cow<'_, BodyNode<'_>>],
+    path: &mut Vec<usize>,
+    patches: &mut Vec<Patch>,
+) {
+    for (i, (old, new)) in old.iter().zip(new).enumerate() {
+        path.push(i);
+        diff_node(old, new, path, patches);
+        path.pop();
+    }
+    for (i, node) in new.iter().enumerate().skip(old.len()) {
+        path.push(i);
+        patches.push(Patch::Insert {
+            path: path.clone(),
+            html: node.to_string(),
+        });
+        path.pop();
+    }
+    for i in (new.len()..old.len()).rev() {
+        path.push(i);
+        patches.push(Patch::Remove { path: path.clone() });
+        path.pop();
+    }
+}
+
+fn diff_keyed_children(
+    old: &[Cow<'_, BodyNode<'_>>],
+    new: &[Cow<'_, BodyNode<'_>>],
+    old_keys: Vec<&str>,
+    new_keys: Vec<&str>,
+    path: &mut Vec<usize>,
+    patches: &mut Vec<Patch>,
+) {
+    let new_key_set = new_keys.iter().copied().collect::<HashSet<_>>();
+    // the keys of the children, as they will be once each patch has been
+    // applied
+    let mut current = old_keys.clone();
+    for i in (0..current.len()).rev() {
+        if !new_key_set.contains(current[i]) {
+            current.remove(i);
+            path.push(i);
+            patches.push(Patch::Remove { path: path.clone() });
+            path.pop();
+        }
+    }
+    for (i, (key, node)) in new_keys.iter().zip(new).enumerate() {
+        match current.iter().position(|current| current == key) {
+            Some(position) => {
+                if position != i {
+                    let moved = current.remove(position);
+                    current.insert(i, moved);
+                    patches.push(Patch::Move {
+                        parent: path.clone(),
+                        from: position,
+                        to: i,
+                    });
+                }
+                let old_position = old_keys.iter().position(|old| old == key).unwrap();
+                path.push(i);
+                diff_node(&old[old_position], node, path, patches);
+                path.pop();
+            }
+            None => {
+                current.insert(i, key);
+                path.push(i);
+                patches.push(Patch::Insert {
+                    path: path.clone(),
+                    html: node.to_string(),
+                });
+                path.pop();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        borrow::Cow,
+        collections::{BTreeSet, HashMap},
+    };
+
+    use super::{diff, diff_nodes, Patch};
+    use crate::{attributes::Attributes, prelude::*, text::Text};
+
+    /// Returns the nodes as the browser sees them (with fragments, lazy
+    /// nodes and shared nodes replaced by the nodes they render), so that
+    /// patches can be applied to them.
+    fn materialize(nodes: &[BodyNode<'static>]) -> Vec<BodyNode<'static>> {
+        let mut materialized = vec![];
+        for node in nodes {
+            match node {
+                BodyNode::Fragment(fragment) => {
+                    materialized.extend(materialize(fragment.child_nodes()))
+                }
+                BodyNode::Lazy(lazy) => materialized.extend(materialize(&[lazy.evaluate()])),
+                BodyNode::Shared(shared) => {
+                    materialized.extend(materialize(std::slice::from_ref(shared.get())))
+                }
+                node => {
+                    let mut node = node.clone();
+                    if let Some(children) = node.children_mut() {
+                        *children = materialize(children);
+                    }
+                    materialized.push(node);
+                }
+            }
+        }
+        materialized
+    }
+
+    /// Returns the text of a node which is rendered before its children.
+    fn text_mut<'a>(node: &'a mut BodyNode<'static>) -> Option<&'a mut Cow<'static, str>> {
+        match node {
+            BodyNode::H1(h1) => Some(&mut h1.text),
+            BodyNode::H2(h2) => Some(&mut h2.text),
+            BodyNode::H3(h3) => Some(&mut h3.text),
+            BodyNode::H4(h4) => Some(&mut h4.text),
+            BodyNode::H5(h5) => Some(&mut h5.text),
+            BodyNode::H6(h6) => Some(&mut h6.text),
+            BodyNode::P(p) => Some(&mut p.text),
+            BodyNode::Text(text) => Some(&mut text.text),
+            BodyNode::A(a) => Some(&mut a.text),
+            BodyNode::Label(label) => Some(&mut label.text),
+            BodyNode::NoScript(noscript) => Some(&mut noscript.text),
+            BodyNode::Form(_)
+            | BodyNode::Br(_)
+            | BodyNode::Div(_)
+            | BodyNode::Input(_)
+            | BodyNode::Select(_)
+            | BodyNode::Img(_)
+            | BodyNode::Fragment(_)
+            | BodyNode::Lazy(_)
+            | BodyNode::Shared(_)
+            | BodyNode::Raw(_) => None,
+        }
+    }
+
+    /// Applies the patches to a tree (inserted nodes are added as unescaped
+    /// text, which renders in the same way as the original node).
+    fn apply(body: &mut Body<'static>, patches: Vec<Patch>) {
+        fn find<'a>(body: &'a mut Body<'static>, path: &[usize]) -> &'a mut BodyNode<'static> {
+            let (first, rest) = path.split_first().unwrap();
+            rest.iter()
+                .fold(&mut body.child_nodes_mut()[*first], |node, i| {
+                    &mut node.children_mut().unwrap()[*i]
+                })
+        }
+        fn siblings<'a>(
+            body: &'a mut Body<'static>,
+            path: &[usize],
+        ) -> &'a mut Vec<BodyNode<'static>> {
+            if path.is_empty() {
+                body.child_nodes_mut()
+            } else {
+                find(body, path).children_mut().unwrap()
+            }
+        }
+        fn attributes<'a>(
+            body: &'a mut Body<'static>,
+            path: &[usize],
+        ) -> &'a mut HashMap<Cow<'static, str>, Cow<'static, str>> {
+            if path.is_empty() {
+                body.attribute_map_mut().unwrap()
+            } else {
+                find(body, path).attribute_map_mut().unwrap()
+            }
+        }
+        let html = |html: String| BodyNode::from(raw(TrustedHtml::dangerously_trust(html)));
+
+        let children = materialize(body.child_nodes());
+        *body.child_nodes_mut() = children;
+        for patch in patches {
+            match patch {
+                Patch::Insert { path, html: node } => {
+                    let (last, parent) = path.split_last().unwrap();
+                    siblings(body, parent).insert(*last, html(node));
+                }
+                Patch::Remove { path } => {
+                    let (last, parent) = path.split_last().unwrap();
+                    siblings(body, parent).remove(*last);
+                }
+                Patch::Replace { path, html: node } => *find(body, &path) = html(node),
+                Patch::Move { parent, from, to } => {
+                    let siblings = siblings(body, &parent);
+                    let node = siblings.remove(from);
+                    siblings.insert(to, node);
+                }
+                Patch::SetAttribute { path, name, value } => {
+                    attributes(body, &path).insert(name.into(), value.into());
+                }
+                Patch::RemoveAttribute { path, name } => {
+                    attributes(body, &path).remove(name.as_str());
+                }
+                Patch::ReplaceChildren {
+                    path,
+                    html: contents,
+                } => {
+                    if !path.is_empty() {
+                        if let Some(text) = text_mut(find(body, &path)) {
+                            *text = "".into();
+                        }
+                    }
+                    *siblings(body, &path) = vec![html(contents)];
+                }
+                Patch::SetText { path, text } => {
+                    *text_mut(find(body, &path)).unwrap() = text.into();
+                }
+            }
+        }
+    }
+
+    fn check(old: Body<'static>, new: Body<'static>) -> Vec<Patch> {
+        let patches = diff(&old, &new);
+        let mut patched = old;
+        apply(&mut patched, patches.clone());
+        assert_eq!(patched.to_string(), new.to_string());
+        patches
+    }
+
+    fn item(key: &'static str) -> Div<'static> {
+        Div::new()
+            .attribute(Data::new().entry("key", key))
+            .child(P::with_text(key))
+    }
+
+    #[test]
+    fn test_unchanged() {
+        let body = Body::new()
+            .child(H1::new("Title"))
+            .child(Div::new().child(A::new().attribute(Href::new("/")).text("Home")));
+        assert!(check(body.clone(), body).is_empty());
+    }
+
+    #[test]
+    fn test_positional() {
+        let patches = check(
+            Body::new()
+                .child(H1::new("Title"))
+                .child(Div::new().child(P::with_text("a")).child(P::with_text("b")))
+                .child(Br::new()),
+            Body::new()
+                .attribute(Class::from("page"))
+                .child(H1::new("New title").attribute(Id::new("title")))
+                .child(
+                    Div::new()
+                        .child(P::with_text("a"))
+                        .child(A::new().text("b"))
+                        .child(P::with_text("c")),
+                ),
+        );
+        assert_eq!(
+            patches,
+            vec![
+                Patch::SetAttribute {
+                    path: vec![],
+                    name: "class".to_string(),
+                    value: "page".to_string()
+                },
+                Patch::SetAttribute {
+                    path: vec![0],
+                    name: "id".to_string(),
+                    value: "title".to_string()
+                },
+                Patch::SetText {
+                    path: vec![0],
+                    text: "New title".to_string()
+                },
+                Patch::Replace {
+                    path: vec![1, 1],
+                    html: "<a>b</a>".to_string()
+                },
+                Patch::Insert {
+                    path: vec![1, 2],
+                    html: "<p>c</p>".to_string()
+                },
+                Patch::Remove { path: vec![2] },
+            ]
+        );
+        check(
+            Body::new().child(P::with_text("a").attribute(Class::from("x"))),
+            Body::new().child(P::with_text("a")),
+        );
+    }
+
+    #[test]
+    fn test_keyed() {
+        let patches = check(
+            Body::new()
+                .child(item("a"))
+                .child(item("b"))
+                .child(item("c"))
+                .child(item("d")),
+            Body::new()
+                .child(item("d"))
+                .child(item("b"))
+                .child(item("e"))
+                .child(item("a").attribute(Class::from("x"))),
+        );
+        assert_eq!(
+            patches
+                .iter()
+                .filter(|patch| matches!(patch, Patch::Insert { .. }))
+                .count(),
+            1
+        );
+        assert!(patches
+            .iter()
+            .all(|patch| !matches!(patch, Patch::Replace { .. })));
+        check(
+            Body::new().child(item("a")).child(item("b")),
+            Body::new()
+                .child(item("b"))
+                .child(item("a"))
+                .child(item("c")),
+        );
+        check(Body::new().child(item("a")).child(item("b")), Body::new());
+    }
+
+    #[test]
+    fn test_every_patch_is_applied() {
+        /// The name of the patch (this must be updated when a patch is added,
+        /// so that it is tested).
+        fn name(patch: &Patch) -> &'static str {
+            match patch {
+                Patch::Insert { .. } => "insert",
+                Patch::Remove { .. } => "remove",
+                Patch::Replace { .. } => "replace",
+                Patch::Move { .. } => "move",
+                Patch::SetAttribute { .. } => "set_attribute",
+                Patch::RemoveAttribute { .. } => "remove_attribute",
+                Patch::ReplaceChildren { .. } => "replace_children",
+                Patch::SetText { .. } => "set_text",
+            }
+        }
+
+        let markdown = |html| raw(TrustedHtml::dangerously_trust(html));
+        let patches = check(
+            Body::new()
+                .child(H1::new("Title"))
+                .child(
+                    Div::new()
+                        .child(item("a"))
+                        .child(item("b"))
+                        .child(item("c")),
+                )
+                .child(P::with_text("p").attribute(Class::from("x")))
+                .child(Div::new().child(markdown("<i>1</i>")))
+                .child(Br::new()),
+            Body::new()
+                .child(H1::new("New title"))
+                .child(
+                    Div::new()
+                        .child(item("c"))
+                        .child(item("a"))
+                        .child(item("d")),
+                )
+                .child(P::with_text("p").attribute(Id::new("p")))
+                .child(Div::new().child(markdown("<i>2</i>")))
+                .child(A::new().text("b")),
+        );
+        let names = patches.iter().map(name).collect::<BTreeSet<_>>();
+        assert_eq!(names.len(), 8, "{:?}", names);
+    }
+
+    #[test]
+    fn test_fragments_are_flattened() {
+        let nav = Shared::new(
+            fragment()
+                .child(A::new().text("a"))
+                .child(A::new().text("b")),
+        );
+        assert_eq!(
+            check(
+                Body::new().child(nav.clone()).child(P::with_text("x")),
+                Body::new()
+                    .child(nav.clone())
+                    .child(P::with_text("x").attribute(Class::from("y"))),
+            ),
+            vec![Patch::SetAttribute {
+                path: vec![2],
+                name: "class".to_string(),
+                value: "y".to_string()
+            }]
+        );
+        assert_eq!(
+            check(
+                Body::new().child(nav).child(Br::new()),
+                Body::new()
+                    .child(Shared::new(
+                        fragment()
+                            .child(A::new().text("a"))
+                            .child(A::new().text("c")),
+                    ))
+                    .child(Br::new().attribute(Id::new("br"))),
+            ),
+            vec![
+                Patch::SetText {
+                    path: vec![1],
+                    text: "c".to_string()
+                },
+                Patch::SetAttribute {
+                    path: vec![2],
+                    name: "id".to_string(),
+                    value: "br".to_string()
+                },
+            ]
+        );
+
+        let list = |items: &'static [&'static str]| {
+            lazy(move || {
+                items
+                    .iter()
+                    .map(|item| P::with_text(*item))
+                    .collect::<Vec<_>>()
+            })
+        };
+        assert_eq!(
+            check(
+                Body::new().child(list(&["a", "b"])).child(Div::new()),
+                Body::new()
+                    .child(list(&["a", "b"]))
+                    .child(Div::new().attribute(Id::new("d"))),
+            ),
+            vec![Patch::SetAttribute {
+                path: vec![2],
+                name: "id".to_string(),
+                value: "d".to_string()
+            }]
+        );
+        check(
+            Body::new().child(list(&["a"])).child(H1::new("h")),
+            Body::new()
+                .child(lazy(|| {
+                    fragment().child(P::with_text("a")).child(Br::new())
+                }))
+                .child(H1::new("h")),
+        );
+
+        assert_eq!(
+            diff_nodes(
+                &fragment().child(Br::new()).into(),
+                &fragment().child(Br::new()).child(Br::new()).into()
+            ),
+            vec![Patch::Replace {
+                path: vec![],
+                html: "<br/><br/>".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_children_which_can_not_be_indexed() {
+        let markdown = |html| raw(TrustedHtml::dangerously_trust(html));
+        // the raw node contains two nodes, so the div is the third child in
+        // the browser
+        let old = Body::new()
+            .child(markdown("<p>a</p><p>b</p>"))
+            .child(Div::new());
+        assert!(check(old.clone(), old.clone()).is_empty());
+        assert_eq!(
+            check(
+                old,
+                Body::new()
+                    .child(markdown("<p>a</p><p>b</p>"))
+                    .child(Div::new().attribute(Class::from("x"))),
+            ),
+            vec![Patch::ReplaceChildren {
+                path: vec![],
+                html: "<p>a</p><p>b</p><div class=\"x\"></div>".to_string()
+            }]
+        );
+
+        // adjacent text nodes are merged
+        assert_eq!(
+            check(
+                Body::new().child(Div::new().child(Text::new("a")).child(Text::new("b"))),
+                Body::new().child(Div::new().child(Text::new("a")).child(Text::new("c"))),
+            ),
+            vec![Patch::ReplaceChildren {
+                path: vec![0],
+                html: "ac".to_string()
+            }]
+        );
+        // as is a text node at the start of a node with its own text
+        assert_eq!(
+            check(
+                Body::new().child(P::with_text("a").child(Text::new("b"))),
+                Body::new().child(P::with_text("c").child(Text::new("b"))),
+            ),
+            vec![Patch::ReplaceChildren {
+                path: vec![0],
+                html: "cb".to_string()
+            }]
+        );
+        // and empty text nodes are not rendered at all
+        check(
+            Body::new().child(Text::new("")).child(Br::new()),
+            Body::new()
+                .child(Text::new(""))
+                .child(Br::new().attribute(Id::new("br"))),
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serialize() {
+        let patch = Patch::Remove { path: vec![1, 2] };
+        let json = serde_json::to_string(&patch).unwrap();
+        assert_eq!(json, r#"{"op":"remove","path":[1,2]}"#);
+        assert_eq!(serde_json::from_str::<Patch>(&json).unwrap(), patch);
+    }
+}
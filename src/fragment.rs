@@ -0,0 +1,139 @@
+/*
+This source code file is distributed subject to the terms of the Mozilla Public License v2.0.
+A copy of this license can be found in the `licenses` directory at the root of this project.
+*/
+use std::fmt::Display;
+
+use crate::{into_grouping_union, tags::body::body_node::BodyNode};
+
+#[derive(Default, Debug, Clone)]
+#[must_use]
+/// A list of nodes which are rendered one after another, without a wrapping
+/// tag. This is useful for functions which need to return more than one node.
+///
+/// When a fragment is added to a tag (using `child` or `children`) its nodes
+/// are added to the tag directly, so the fragment does not appear in the tree.
+///
+/// ```
+/// # use malvolio::prelude::*;
+/// fn header() -> Fragment<'static> {
+///     Fragment::new().child(P::with_text("Title")).child(P::with_text("Subtitle"))
+/// }
+///
+/// let body = Body::new().child(header()).child(Div::new());
+/// assert_eq!(body.child_nodes().len(), 3);
+/// assert_eq!(
+///     body.to_string(),
+///     "<body><p>Title</p><p>Subtitle</p><div></div></body>"
+/// );
+/// ```
+///
+/// Fragments do not have any attributes of their own, so setting an attribute
+/// on a `BodyNode::Fragment` returns an error.
+pub struct Fragment<'a> {
+    pub(crate) children: Vec<BodyNode<'a>>,
+}
+
+impl<'a> Fragment<'a> {
+    /// Creates a new (empty) [`Fragment`].
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add a single node to this fragment.
+    pub fn child<C>(mut self, child: C) -> Self
+    where
+        C: Into<BodyNode<'a>>,
+    {
+        crate::utils::push_child(&mut self.children, child.into());
+        self
+    }
+
+    /// Add a number of nodes to this fragment from an iterator.
+    pub fn children<I, C>(mut self, children: I) -> Self
+    where
+        I: IntoIterator<Item = C>,
+        C: Into<BodyNode<'a>>,
+    {
+        crate::utils::extend_children(&mut self.children, children);
+        self
+    }
+
+    crate::define_child_node_accessors!(BodyNode<'a>);
+
+    crate::define_conditional_child_fns!(BodyNode<'a>);
+}
+
+/// Creates a new `Fragment` – functionally equivalent to `Fragment::new()` (but
+/// easier to type.)
+pub fn fragment<'a>() -> Fragment<'a> {
+    Fragment::new()
+}
+
+impl<'a, C> FromIterator<C> for Fragment<'a>
+where
+    C: Into<BodyNode<'a>>,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = C>,
+    {
+        Fragment::new().children(iter)
+    }
+}
+
+impl Display for Fragment<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for child in &self.children {
+            child.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+into_grouping_union!(Fragment, BodyNode);
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_fragment_flattening() {
+        let fragment = (1..=2)
+            .map(|i| P::with_text(i.to_string()))
+            .collect::<Fragment>()
+            .child(Fragment::new().child(Br::new()));
+        assert_eq!(fragment.child_nodes().len(), 3);
+        assert_eq!(fragment.to_string(), "<p>1</p><p>2</p><br/>");
+
+        let div = Div::new().children(vec![BodyNode::from(fragment.clone()), H1::new("3").into()]);
+        assert_eq!(div.child_nodes().len(), 4);
+        assert!(div
+            .child_nodes()
+            .iter()
+            .all(|node| node.as_fragment().is_none()));
+
+        let form = Form::new().child(fragment.clone());
+        assert_eq!(form.child_nodes().len(), 3);
+        let p = P::with_text("text").children(vec![fragment.clone()]);
+        assert_eq!(p.child_nodes().len(), 3);
+        let body = Body::new().child(fragment.clone()).children(vec![fragment]);
+        assert_eq!(body.child_nodes().len(), 6);
+
+        let document = scraper::Html::parse_document(&Html::new().body(body).to_string());
+        let selector = scraper::Selector::parse("body > p").unwrap();
+        assert_eq!(document.select(&selector).count(), 4);
+    }
+
+    #[test]
+    fn test_fragment_has_no_attributes() {
+        use crate::attributes::{Attributes, CannotHaveAttributes};
+
+        let mut node = BodyNode::from(Fragment::new().child(P::with_text("text")));
+        assert_eq!(node.set_attribute(Id::new("x")), Err(CannotHaveAttributes));
+        assert_eq!(node.get_attribute("id"), None);
+        assert!(node.attributes().next().is_none());
+        assert_eq!(node.remove_attribute("id"), None);
+        assert_eq!(node.to_string(), "<p>text</p>");
+    }
+}
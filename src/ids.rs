@@ -0,0 +1,553 @@
+/*
+This source code file is distributed subject to the terms of the Mozilla Public License v2.0.
+A copy of this license can be found in the `licenses` directory at the root of this project.
+*/
+//! Finds duplicate ids, and references to ids which do not exist.
+//!
+//! An [`IdRegistry`] records the ids in a document, and every attribute which
+//! refers to one (such as the `for` attribute of a `<label>`,
+//! `aria-labelledby`, or a link to `#section`), so that it can report any
+//! [`IdProblem`]s:
+//!
+//! ```
+//! # use malvolio::prelude::*;
+//! use malvolio::ids::{IdProblem, IdRegistry, Location};
+//!
+//! let document = Html::new().body(
+//!     Body::new()
+//!         .child(Label::new("Email").raw_attribute("for", "emial"))
+//!         .child(Input::new().attribute(Id::new("email")))
+//!         .child(Div::new().attribute(Id::new("email"))),
+//! );
+//! let registry = IdRegistry::new(&document);
+//! assert_eq!(
+//!     registry.problems(),
+//!     vec![
+//!         IdProblem::Dangling {
+//!             location: Location::Body(vec![0]),
+//!             attribute: "for",
+//!             id: "emial".to_string()
+//!         },
+//!         IdProblem::Duplicate {
+//!             id: "email".to_string(),
+//!             locations: vec![Location::Body(vec![1]), Location::Body(vec![2])]
+//!         },
+//!     ]
+//! );
+//! ```
+//!
+//! Nodes in the body are identified by their path, in the same way as in the
+//! [`diff`](crate::diff) module (the options of a `<select>` are identified by
+//! the path of the `<select>`, followed by the index of the option), and the
+//! children of the `<head>` by their index (see [`Location`]). `Shared` nodes
+//! are checked as if they were the node they contain, and `Lazy` nodes are
+//! constructed (and then checked). Ids inside `Raw` nodes are not seen.
+//!
+//! # Checking documents when they are rendered
+//!
+//! Every document which is rendered inside [`check_on_render`] is checked, so
+//! that (for example) a test can check the pages it renders without changing
+//! how they are rendered:
+//!
+//! ```
+//! # use malvolio::prelude::*;
+//! let document = Html::new().body(Body::new().child(A::new().href("#missing").text("Skip")));
+//! let problems = malvolio::ids::check_on_render(|| document.to_string()).unwrap_err();
+//! // nothing has the id `missing`
+//! assert_eq!(problems.len(), 1);
+//! ```
+
+use std::{cell::RefCell, collections::HashMap, fmt::Display};
+
+use crate::{
+    attributes::Attributes,
+    tags::{
+        body::{body_node::BodyNode, Body},
+        html::Html,
+    },
+    utils::flatten_children,
+};
+
+thread_local! {
+    static PROBLEMS: RefCell<Option<Vec<IdProblem>>> = const { RefCell::new(None) };
+}
+
+/// The attributes which contain the id of another element.
+const ID_ATTRIBUTES: &[&str] = &["for", "list", "form", "aria-activedescendant"];
+
+/// The attributes which contain a list of ids (separated by spaces).
+const ID_LIST_ATTRIBUTES: &[&str] = &[
+    "aria-labelledby",
+    "aria-describedby",
+    "aria-controls",
+    "aria-owns",
+    "aria-flowto",
+    "aria-errormessage",
+];
+
+/// Calls the function, checking every [`Html`] document which is rendered on
+/// the current thread while the function runs. Returns the result of the
+/// function if none of the documents had any problems, or the problems
+/// (rather than panicking while the document is rendered, so that the caller
+/// can decide what to do with them).
+pub fn check_on_render<F, R>(f: F) -> Result<R, Vec<IdProblem>>
+where
+    F: FnOnce() -> R,
+{
+    /// Restores the previous state (even if the function panics).
+    struct Reset(Option<Vec<IdProblem>>);
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            PROBLEMS.with(|problems| *problems.borrow_mut() = previous);
+        }
+    }
+
+    let _reset = Reset(PROBLEMS.with(|problems| problems.replace(Some(vec![]))));
+    let result = f();
+    match PROBLEMS.with(|problems| problems.replace(Some(vec![]))) {
+        Some(problems) if !problems.is_empty() => Err(problems),
+        _ => Ok(result),
+    }
+}
+
+/// Checks the document, if it is being rendered inside [`check_on_render`].
+pub(crate) fn record_on_render(html: &Html<'_>) {
+    let checking = PROBLEMS.with(|problems| problems.borrow().is_some());
+    if checking {
+        let found = IdRegistry::new(html).problems();
+        PROBLEMS.with(|problems| {
+            if let Some(problems) = &mut *problems.borrow_mut() {
+                problems.extend(found);
+            }
+        });
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Where a node is in a document (in document order, so that `Html` comes
+/// before `Head`, which comes before `Body`).
+pub enum Location {
+    /// The `<html>` tag.
+    Html,
+    /// The `<head>` tag (if the path is empty), or one of its children (if the
+    /// path contains the index of the child).
+    Head(Vec<usize>),
+    /// The `<body>` tag (if the path is empty), or the node with the path (the
+    /// list of indices of the children which need to be followed to get from
+    /// the `<body>` to the node).
+    Body(Vec<usize>),
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Location::Html => f.write_str("<html>"),
+            Location::Head(path) => write!(f, "<head> {:?}", path),
+            Location::Body(path) => write!(f, "<body> {:?}", path),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An attribute which refers to an id.
+pub struct Reference {
+    /// The node which has the attribute.
+    pub location: Location,
+    /// The name of the attribute (e.g. `"for"`).
+    pub attribute: &'static str,
+    /// The id which is referred to.
+    pub id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A problem found by an [`IdRegistry`].
+pub enum IdProblem {
+    /// More than one node has the same id.
+    Duplicate {
+        /// The id.
+        id: String,
+        /// The nodes with the id, in document order.
+        locations: Vec<Location>,
+    },
+    /// An attribute refers to an id which no node has.
+    Dangling {
+        /// The node which has the attribute.
+        location: Location,
+        /// The name of the attribute.
+        attribute: &'static str,
+        /// The id which does not exist.
+        id: String,
+    },
+}
+
+impl IdProblem {
+    /// The (first) node with the problem.
+    fn location(&self) -> &Location {
+        match self {
+            IdProblem::Duplicate { locations, .. } => &locations[0],
+            IdProblem::Dangling { location, .. } => location,
+        }
+    }
+}
+
+impl Display for IdProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IdProblem::Duplicate { id, locations } => {
+                write!(f, "the id `{}` is used more than once (at ", id)?;
+                for (index, location) in locations.iter().enumerate() {
+                    if index != 0 {
+                        f.write_str(", ")?;
+                    }
+                    location.fmt(f)?;
+                }
+                f.write_str(")")
+            }
+            IdProblem::Dangling {
+                location,
+                attribute,
+                id,
+            } => write!(
+                f,
+                "`{}` refers to the id `{}`, which does not exist (at {})",
+                attribute, id, location
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+/// The ids in a document, and the attributes which refer to them.
+pub struct IdRegistry {
+    ids: HashMap<String, Vec<Location>>,
+    references: Vec<Reference>,
+}
+
+impl IdRegistry {
+    /// Records the ids (and references to ids) in the document.
+    pub fn new(html: &Html<'_>) -> Self {
+        let mut registry = Self::default();
+        registry.add_attributes(Location::Html, html);
+        registry.add_attributes(Location::Head(vec![]), &html.head);
+        for (index, node) in html.head.child_nodes().iter().enumerate() {
+            registry.add_attributes(Location::Head(vec![index]), node);
+        }
+        registry.add_body(&html.body);
+        registry
+    }
+
+    /// Records the ids (and references to ids) in a `<body>` tag.
+    pub fn from_body(body: &Body<'_>) -> Self {
+        let mut registry = Self::default();
+        registry.add_body(body);
+        registry
+    }
+
+    /// Returns true if a node has the id.
+    pub fn contains(&self, id: &str) -> bool {
+        self.ids.contains_key(id)
+    }
+
+    /// Returns the (first) node with the id.
+    pub fn location(&self, id: &str) -> Option<&Location> {
+        self.ids.get(id).map(|locations| &locations[0])
+    }
+
+    /// Returns the attributes which refer to an id, in document order.
+    pub fn references(&self) -> &[Reference] {
+        &self.references
+    }
+
+    /// Returns the duplicate ids and the references to ids which do not exist,
+    /// in document order.
+    pub fn problems(&self) -> Vec<IdProblem> {
+        let mut problems = self
+            .ids
+            .iter()
+            .filter(|(_, locations)| locations.len() > 1)
+            .map(|(id, locations)| IdProblem::Duplicate {
+                id: id.clone(),
+                locations: locations.clone(),
+            })
+            .chain(
+                self.references
+                    .iter()
+                    .filter(|reference| !self.contains(&reference.id))
+                    .map(|reference| IdProblem::Dangling {
+                        location: reference.location.clone(),
+                        attribute: reference.attribute,
+                        id: reference.id.clone(),
+                    }),
+            )
+            .collect::<Vec<_>>();
+        problems.sort_by(|a, b| a.location().cmp(b.location()));
+        problems
+    }
+
+    /// Panics (listing the problems) if there are any duplicate ids or
+    /// references to ids which do not exist.
+    pub fn assert_valid(&self) {
+        let problems = self.problems();
+        if !problems.is_empty() {
+            let problems = problems.iter().map(ToString::to_string).collect::<Vec<_>>();
+            panic!("invalid ids in document:\n{}", problems.join("\n"));
+        }
+    }
+
+    fn add_body(&mut self, body: &Body<'_>) {
+        self.add_attributes(Location::Body(vec![]), body);
+        self.add_children(&mut vec![], body.child_nodes());
+    }
+
+    fn add_children(&mut self, path: &mut Vec<usize>, children: &[BodyNode<'_>]) {
+        for (index, child) in flatten_children(children).iter().enumerate() {
+            path.push(index);
+            self.add_node(path, child);
+            path.pop();
+        }
+    }
+
+    fn add_node(&mut self, path: &mut Vec<usize>, node: &BodyNode<'_>) {
+        match node {
+            BodyNode::Shared(shared) => return self.add_node(path, shared.get()),
+            BodyNode::Select(select) => {
+                for (index, option) in select.child_nodes().iter().enumerate() {
+                    path.push(index);
+                    self.add_attributes(Location::Body(path.clone()), option);
+                    path.pop();
+                }
+            }
+            _ => {}
+        }
+        self.add_attributes(Location::Body(path.clone()), node);
+        self.add_children(path, node.children());
+    }
+
+    fn add_attributes<'a, A>(&mut self, location: Location, node: &A)
+    where
+        A: Attributes<'a>,
+    {
+        if let Some(id) = node.get_attribute("id") {
+            self.ids
+                .entry(id.to_string())
+                .or_default()
+                .push(location.clone());
+        }
+        let mut refer = |attribute, id: &str| {
+            self.references.push(Reference {
+                location: location.clone(),
+                attribute,
+                id: id.to_string(),
+            })
+        };
+        for attribute in ID_ATTRIBUTES {
+            if let Some(id) = node.get_attribute(attribute) {
+                refer(attribute, id);
+            }
+        }
+        for attribute in ID_LIST_ATTRIBUTES {
+            for id in node
+                .get_attribute(attribute)
+                .unwrap_or_default()
+                .split_whitespace()
+            {
+                refer(attribute, id);
+            }
+        }
+        // `#` and `#top` scroll to the top of the page, even if there is no
+        // element with that id
+        let fragment = node
+            .get_attribute("href")
+            .and_then(|href| href.strip_prefix('#'))
+            .filter(|fragment| !fragment.is_empty() && !fragment.eq_ignore_ascii_case("top"));
+        if let Some(fragment) = fragment {
+            refer("href", &percent_decode(fragment));
+        }
+    }
+}
+
+/// Decodes the percent-encoded characters in the fragment of a URL (as
+/// `SafeUrl` encodes any characters which are not allowed in URLs).
+fn percent_decode(fragment: &str) -> String {
+    let bytes = fragment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escape = bytes
+            .get(index + 1..index + 3)
+            .filter(|_| bytes[index] == b'%')
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match escape {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{check_on_render, IdProblem, IdRegistry, Location};
+    use crate::prelude::*;
+
+    #[test]
+    fn test_registry() {
+        let heading = Shared::new(H2::new("Café").attribute(Id::new("café")));
+        let document = Html::new().body(
+            Body::new()
+                .child(A::new().href("#caf%C3%A9").text("Skip"))
+                .child(A::new().href("#top").text("Top"))
+                .child(heading.clone())
+                .child(
+                    Form::new()
+                        .attribute(Id::new("signup"))
+                        .child(
+                            Input::new().attribute(
+                                AriaLabelledBy::new_unchecked("café").id_unchecked("hint"),
+                            ),
+                        )
+                        .child(Select::new().child(SelectOption::new().attribute(Id::new("x")))),
+                )
+                .child(Div::new().child(lazy(move || heading.clone()))),
+        );
+        let registry = IdRegistry::new(&document);
+        assert_eq!(registry.location("signup"), Some(&Location::Body(vec![3])));
+        assert_eq!(registry.location("x"), Some(&Location::Body(vec![3, 1, 0])));
+        assert_eq!(registry.references().len(), 3);
+        assert_eq!(
+            registry.problems(),
+            vec![
+                IdProblem::Duplicate {
+                    id: "café".to_string(),
+                    locations: vec![Location::Body(vec![2]), Location::Body(vec![4, 0])]
+                },
+                IdProblem::Dangling {
+                    location: Location::Body(vec![3, 0]),
+                    attribute: "aria-labelledby",
+                    id: "hint".to_string()
+                },
+            ]
+        );
+        assert!(
+            std::panic::catch_unwind(|| registry.assert_valid()).is_err(),
+            "registry should not be valid"
+        );
+        IdRegistry::from_body(&Body::new().child(Div::new().attribute(Id::new("a"))))
+            .assert_valid();
+    }
+
+    #[test]
+    fn test_duplicate_ids_through_shared_clones() {
+        use crate::attributes::Attributes;
+
+        let card = Shared::new(Div::new().attribute(Id::new("card")));
+        let body = Body::new()
+            .child(card.clone())
+            .child(Div::new().child(card.clone()));
+        assert_eq!(
+            IdRegistry::from_body(&body).problems(),
+            vec![IdProblem::Duplicate {
+                id: "card".to_string(),
+                locations: vec![Location::Body(vec![0]), Location::Body(vec![1, 0])]
+            }]
+        );
+
+        // changing a clone does not change the other clones
+        let mut changed = BodyNode::from(card.clone());
+        changed.set_attribute(Id::new("other")).unwrap();
+        let body = Body::new().child(card).child(changed);
+        assert!(IdRegistry::from_body(&body).problems().is_empty());
+    }
+
+    #[test]
+    fn test_dangling_id_ref() {
+        // the input is never added to the document
+        let (_input, email) = Input::new().with_id("email");
+        let body = Body::new()
+            .child(Label::new("Email").attribute(For::new(&email)))
+            .child(A::new().href(email.url()).text("Skip to the form"));
+        assert_eq!(
+            IdRegistry::from_body(&body).problems(),
+            vec![
+                IdProblem::Dangling {
+                    location: Location::Body(vec![0]),
+                    attribute: "for",
+                    id: "email".to_string()
+                },
+                IdProblem::Dangling {
+                    location: Location::Body(vec![1]),
+                    attribute: "href",
+                    id: "email".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_html_head_and_body_ids() {
+        let document = Html::new()
+            .attribute(Id::new("page"))
+            .head(Head::new().child(Meta::new().attribute(Id::new("meta"))))
+            .body(
+                Body::new()
+                    .attribute(Id::new("main"))
+                    .child(A::new().href("#main").text("Skip"))
+                    .child(Div::new().attribute(Id::new("page")))
+                    .child(Div::new().attribute(AriaOwns::new_unchecked("meta"))),
+            );
+        let registry = IdRegistry::new(&document);
+        assert_eq!(registry.location("meta"), Some(&Location::Head(vec![0])));
+        assert_eq!(registry.location("main"), Some(&Location::Body(vec![])));
+        assert_eq!(
+            registry.problems(),
+            vec![IdProblem::Duplicate {
+                id: "page".to_string(),
+                locations: vec![Location::Html, Location::Body(vec![1])]
+            }]
+        );
+    }
+
+    #[test]
+    fn test_fragments_from_lazy_nodes_are_flattened() {
+        let body = Body::new().child(Div::new()).child(lazy(|| {
+            Fragment::new()
+                .child(Div::new())
+                .child(Div::new().attribute(Id::new("a")))
+        }));
+        let registry = IdRegistry::from_body(&body);
+        assert_eq!(registry.location("a"), Some(&Location::Body(vec![2])));
+    }
+
+    #[test]
+    fn test_check_on_render() {
+        let valid = Html::new().body(Body::new().child(Div::new().attribute(Id::new("a"))));
+        let invalid = Html::new().body(Body::new().child(A::new().href("#missing").text("a")));
+
+        assert_eq!(check_on_render(|| valid.to_string()), Ok(valid.to_string()));
+        let problems = check_on_render(|| {
+            // checks in a nested call are reported to that call
+            assert!(check_on_render(|| invalid.to_string()).is_err());
+            assert!(check_on_render(|| valid.to_string()).is_ok());
+            (valid.to_string(), invalid.to_string())
+        })
+        .unwrap_err();
+        assert_eq!(problems, IdRegistry::new(&invalid).problems());
+
+        // documents are only checked inside `check_on_render`, and only on the
+        // thread which called it
+        check_on_render(|| {
+            std::thread::spawn(move || invalid.to_string())
+                .join()
+                .unwrap()
+        })
+        .unwrap();
+    }
+}
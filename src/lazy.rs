@@ -0,0 +1,114 @@
+/*
+This source code file is distributed subject to the terms of the Mozilla Public License v2.0.
+A copy of this license can be found in the `licenses` directory at the root of this project.
+*/
+use std::{fmt::Display, sync::Arc};
+
+use crate::{into_grouping_union, tags::body::body_node::BodyNode};
+
+#[derive(Clone)]
+/// A node which is only constructed when it is rendered. This is useful for
+/// subtrees which are expensive to construct, and which might not end up being
+/// rendered.
+///
+/// ```
+/// # use malvolio::prelude::*;
+/// let div = Div::new().child(Lazy::new(|| P::with_text("Expensive")));
+/// assert_eq!(div.to_string(), "<div><p>Expensive</p></div>");
+/// ```
+///
+/// The closure is called every time the node is rendered. Cloning a `Lazy`
+/// node is cheap, as the clone shares the closure with the original node. The
+/// `Debug` implementation does not call the closure.
+///
+/// As the node is not constructed until it is rendered, the node is opaque to
+/// everything else which inspects the tree (for example, the visitors in the
+/// `visit` module and `select` do not see its contents). [`Lazy::evaluate`]
+/// can be used to construct the node. Lazy nodes do not have any attributes
+/// of their own, so setting an attribute on a `BodyNode::Lazy` returns an
+/// error.
+pub struct Lazy<'a> {
+    render: Arc<dyn Fn() -> BodyNode<'a> + Send + Sync + 'a>,
+}
+
+impl<'a> Lazy<'a> {
+    /// Creates a new [`Lazy`] node, which will call the provided function to
+    /// construct the node whenever it is rendered.
+    pub fn new<F, N>(render: F) -> Self
+    where
+        F: Fn() -> N + Send + Sync + 'a,
+        N: Into<BodyNode<'a>>,
+    {
+        Self {
+            render: Arc::new(move || render().into()),
+        }
+    }
+
+    /// Constructs the node.
+    pub fn evaluate(&self) -> BodyNode<'a> {
+        (self.render)()
+    }
+}
+
+/// Creates a new `Lazy` node – functionally equivalent to `Lazy::new(render)`
+/// (but easier to type.)
+pub fn lazy<'a, F, N>(render: F) -> Lazy<'a>
+where
+    F: Fn() -> N + Send + Sync + 'a,
+    N: Into<BodyNode<'a>>,
+{
+    Lazy::new(render)
+}
+
+impl std::fmt::Debug for Lazy<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Lazy").finish_non_exhaustive()
+    }
+}
+
+impl Display for Lazy<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.evaluate().fmt(f)
+    }
+}
+
+into_grouping_union!(Lazy, BodyNode);
+
+#[cfg(test)]
+mod test {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use crate::prelude::*;
+
+    #[test]
+    fn test_lazy_rendering() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let body = Body::new().child(Lazy::new(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Div::new().child(P::with_text("lazy"))
+        }));
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        let cloned = body.clone();
+        assert!(format!("{:?}", cloned).contains("Lazy"));
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        assert_eq!(body.to_string(), "<body><div><p>lazy</p></div></body>");
+        assert_eq!(cloned.to_string(), body.to_string());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_lazy_has_no_attributes() {
+        use crate::attributes::{Attributes, CannotHaveAttributes};
+
+        let mut node = BodyNode::from(Lazy::new(|| Div::new().attribute(Id::new("inner"))));
+        assert_eq!(node.set_attribute(Id::new("x")), Err(CannotHaveAttributes));
+        assert_eq!(node.get_attribute("id"), None);
+        assert_eq!(node.to_string(), "<div id=\"inner\"></div>");
+    }
+}
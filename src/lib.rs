@@ -29,16 +29,36 @@ A copy of this license can be found in the `licenses` directory at the root of t
 
 #![deny(missing_docs, missing_debug_implementations)]
 
+pub mod a11y;
 /// Attributes which can be attached to multiple nodes.
 pub mod attributes;
+#[cfg(feature = "csp")]
+pub mod csp;
+/// Typed CSS, for use in the `style` attribute and in `<style>` tags.
+pub mod css;
+pub mod diff;
+/// Fragments, which group nodes together without a wrapping tag.
+pub mod fragment;
+pub mod ids;
+/// Nodes which are only constructed when they are rendered.
+pub mod lazy;
 /// A list of types which are useful for using the library. Unless you have name
 /// conflicts, we recommend just inserting a `use malvolio::prelude::*;` in
 /// files where you're using Malvolio.
 pub mod prelude;
+/// Nodes containing HTML which has already been rendered.
+pub mod raw;
+pub mod sanitize;
+/// Nodes which can be shared between several trees.
+pub mod shared;
 /// The different HTML tags which Malvolio supports.
 pub mod tags;
 /// A text node.
 pub mod text;
+/// Safe URLs, for use in attributes such as `href`.
+pub mod url;
+pub mod validate;
+pub mod visit;
 
 #[macro_use]
 #[doc(hidden)]
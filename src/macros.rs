@@ -16,11 +16,10 @@ A copy of this license can be found in the `licenses` directory at the root of t
 /// For internal use only.
 macro_rules! heading_display {
     ($name:ident) => {
-        impl std::fmt::Display for $name {
+        impl std::fmt::Display for $name<'_> {
             fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
                 f.write_str("<")?;
                 f.write_str(stringify!($name))?;
-                f.write_str(" ")?;
                 $crate::utils::write_attributes(&self.attrs, f)?;
                 f.write_str(">")?;
                 self.text.fmt(f)?;
@@ -39,55 +38,102 @@ macro_rules! heading_display {
 /// Generates new code to construct a heading.
 macro_rules! impl_of_heading_new_fn {
     ($name:ident, $lowercase_name:ident) => {
+        $crate::impl_of_heading_new_fn!(
+            $name,
+            $lowercase_name,
+            $crate::tags::headings::HeadingAttr<'a>
+        );
+    };
+    ($name:ident, $lowercase_name:ident, $attr:ty) => {
+        $crate::impl_of_text_new_fn!(
+            $name,
+            $lowercase_name,
+            attrs: std::collections::HashMap::new()
+        );
+
+        impl<'a> $name<'a> {
+            /// Attach a new attribute to this node.
+            pub fn attribute<A>(mut self, a: A) -> Self
+            where
+                A: Into<$attr>,
+            {
+                use $crate::attributes::IntoAttributes;
+                $crate::utils::insert_attributes(&mut self.attrs, a.into().into_attributes());
+                self
+            }
+
+            $crate::define_attribute_helper_fns!($attr);
+
+            $crate::define_raw_attribute_fn!();
+
+            /// Read an attribute that has been set.
+            pub fn read_attribute(&self, a: &str) -> Option<&Cow<'a, str>> {
+                self.attrs.get(a)
+            }
+        }
+
+        $crate::impl_attributes!($name);
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+/// For internal use only.
+///
+/// Generates new code to construct a node which contains text (the other
+/// fields of the node, if there are any, are set to the provided values).
+macro_rules! impl_of_text_new_fn {
+    ($name:ident, $lowercase_name:ident $(, $field:ident: $value:expr)*) => {
         /// Create a new instance of the tag in question. Equivalent to `<tag
         /// name>::new(<text>)`, but easier to type (and therefore hopefully more
         /// ergonomic.)
-        pub fn $lowercase_name(text: impl ToString) -> $name {
+        pub fn $lowercase_name<'a>(text: impl Into<Cow<'a, str>>) -> $name<'a> {
             $name::new(text)
         }
-        impl $name {
-            /// Create a new item of this type, given an item which can be converted
-            /// into a `Cow<'static, str>` (for example a `&str` or a `String`).
+        impl<'a> $name<'a> {
+            /// Create a new item of this type, given some text (which is only
+            /// copied if it needs to be escaped), escaping it first (so that any
+            /// HTML in the text is displayed as it is, rather than being
+            /// interpreted).
             pub fn new<S>(from: S) -> Self
             where
-                S: ToString,
+                S: Into<Cow<'a, str>>,
             {
                 Self {
-                    text: From::from(::ammonia::clean(&from.to_string())),
-                    attrs: std::collections::HashMap::new(),
+                    text: $crate::utils::escape_text(from.into()),
+                    $($field: $value,)*
                 }
             }
-            /// Create a new item of this type **without first sanitizing the text**.
-            /// You only want this if you are certain that the text in question is
-            /// safe (i.e. will not execute malicious Javascript when run.)
-            pub fn new_unchecked<S>(from: S) -> Self
+            /// Create a new item of this type from some HTML (e.g. user-supplied
+            /// rich text), sanitizing it first (using the policy set with
+            /// `SanitizePolicy::scope`, or `ammonia`'s default policy).
+            pub fn html<S>(from: S) -> Self
             where
-                S: Into<Cow<'static, str>>,
+                S: ToString,
             {
                 Self {
-                    text: from.into(),
-                    attrs: std::collections::HashMap::new(),
+                    text: From::from($crate::sanitize::clean(&from.to_string())),
+                    $($field: $value,)*
                 }
             }
-            /// Attach a new attribute to this node.
-            pub fn attribute<A>(mut self, a: A) -> Self
+            /// Create a new item of this type from some HTML, sanitizing it
+            /// according to the provided policy.
+            pub fn with_policy<S>(from: S, policy: &$crate::sanitize::SanitizePolicy) -> Self
             where
-                A: Into<$crate::tags::headings::HeadingAttr>,
+                S: ToString,
             {
-                use $crate::attributes::IntoAttribute;
-                let (a, b) = a.into().into_attribute();
-                self.attrs.insert(a, b);
-                self
+                Self {
+                    text: From::from(policy.clean(&from.to_string())),
+                    $($field: $value,)*
+                }
             }
-
-            $crate::define_raw_attribute_fn!();
-
-            /// Read an attribute that has been set.
-            pub fn read_attribute(
-                &self,
-                a: impl Into<Cow<'static, str>>,
-            ) -> Option<&Cow<'static, str>> {
-                self.attrs.get(&a.into())
+            /// Create a new item of this type from some HTML **without sanitizing
+            /// it** (see [`TrustedHtml`]($crate::sanitize::TrustedHtml)).
+            pub fn new_unchecked(html: $crate::sanitize::TrustedHtml<'a>) -> Self {
+                Self {
+                    text: html.into(),
+                    $($field: $value,)*
+                }
             }
             /// Applies the provided function to this item.
             pub fn map<F>(mut self, mapping: F) -> Self
@@ -99,8 +145,8 @@ macro_rules! impl_of_heading_new_fn {
             }
         }
 
-        impl From<&'static str> for $name {
-            fn from(string: &'static str) -> Self {
+        impl<'a> From<&'a str> for $name<'a> {
+            fn from(string: &'a str) -> Self {
                 $name::new(string)
             }
         }
@@ -112,10 +158,10 @@ macro_rules! impl_of_heading_new_fn {
 /// For internal use only.
 macro_rules! enum_display {
     ($on:ident, $($variant:ident),*) => {
-        impl std::fmt::Display for $on {
+        impl std::fmt::Display for $on<'_> {
             fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
                 match self {
-                    $(Self::$variant(x) => <$variant as std::fmt::Display>::fmt(&x.clone(), f)),*,
+                    $(Self::$variant(x) => std::fmt::Display::fmt(x, f)),*,
                     #[allow(unreachable_patterns)]
                     _ => panic!("Virtual components are not supported.")
                 }
@@ -126,12 +172,35 @@ macro_rules! enum_display {
 
 #[macro_export]
 #[doc(hidden)]
+/// Implements `Attributes` for a grouping enum by delegating to its variants.
+/// The variants listed after the `;` can not have any attributes.
+///
 /// For internal use only.
-macro_rules! into_grouping_union {
-    ($name:ident, $enum_name:ident) => {
-        impl From<$name> for $enum_name {
-            fn from(t: $name) -> $enum_name {
-                $enum_name::$name(t)
+macro_rules! enum_attributes {
+    ($on:ident, $($variant:ident),* $(; $($without:ident),*)?) => {
+        impl<'a> $crate::attributes::Attributes<'a> for $on<'a> {
+            fn attribute_map(
+                &self,
+            ) -> &std::collections::HashMap<std::borrow::Cow<'a, str>, std::borrow::Cow<'a, str>>
+            {
+                match self {
+                    $(Self::$variant(x) => $crate::attributes::Attributes::attribute_map(x),)*
+                    $($(Self::$without(_) => $crate::attributes::empty_attribute_map(),)*)?
+                }
+            }
+
+            fn attribute_map_mut(
+                &mut self,
+            ) -> Option<
+                &mut std::collections::HashMap<
+                    std::borrow::Cow<'a, str>,
+                    std::borrow::Cow<'a, str>,
+                >,
+            > {
+                match self {
+                    $(Self::$variant(x) => $crate::attributes::Attributes::attribute_map_mut(x),)*
+                    $($(Self::$without(_) => None,)*)?
+                }
             }
         }
     };
@@ -139,11 +208,11 @@ macro_rules! into_grouping_union {
 
 #[macro_export]
 #[doc(hidden)]
-/// For intenal use only.
-macro_rules! into_grouping_union_without_lifetimes {
+/// For internal use only.
+macro_rules! into_grouping_union {
     ($name:ident, $enum_name:ident) => {
-        impl From<$name> for $enum_name {
-            fn from(t: $name) -> $enum_name {
+        impl<'a> From<$name<'a>> for $enum_name<'a> {
+            fn from(t: $name<'a>) -> $enum_name<'a> {
                 $enum_name::$name(t)
             }
         }
@@ -160,8 +229,8 @@ macro_rules! define_raw_attribute_fn {
         /// takes better advantage of Rust's type system.
         pub fn raw_attribute(
             mut self,
-            key: impl Into<Cow<'static, str>>,
-            value: impl Into<Cow<'static, str>>,
+            key: impl Into<Cow<'a, str>>,
+            value: impl Into<Cow<'a, str>>,
         ) -> Self {
             self.attrs.insert(key.into(), value.into());
             self
@@ -171,33 +240,299 @@ macro_rules! define_raw_attribute_fn {
 
 #[macro_export]
 #[doc(hidden)]
+/// Generates code to convert an attribute into a grouping enum.
+///
 /// For internal use only.
-macro_rules! add_single_attribute {
-    ($lifetime:tt) => {
-        #[inline(always)]
-        pub fn attribute(mut self, k: & $lifetime str, v: & $lifetime str) -> Self {
-            self.attrs.push((k, v));
-            self
+macro_rules! into_attribute_for_grouping_enum {
+    ($name:ident, $($variant:ident),*) => {
+        impl<'a> $crate::attributes::IntoAttribute<'a> for $name<'a> {
+            fn into_attribute(self) -> (std::borrow::Cow<'a, str>, std::borrow::Cow<'a, str>) {
+                match self {
+                    $(
+                        Self::$variant(x) => {$crate::attributes::IntoAttribute::into_attribute(x)}
+                    ),*
+
+                }
+            }
         }
     };
 }
 
 #[macro_export]
 #[doc(hidden)]
-/// Generates code to convert an attribute into a grouping enum.
+/// Generates the attribute grouping enum for a tag. As well as the
+/// tag-specific attributes which are listed, the generated enum accepts all
+/// of the HTML global attributes (`Id`, `Class`, `Style` and so on).
 ///
 /// For internal use only.
-macro_rules! into_attribute_for_grouping_enum {
-    ($name:ident, $($variant:ident),*) => {
-        impl $crate::attributes::IntoAttribute for $name {
-            fn into_attribute(self) -> (std::borrow::Cow<'static, str>, std::borrow::Cow<'static, str>) {
+macro_rules! attribute_enum {
+    ($(#[$meta:meta])* pub enum $name:ident { $($variant:ident $(<$lt:lifetime>)?),* $(,)? }) => {
+        $crate::attribute_enum!(
+            @expand $(#[$meta])* $name;
+            $($variant => $variant $(<$lt>)?,)*
+            Id => $crate::attributes::common::Id<'a>,
+            Class => $crate::attributes::common::Class<'a>,
+            Style => $crate::attributes::common::Style<'a>,
+            TitleAttr => $crate::attributes::common::TitleAttr<'a>,
+            Lang => $crate::attributes::common::Lang<'a>,
+            Dir => $crate::attributes::common::Dir,
+            Hidden => $crate::attributes::common::Hidden,
+            TabIndex => $crate::attributes::common::TabIndex,
+            AccessKey => $crate::attributes::common::AccessKey,
+            ContentEditable => $crate::attributes::common::ContentEditable,
+            Draggable => $crate::attributes::common::Draggable,
+            SpellCheck => $crate::attributes::common::SpellCheck,
+            Translate => $crate::attributes::common::Translate,
+            Inert => $crate::attributes::common::Inert,
+            Popover => $crate::attributes::common::Popover,
+            Autofocus => $crate::attributes::common::Autofocus,
+            Nonce => $crate::attributes::common::Nonce<'a>,
+            Data => $crate::attributes::data::Data<'a>,
+            Role => $crate::attributes::aria::Role,
+            AriaLabel => $crate::attributes::aria::AriaLabel<'a>,
+            AriaRoleDescription => $crate::attributes::aria::AriaRoleDescription<'a>,
+            AriaPlaceholder => $crate::attributes::aria::AriaPlaceholder<'a>,
+            AriaKeyShortcuts => $crate::attributes::aria::AriaKeyShortcuts<'a>,
+            AriaValueText => $crate::attributes::aria::AriaValueText<'a>,
+            AriaLabelledBy => $crate::attributes::aria::AriaLabelledBy<'a>,
+            AriaDescribedBy => $crate::attributes::aria::AriaDescribedBy<'a>,
+            AriaControls => $crate::attributes::aria::AriaControls<'a>,
+            AriaOwns => $crate::attributes::aria::AriaOwns<'a>,
+            AriaFlowTo => $crate::attributes::aria::AriaFlowTo<'a>,
+            AriaErrorMessage => $crate::attributes::aria::AriaErrorMessage<'a>,
+            AriaActiveDescendant => $crate::attributes::aria::AriaActiveDescendant<'a>,
+            AriaHidden => $crate::attributes::aria::AriaHidden,
+            AriaExpanded => $crate::attributes::aria::AriaExpanded,
+            AriaDisabled => $crate::attributes::aria::AriaDisabled,
+            AriaRequired => $crate::attributes::aria::AriaRequired,
+            AriaReadOnly => $crate::attributes::aria::AriaReadOnly,
+            AriaSelected => $crate::attributes::aria::AriaSelected,
+            AriaModal => $crate::attributes::aria::AriaModal,
+            AriaMultiline => $crate::attributes::aria::AriaMultiline,
+            AriaMultiSelectable => $crate::attributes::aria::AriaMultiSelectable,
+            AriaAtomic => $crate::attributes::aria::AriaAtomic,
+            AriaBusy => $crate::attributes::aria::AriaBusy,
+            AriaChecked => $crate::attributes::aria::AriaChecked,
+            AriaPressed => $crate::attributes::aria::AriaPressed,
+            AriaLive => $crate::attributes::aria::AriaLive,
+            AriaCurrent => $crate::attributes::aria::AriaCurrent,
+            AriaHasPopup => $crate::attributes::aria::AriaHasPopup,
+            AriaInvalid => $crate::attributes::aria::AriaInvalid,
+            AriaSort => $crate::attributes::aria::AriaSort,
+            AriaOrientation => $crate::attributes::aria::AriaOrientation,
+            AriaAutocomplete => $crate::attributes::aria::AriaAutocomplete,
+            AriaLevel => $crate::attributes::aria::AriaLevel,
+        );
+    };
+    (@expand $(#[$meta:meta])* $name:ident; $($variant:ident => $ty:ty,)*) => {
+        $crate::utility_enum!(
+            $(#[$meta])*
+            #[allow(missing_docs)]
+            pub enum $name<'a> {
+                $($variant($ty)),*
+            }
+        );
+
+        impl<'a> $crate::attributes::IntoAttributes<'a> for $name<'a> {
+            fn into_attributes(self) -> Vec<(std::borrow::Cow<'a, str>, std::borrow::Cow<'a, str>)> {
                 match self {
                     $(
-                        Self::$variant(x) => {$crate::attributes::IntoAttribute::into_attribute(x)}
+                        Self::$variant(x) => $crate::attributes::IntoAttributes::into_attributes(x)
                     ),*
+                }
+            }
+        }
 
+        $(
+            impl<'a> From<$ty> for $name<'a> {
+                fn from(t: $ty) -> $name<'a> {
+                    $name::$variant(t)
                 }
             }
+        )*
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+/// Generates the `replace_attribute`, `add_class`, `remove_class` and
+/// `toggle_class` methods for a tag with the given attribute enum.
+///
+/// For internal use only.
+macro_rules! define_attribute_helper_fns {
+    ($attr:ty) => {
+        /// Attach an attribute to this tag, overwriting any existing value for
+        /// the attribute. Unlike `attribute`, this does not merge the `class`
+        /// or `style` attributes with their existing values.
+        pub fn replace_attribute<A>(mut self, attribute: A) -> Self
+        where
+            A: Into<$attr>,
+        {
+            use $crate::attributes::IntoAttributes;
+            self.attrs.extend(attribute.into().into_attributes());
+            self
+        }
+
+        /// Add a class to this tag (keeping any existing classes).
+        pub fn add_class<C>(mut self, class: C) -> Self
+        where
+            C: Into<std::borrow::Cow<'a, str>>,
+        {
+            $crate::utils::insert_attributes(&mut self.attrs, vec![("class".into(), class.into())]);
+            self
+        }
+
+        /// Remove a class from this tag (if it has been added).
+        pub fn remove_class(mut self, class: &str) -> Self {
+            $crate::utils::remove_class(&mut self.attrs, class);
+            self
+        }
+
+        /// Add the class to this tag if `enabled` is true, and otherwise remove
+        /// it.
+        ///
+        /// ```
+        /// # use malvolio::prelude::*;
+        /// let is_active = true;
+        /// Div::new().toggle_class("active", is_active);
+        /// ```
+        pub fn toggle_class<C>(self, class: C, enabled: bool) -> Self
+        where
+            C: Into<std::borrow::Cow<'a, str>>,
+        {
+            if enabled {
+                self.add_class(class)
+            } else {
+                self.remove_class(&class.into())
+            }
+        }
+
+        /// Set the `id` of this tag, returning the tag along with an
+        /// [`IdRef`](crate::attributes::common::IdRef), which can be used to
+        /// refer to it from another tag (for example, with the `for` attribute
+        /// of a `<label>`).
+        pub fn with_id<C>(mut self, id: C) -> (Self, $crate::attributes::common::IdRef<'a>)
+        where
+            C: Into<std::borrow::Cow<'a, str>>,
+        {
+            let id = $crate::attributes::common::IdRef::new(id.into());
+            self.attrs.insert("id".into(), id.clone().into());
+            (self, id)
+        }
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+/// Implements `Attributes` for a tag which stores its attributes in a field
+/// called `attrs`.
+///
+/// For internal use only.
+macro_rules! impl_attributes {
+    ($name:ident) => {
+        impl<'a> $crate::attributes::Attributes<'a> for $name<'a> {
+            fn attribute_map(
+                &self,
+            ) -> &std::collections::HashMap<std::borrow::Cow<'a, str>, std::borrow::Cow<'a, str>>
+            {
+                &self.attrs
+            }
+
+            fn attribute_map_mut(
+                &mut self,
+            ) -> Option<
+                &mut std::collections::HashMap<
+                    std::borrow::Cow<'a, str>,
+                    std::borrow::Cow<'a, str>,
+                >,
+            > {
+                Some(&mut self.attrs)
+            }
+        }
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+/// Implements `FromAttribute` for an attribute which wraps a string and has a
+/// `new` constructor.
+///
+/// For internal use only.
+macro_rules! from_attribute_via_new {
+    ($name:ident, $key:literal) => {
+        impl $crate::attributes::FromAttribute for $name<'static> {
+            const NAME: &'static str = $key;
+
+            fn from_attribute(value: &str) -> Option<Self> {
+                Some(Self::new(value.to_string()))
+            }
+        }
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+/// Generates the `child_nodes` and `child_nodes_mut` accessors for a tag which
+/// stores its children in a field called `children`.
+///
+/// For internal use only.
+macro_rules! define_child_node_accessors {
+    ($node:ty) => {
+        /// Returns the children which have been attached to this tag.
+        pub fn child_nodes(&self) -> &[$node] {
+            &self.children
+        }
+
+        /// Returns a mutable reference to the children of this tag, which can
+        /// be used to add, remove or modify children after the tag has been
+        /// constructed.
+        pub fn child_nodes_mut(&mut self) -> &mut Vec<$node> {
+            &mut self.children
+        }
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+/// Generates the `child_if` and `children_if` methods for a tag which has
+/// `child` and `children` methods.
+///
+/// For internal use only.
+macro_rules! define_conditional_child_fns {
+    ($node:ty) => {
+        /// Add a single child to this tag, but only if `condition` is true (the
+        /// child is only constructed if it will be added).
+        ///
+        /// ```
+        /// # use malvolio::prelude::*;
+        /// let logged_in = false;
+        /// Div::new().child_if(!logged_in, || A::new().attribute(Href::new("/login")));
+        /// ```
+        pub fn child_if<F, C>(self, condition: bool, child: F) -> Self
+        where
+            F: FnOnce() -> C,
+            C: Into<$node>,
+        {
+            if condition {
+                self.child(child())
+            } else {
+                self
+            }
+        }
+
+        /// Add a number of children to this tag, but only if `condition` is
+        /// true (the children are only constructed if they will be added).
+        pub fn children_if<F, I, C>(self, condition: bool, children: F) -> Self
+        where
+            F: FnOnce() -> I,
+            I: IntoIterator<Item = C>,
+            C: Into<$node>,
+        {
+            if condition {
+                self.children(children())
+            } else {
+                self
+            }
         }
     };
 }
@@ -6,15 +6,15 @@ pub use crate::tags::{
     a::{a, Href, A},
     body::body_node::BodyNode,
     body::{body, Body},
-    br::Br,
+    br::{br, Br},
     div::{div, Div},
     form::{form, Action, Form, Method},
-    head::{head, Head},
+    head::{head, head_node::HeadNode, Head},
     headings::{h1, h2, h3, h4, h5, h6, H1, H2, H3, H4, H5, H6},
     html::{html, Html},
     img::{img, Alt, Img, Src},
-    input::{input, Input, Name, Placeholder, Type, Value},
-    label::{label, Label},
+    input::{input, Input, List, Name, Placeholder, Type, Value},
+    label::{label, For, Label},
     meta::{meta, Content, Meta, MetaName},
     option::{select_option, SelectOption},
     p::{p, P},
@@ -23,4 +23,30 @@ pub use crate::tags::{
     title::{title, Title},
 };
 
-pub use crate::attributes::common::{Class, Id, Style};
+pub use crate::fragment::{fragment, Fragment};
+
+pub use crate::lazy::{lazy, Lazy};
+
+pub use crate::shared::{shared, Shared};
+
+pub use crate::raw::{raw, Raw};
+
+pub use crate::sanitize::TrustedHtml;
+
+pub use crate::url::SafeUrl;
+
+pub use crate::attributes::data::Data;
+
+pub use crate::attributes::aria::{
+    AriaActiveDescendant, AriaAtomic, AriaAutocomplete, AriaBusy, AriaChecked, AriaControls,
+    AriaCurrent, AriaDescribedBy, AriaDisabled, AriaErrorMessage, AriaExpanded, AriaFlowTo,
+    AriaHasPopup, AriaHidden, AriaInvalid, AriaKeyShortcuts, AriaLabel, AriaLabelledBy, AriaLevel,
+    AriaLive, AriaModal, AriaMultiSelectable, AriaMultiline, AriaOrientation, AriaOwns,
+    AriaPlaceholder, AriaPressed, AriaReadOnly, AriaRequired, AriaRoleDescription, AriaSelected,
+    AriaSort, AriaValueText, Role, Tristate,
+};
+
+pub use crate::attributes::common::{
+    AccessKey, Autofocus, Class, ContentEditable, Dir, Draggable, Hidden, Id, IdRef, Inert, Lang,
+    Nonce, Popover, SpellCheck, Style, TabIndex, TitleAttr, Translate,
+};
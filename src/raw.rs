@@ -0,0 +1,101 @@
+/*
+This source code file is distributed subject to the terms of the Mozilla Public License v2.0.
+A copy of this license can be found in the `licenses` directory at the root of this project.
+*/
+use std::fmt::Display;
+
+use crate::{into_grouping_union, sanitize::TrustedHtml, tags::body::body_node::BodyNode};
+
+#[derive(Debug, Clone)]
+#[must_use]
+/// Some HTML which has already been rendered (for example by a template
+/// engine, or a Markdown renderer), which is added to the document as it is.
+///
+/// ```
+/// # use malvolio::prelude::*;
+/// let html = TrustedHtml::sanitize("<em>Hello</em> <script>alert(1)</script>");
+/// let body = Body::new().child(Raw::new(html));
+/// assert_eq!(body.to_string(), "<body><em>Hello</em> </body>");
+/// ```
+///
+/// The HTML is opaque to everything which inspects the tree (for example the
+/// visitors in the `visit` module and `select` do not see inside it). Raw
+/// nodes do not have any attributes of their own, so setting an attribute on a
+/// `BodyNode::Raw` returns an error.
+pub struct Raw<'a> {
+    html: TrustedHtml<'a>,
+}
+
+impl<'a> Raw<'a> {
+    /// Creates a new [`Raw`] node.
+    pub fn new(html: TrustedHtml<'a>) -> Self {
+        Self { html }
+    }
+
+    /// Returns the HTML which this node contains.
+    pub fn html(&self) -> &TrustedHtml<'a> {
+        &self.html
+    }
+}
+
+/// Creates a new `Raw` node – functionally equivalent to `Raw::new(html)` (but
+/// easier to type.)
+pub fn raw(html: TrustedHtml<'_>) -> Raw<'_> {
+    Raw::new(html)
+}
+
+impl Display for Raw<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.html.fmt(f)
+    }
+}
+
+into_grouping_union!(Raw, BodyNode);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        diff::{diff, Patch},
+        prelude::*,
+    };
+
+    #[test]
+    fn test_raw() {
+        let markdown = |html| Raw::new(TrustedHtml::dangerously_trust(html));
+        let old = Body::new().child(Div::new().child(markdown("<p>a</p>")));
+        assert_eq!(old.to_string(), "<body><div><p>a</p></div></body>");
+        assert!(old.select("p").is_empty());
+
+        let escaped = TrustedHtml::escape("<p>");
+        assert_eq!(raw(escaped).to_string(), "&lt;p&gt;");
+
+        let new = Body::new().child(Div::new().child(markdown("<p>b</p>")));
+        assert!(diff(&old, &old.clone()).is_empty());
+        // the raw node can contain any number of nodes, so the contents of its
+        // parent are replaced
+        assert_eq!(
+            diff(&old, &new),
+            vec![Patch::ReplaceChildren {
+                path: vec![0],
+                html: "<p>b</p>".to_string()
+            }]
+        );
+        assert_eq!(
+            diff(&new, &Body::new().child(Div::new().child(Fragment::new()))),
+            vec![Patch::ReplaceChildren {
+                path: vec![0],
+                html: "".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_raw_has_no_attributes() {
+        use crate::attributes::{Attributes, CannotHaveAttributes};
+
+        let mut node = BodyNode::from(raw(TrustedHtml::escape("text")));
+        assert_eq!(node.set_attribute(Id::new("x")), Err(CannotHaveAttributes));
+        assert_eq!(node.get_attribute("id"), None);
+        assert_eq!(node.to_string(), "text");
+    }
+}
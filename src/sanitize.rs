@@ -0,0 +1,543 @@
+/*
+This source code file is distributed subject to the terms of the Mozilla Public License v2.0.
+A copy of this license can be found in the `licenses` directory at the root of this project.
+*/
+//! Control over how text is sanitized.
+//!
+//! The methods which sanitize HTML (such as `Text::html`, `P::with_html` and
+//! `A::html`) use the [`ammonia`] crate to remove any HTML which could be
+//! dangerous. By default they use `ammonia`'s default policy, but a
+//! [`SanitizePolicy`] can be used instead, either for a single call:
+//!
+//! ```
+//! # use malvolio::prelude::*;
+//! use malvolio::sanitize::SanitizePolicy;
+//!
+//! let policy = SanitizePolicy::empty().tags(["b", "i"]);
+//! let p = P::with_policy("<b>bold</b> and <u>underlined</u>", &policy);
+//! assert_eq!(p.to_string(), "<p><b>bold</b> and underlined</p>");
+//! ```
+//!
+//! or for everything which is constructed inside a closure (for example, an
+//! entire document):
+//!
+//! ```
+//! # use malvolio::prelude::*;
+//! use malvolio::sanitize::SanitizePolicy;
+//!
+//! let policy = SanitizePolicy::empty().tags(["b"]);
+//! let body = policy.scope(|| {
+//!     Body::new()
+//!         .child(P::with_html("<b>one</b>"))
+//!         .child(P::with_html("<i>two</i>"))
+//! });
+//! assert_eq!(body.to_string(), "<body><p><b>one</b></p><p>two</p></body>");
+//! ```
+//!
+//! Note that the text is sanitized when the node is constructed, so a policy
+//! set with [`SanitizePolicy::scope`] does not apply to `Lazy` nodes (which are
+//! constructed when they are rendered) unless the document is also rendered
+//! inside the closure.
+
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    sync::{Arc, OnceLock},
+};
+
+thread_local! {
+    static CURRENT: RefCell<Option<SanitizePolicy>> = const { RefCell::new(None) };
+}
+
+#[derive(Debug, Clone)]
+#[must_use]
+/// A policy which determines which HTML is kept when text is sanitized (and
+/// which is removed).
+///
+/// [`SanitizePolicy::new`] returns `ammonia`'s default policy (which is the
+/// one used if no other policy has been set), and [`SanitizePolicy::empty`]
+/// returns a policy which does not allow any tags at all.
+pub struct SanitizePolicy {
+    tags: HashSet<&'static str>,
+    tag_attributes: HashMap<&'static str, HashSet<&'static str>>,
+    generic_attributes: HashSet<&'static str>,
+    url_schemes: HashSet<&'static str>,
+    link_rel: Option<&'static str>,
+    /// The `ammonia::Builder` for this policy, which is created the first time
+    /// that the policy is used (and shared between clones of the policy).
+    builder: Arc<OnceLock<ammonia::Builder<'static>>>,
+}
+
+impl Default for SanitizePolicy {
+    fn default() -> Self {
+        let builder = ammonia::Builder::default();
+        Self {
+            tags: builder.clone_tags(),
+            tag_attributes: builder.clone_tag_attributes(),
+            generic_attributes: builder.clone_generic_attributes(),
+            url_schemes: builder.clone_url_schemes(),
+            link_rel: Some("noopener noreferrer"),
+            builder: Default::default(),
+        }
+    }
+}
+
+impl SanitizePolicy {
+    /// Creates a policy which is the same as `ammonia`'s default policy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a policy which does not allow any tags, attributes or URL
+    /// schemes (so all HTML is removed, but the text inside it is kept).
+    pub fn empty() -> Self {
+        Self {
+            tags: HashSet::new(),
+            tag_attributes: HashMap::new(),
+            generic_attributes: HashSet::new(),
+            url_schemes: HashSet::new(),
+            link_rel: None,
+            builder: Default::default(),
+        }
+    }
+
+    /// Sets the tags which are allowed (replacing the existing list).
+    ///
+    /// `<script>` and `<style>` tags are never allowed by this method (use
+    /// [`SanitizePolicy::dangerously_trust_tags`] to allow them).
+    pub fn tags<I>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = &'static str>,
+    {
+        self.tags = tags.into_iter().filter(|tag| !is_script(tag)).collect();
+        self.changed()
+    }
+
+    /// Allows the provided tags (as well as the tags which are already
+    /// allowed).
+    ///
+    /// `<script>` and `<style>` tags are never allowed by this method (use
+    /// [`SanitizePolicy::dangerously_trust_tags`] to allow them).
+    pub fn add_tags<I>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = &'static str>,
+    {
+        self.tags
+            .extend(tags.into_iter().filter(|tag| !is_script(tag)));
+        self.changed()
+    }
+
+    /// Allows the provided tags **without checking them**, including
+    /// `<script>` and `<style>` tags (whose contents are then kept). Only use
+    /// this if the HTML which is sanitized with this policy is trusted, as it
+    /// can then contain scripts.
+    pub fn dangerously_trust_tags<I>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = &'static str>,
+    {
+        self.tags.extend(tags);
+        self.changed()
+    }
+
+    /// Stops the provided tags from being allowed.
+    pub fn rm_tags<I>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = &'static str>,
+    {
+        for tag in tags {
+            self.tags.remove(tag);
+        }
+        self.changed()
+    }
+
+    /// Allows the provided attributes on the given tag (as well as the
+    /// attributes which are already allowed on it).
+    ///
+    /// Event handler attributes (such as `onclick`), and attributes on
+    /// `<script>` and `<style>` tags, are never allowed by this method (use
+    /// [`SanitizePolicy::dangerously_trust_tag_attributes`] to allow them).
+    pub fn tag_attributes<I>(mut self, tag: &'static str, attributes: I) -> Self
+    where
+        I: IntoIterator<Item = &'static str>,
+    {
+        if !is_script(tag) {
+            self.tag_attributes.entry(tag).or_default().extend(
+                attributes
+                    .into_iter()
+                    .filter(|attribute| !is_event_handler(attribute)),
+            );
+        }
+        self.changed()
+    }
+
+    /// Allows the provided attributes on the given tag **without checking
+    /// them**, including event handler attributes (such as `onclick`). Only
+    /// use this if the HTML which is sanitized with this policy is trusted,
+    /// as it can then contain scripts.
+    pub fn dangerously_trust_tag_attributes<I>(mut self, tag: &'static str, attributes: I) -> Self
+    where
+        I: IntoIterator<Item = &'static str>,
+    {
+        self.tag_attributes
+            .entry(tag)
+            .or_default()
+            .extend(attributes);
+        self.changed()
+    }
+
+    /// Allows the provided attributes on every tag (as well as the attributes
+    /// which are already allowed).
+    ///
+    /// Event handler attributes (such as `onclick`) are never allowed by this
+    /// method.
+    pub fn generic_attributes<I>(mut self, attributes: I) -> Self
+    where
+        I: IntoIterator<Item = &'static str>,
+    {
+        self.generic_attributes.extend(
+            attributes
+                .into_iter()
+                .filter(|attribute| !is_event_handler(attribute)),
+        );
+        self.changed()
+    }
+
+    /// Sets the URL schemes (e.g. `"https"`) which are allowed in links
+    /// (replacing the existing list). Relative URLs are always allowed.
+    pub fn url_schemes<I>(mut self, schemes: I) -> Self
+    where
+        I: IntoIterator<Item = &'static str>,
+    {
+        self.url_schemes = schemes.into_iter().collect();
+        self.changed()
+    }
+
+    /// Sets the value of the `rel` attribute which is added to every link (by
+    /// default this is `"noopener noreferrer"`). If this is `None` the `rel`
+    /// attribute is not added.
+    ///
+    /// If this is not `None` it replaces the `rel` attribute of links, even if
+    /// `rel` has been allowed on `<a>` tags (or on every tag).
+    pub fn link_rel(mut self, rel: Option<&'static str>) -> Self {
+        self.link_rel = rel;
+        self.changed()
+    }
+
+    /// Sanitizes the HTML according to this policy.
+    pub fn clean(&self, html: &str) -> TrustedHtml<'static> {
+        TrustedHtml(
+            self.builder
+                .get_or_init(|| self.build())
+                .clean(html)
+                .to_string()
+                .into(),
+        )
+    }
+
+    /// Calls the function, using this policy (in place of the default policy)
+    /// to sanitize any HTML which is sanitized on the current thread while the
+    /// function runs.
+    ///
+    /// Methods which are passed a policy explicitly (such as
+    /// `Text::with_policy`) use that policy instead.
+    pub fn scope<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        /// Restores the previous policy (even if the function panics).
+        struct Reset(Option<SanitizePolicy>);
+
+        impl Drop for Reset {
+            fn drop(&mut self) {
+                let previous = self.0.take();
+                CURRENT.with(|current| *current.borrow_mut() = previous);
+            }
+        }
+
+        let _reset = Reset(CURRENT.with(|current| current.replace(Some(self.clone()))));
+        f()
+    }
+
+    /// Throws away the cached `ammonia::Builder`, as the policy has changed.
+    fn changed(mut self) -> Self {
+        self.builder = Default::default();
+        self
+    }
+
+    /// Creates the `ammonia::Builder` for this policy, adjusting the policy
+    /// where `ammonia` would otherwise panic.
+    fn build(&self) -> ammonia::Builder<'static> {
+        let mut builder = ammonia::Builder::default();
+        let mut tags = self.tags.clone();
+        let mut tag_attributes = self.tag_attributes.clone();
+        let mut generic_attributes = self.generic_attributes.clone();
+        // `ammonia` does not support SVG animation
+        for tag in [
+            "animate",
+            "animateTransform",
+            "animateMotion",
+            "animateColor",
+            "set",
+        ] {
+            tags.remove(tag);
+        }
+        // tags whose contents are removed (such as `<script>`) must not be
+        // allowed, so tags which have been allowed are no longer removed
+        let clean_content_tags = builder
+            .clone_clean_content_tags()
+            .into_iter()
+            .filter(|tag| !tags.contains(tag) && !tag_attributes.contains_key(tag))
+            .collect();
+        // the `rel` attribute of links is replaced by `link_rel`, so it can only
+        // be allowed on other tags
+        if self.link_rel.is_some() {
+            if generic_attributes.remove("rel") {
+                for tag in tags.iter().filter(|tag| **tag != "a") {
+                    tag_attributes.entry(tag).or_default().insert("rel");
+                }
+            }
+            if let Some(attributes) = tag_attributes.get_mut("a") {
+                attributes.remove("rel");
+            }
+        }
+        builder
+            .tags(tags)
+            .clean_content_tags(clean_content_tags)
+            .tag_attributes(tag_attributes)
+            .generic_attributes(generic_attributes)
+            .url_schemes(self.url_schemes.clone())
+            .link_rel(self.link_rel);
+        builder
+    }
+}
+
+/// Whether the tag can contain scripts (or styles), so that the policy should
+/// only allow it if it has been explicitly trusted.
+fn is_script(tag: &str) -> bool {
+    tag.eq_ignore_ascii_case("script") || tag.eq_ignore_ascii_case("style")
+}
+
+/// Whether the attribute is an event handler (such as `onclick`).
+fn is_event_handler(attribute: &str) -> bool {
+    attribute
+        .get(..2)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case("on"))
+}
+
+/// Sanitizes the HTML using the policy set with [`SanitizePolicy::scope`] (or
+/// `ammonia`'s default policy, if no policy has been set).
+pub(crate) fn clean(html: &str) -> TrustedHtml<'static> {
+    CURRENT.with(|current| match &*current.borrow() {
+        Some(policy) => policy.clean(html),
+        None => TrustedHtml(ammonia::clean(html).into()),
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[must_use]
+/// HTML which is known to be safe to render as it is.
+///
+/// The methods which add HTML to a document without sanitizing it (such as
+/// `Text::new_unchecked` and `A::text_unsanitized`), as well as the [`Raw`]
+/// node, only accept `TrustedHtml`. A `TrustedHtml` can only be created by
+/// sanitizing (or escaping) some text, or by calling
+/// [`TrustedHtml::dangerously_trust`] (or sanitizing it with a policy which
+/// has been told to trust scripts with one of the `dangerously_trust_*`
+/// methods of [`SanitizePolicy`]), so searching for `dangerously_trust` finds
+/// every place where unchecked HTML enters a document.
+///
+/// ```
+/// # use malvolio::prelude::*;
+/// let html = TrustedHtml::sanitize("<b>bold</b><script>alert(1)</script>");
+/// assert_eq!(html.as_str(), "<b>bold</b>");
+///
+/// let rendered = TrustedHtml::dangerously_trust("<p>Rendered elsewhere</p>");
+/// let div = Div::new().child(Raw::new(rendered));
+/// assert_eq!(div.to_string(), "<div><p>Rendered elsewhere</p></div>");
+/// ```
+///
+/// [`Raw`]: crate::raw::Raw
+pub struct TrustedHtml<'a>(Cow<'a, str>);
+
+impl<'a> TrustedHtml<'a> {
+    /// Marks the HTML as trusted **without checking it**. Only use this for
+    /// HTML which you are certain is safe (i.e. HTML which does not contain
+    /// any user-supplied content, or which has already been sanitized).
+    pub fn dangerously_trust<S>(html: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        Self(html.into())
+    }
+
+    /// Sanitizes the HTML (using the policy set with
+    /// [`SanitizePolicy::scope`], or `ammonia`'s default policy). Use
+    /// [`SanitizePolicy::clean`] to sanitize HTML with a specific policy.
+    pub fn sanitize(html: &str) -> Self {
+        clean(html)
+    }
+
+    /// Escapes the text, so that it is displayed as it is (rather than being
+    /// interpreted as HTML).
+    pub fn escape<S>(text: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        Self(crate::utils::escape_text(text.into()))
+    }
+
+    /// Returns the HTML.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the HTML.
+    pub fn into_inner(self) -> Cow<'a, str> {
+        self.0
+    }
+}
+
+impl<'a> From<TrustedHtml<'a>> for Cow<'a, str> {
+    fn from(html: TrustedHtml<'a>) -> Self {
+        html.0
+    }
+}
+
+impl Display for TrustedHtml<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    use super::SanitizePolicy;
+    use crate::{prelude::*, text::Text};
+
+    #[test]
+    fn test_policies() {
+        let html = "<a href=\"javascript:alert(1)\">x</a><a href=\"ftp://a\">y</a><u>z</u>";
+        assert_eq!(
+            Text::html(html).to_string(),
+            SanitizePolicy::new().clean(html).as_str()
+        );
+
+        let policy = SanitizePolicy::empty()
+            .tags(["a"])
+            .tag_attributes("a", ["href"])
+            .url_schemes(["ftp"])
+            .link_rel(None);
+        assert_eq!(
+            A::new().html_with_policy(html, &policy).to_string(),
+            "<a><a>x</a><a href=\"ftp://a\">y</a>z</a>"
+        );
+
+        let policy = SanitizePolicy::new().rm_tags(["u"]).add_tags(["s"]);
+        assert_eq!(policy.clean("<u>a</u><s>b</s>").as_str(), "a<s>b</s>");
+    }
+
+    #[test]
+    fn test_rejecting_scripts() {
+        let html = "<script>a()</script><style>b {}</style><b onclick=\"c()\">d</b>";
+        assert_eq!(SanitizePolicy::new().clean(html).as_str(), "<b>d</b>");
+        assert_eq!(
+            SanitizePolicy::new()
+                .add_tags(["script", "STYLE"])
+                .tag_attributes("b", ["onclick"])
+                .generic_attributes(["OnClick"])
+                .clean(html)
+                .as_str(),
+            "<b>d</b>"
+        );
+        assert_eq!(
+            SanitizePolicy::empty()
+                .tags(["script", "style", "set", "b"])
+                .tag_attributes("style", ["media"])
+                .clean(html)
+                .as_str(),
+            "<b>d</b>"
+        );
+    }
+
+    #[test]
+    fn test_dangerously_trusting_scripts() {
+        let html = "<script>a()</script><style>b {}</style><b onclick=\"c()\">d</b>";
+        assert_eq!(
+            SanitizePolicy::new()
+                .dangerously_trust_tags(["script"])
+                .dangerously_trust_tag_attributes("b", ["onclick"])
+                .clean(html)
+                .as_str(),
+            "<script>a()</script><b onclick=\"c()\">d</b>"
+        );
+        assert_eq!(
+            SanitizePolicy::empty()
+                .dangerously_trust_tag_attributes("style", ["media"])
+                .clean(html)
+                .as_str(),
+            "b {}d"
+        );
+        assert_eq!(
+            SanitizePolicy::empty()
+                .dangerously_trust_tags(["style", "set"])
+                .clean(html)
+                .as_str(),
+            "<style>b {}</style>d"
+        );
+    }
+
+    #[test]
+    fn test_allowing_rel_with_link_rel() {
+        let html = "<a href=\"/\" rel=\"next\">a</a><b rel=\"x\">b</b>";
+        assert_eq!(
+            SanitizePolicy::new()
+                .tag_attributes("a", ["rel"])
+                .clean(html)
+                .as_str(),
+            "<a href=\"/\" rel=\"noopener noreferrer\">a</a><b>b</b>"
+        );
+        assert_eq!(
+            SanitizePolicy::new()
+                .generic_attributes(["rel"])
+                .clean(html)
+                .as_str(),
+            "<a href=\"/\" rel=\"noopener noreferrer\">a</a><b rel=\"x\">b</b>"
+        );
+        assert_eq!(
+            SanitizePolicy::new()
+                .tag_attributes("a", ["rel"])
+                .link_rel(None)
+                .clean(html)
+                .as_str(),
+            "<a href=\"/\" rel=\"next\">a</a><b>b</b>"
+        );
+    }
+
+    #[test]
+    fn test_scope() {
+        let policy = SanitizePolicy::empty();
+        let (inside, nested) = policy.scope(|| {
+            let nested = SanitizePolicy::empty()
+                .tags(["b"])
+                .scope(|| H1::html("<b>a</b>").to_string());
+            (H1::html("<b>a</b>").to_string(), nested)
+        });
+        assert_eq!(inside, "<H1>a</H1>");
+        assert_eq!(nested, "<H1><b>a</b></H1>");
+        assert_eq!(H1::html("<b>a</b>").to_string(), "<H1><b>a</b></H1>");
+        assert_eq!(
+            SelectOption::new()
+                .html_with_policy("<b>a</b>", &policy)
+                .to_string(),
+            "<option>a</option>"
+        );
+
+        let result = catch_unwind(AssertUnwindSafe(|| policy.scope(|| panic!())));
+        assert!(result.is_err());
+        assert_eq!(P::with_html("<b>a</b>").to_string(), "<p><b>a</b></p>");
+    }
+}
@@ -0,0 +1,146 @@
+/*
+This source code file is distributed subject to the terms of the Mozilla Public License v2.0.
+A copy of this license can be found in the `licenses` directory at the root of this project.
+*/
+use std::{borrow::Cow, collections::HashMap, fmt::Display, sync::Arc};
+
+use crate::{attributes::Attributes, into_grouping_union, tags::body::body_node::BodyNode};
+
+#[derive(Debug, Clone)]
+/// A node which can be shared between several trees without being copied.
+/// Cloning a `Shared` node (or a tree containing one) only increments a
+/// reference count, so this is useful for parts of a page (such as headers and
+/// footers) which are the same on every page.
+///
+/// `Shared` is `Send` and `Sync`, so a node can be built once and then reused
+/// for every request (for example by storing it in a `static`, or in the state
+/// of your web framework).
+///
+/// ```
+/// # use malvolio::prelude::*;
+/// let nav = Shared::new(Div::new().attribute(Class::from("nav")).child(A::new().text("Home")));
+///
+/// let page = |content: &'static str| {
+///     Body::new().child(nav.clone()).child(P::with_text(content))
+/// };
+/// assert_eq!(
+///     page("Hello").to_string(),
+///     "<body><div class=\"nav\"><a>Home</a></div><p>Hello</p></body>"
+/// );
+/// ```
+///
+/// When a tree is inspected, a shared node appears as a wrapper whose only
+/// child is the node it contains (`select` looks straight through it). The
+/// contained node can be modified with [`Shared::make_mut`] (which `VisitorMut`
+/// uses to descend into shared nodes) or through the `Attributes` trait, in
+/// which case the node is copied first if it is still being shared.
+pub struct Shared<'a> {
+    node: Arc<BodyNode<'a>>,
+}
+
+impl<'a> Shared<'a> {
+    /// Creates a new [`Shared`] node.
+    pub fn new<N>(node: N) -> Self
+    where
+        N: Into<BodyNode<'a>>,
+    {
+        Self {
+            node: Arc::new(node.into()),
+        }
+    }
+
+    /// Returns the node which is being shared.
+    pub fn get(&self) -> &BodyNode<'a> {
+        &self.node
+    }
+
+    /// Returns a mutable reference to the node which is being shared, copying
+    /// it first if it is still shared with another tree (so the other trees
+    /// are not changed).
+    pub fn make_mut(&mut self) -> &mut BodyNode<'a> {
+        Arc::make_mut(&mut self.node)
+    }
+
+    /// Returns true if both of the shared nodes point to the same node (this is
+    /// the case if one is a clone of the other).
+    pub fn ptr_eq(&self, other: &Shared<'_>) -> bool {
+        std::ptr::eq(
+            Arc::as_ptr(&self.node).cast::<()>(),
+            Arc::as_ptr(&other.node).cast::<()>(),
+        )
+    }
+}
+
+/// Creates a new `Shared` node – functionally equivalent to `Shared::new(node)`
+/// (but easier to type.)
+pub fn shared<'a, N>(node: N) -> Shared<'a>
+where
+    N: Into<BodyNode<'a>>,
+{
+    Shared::new(node)
+}
+
+impl<'a> From<Arc<BodyNode<'a>>> for Shared<'a> {
+    fn from(node: Arc<BodyNode<'a>>) -> Self {
+        Self { node }
+    }
+}
+
+impl Display for Shared<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.node.fmt(f)
+    }
+}
+
+into_grouping_union!(Shared, BodyNode);
+
+impl<'a> Attributes<'a> for Shared<'a> {
+    fn attribute_map(&self) -> &HashMap<Cow<'a, str>, Cow<'a, str>> {
+        self.node.attribute_map()
+    }
+
+    fn attribute_map_mut(&mut self) -> Option<&mut HashMap<Cow<'a, str>, Cow<'a, str>>> {
+        Arc::make_mut(&mut self.node).attribute_map_mut()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Shared;
+    use crate::{attributes::Attributes, prelude::*};
+
+    #[test]
+    fn test_shared() {
+        fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+        let nav = Shared::new(
+            Div::new()
+                .attribute(Id::new("nav"))
+                .child(A::new().text("Home")),
+        );
+        assert_send_sync(&nav);
+
+        let pages = (0..3)
+            .map(|i| {
+                Body::new()
+                    .child(nav.clone())
+                    .child(P::with_text(i.to_string()))
+            })
+            .collect::<Vec<_>>();
+        for page in &pages {
+            let shared = page.child_nodes()[0].as_shared().unwrap();
+            assert!(shared.ptr_eq(&nav));
+            assert_eq!(page.select("#nav > a").len(), 1);
+            assert_eq!(page.child_nodes()[0].children()[0].tag_name(), Some("div"));
+            assert_eq!(page.descendants().filter_map(BodyNode::as_div).count(), 1);
+        }
+        let copy = pages[0].clone();
+        assert!(copy.child_nodes()[0].as_shared().unwrap().ptr_eq(&nav));
+
+        let mut changed = BodyNode::from(nav.clone());
+        changed.set_attribute(Class::from("changed")).unwrap();
+        assert_eq!(changed.get_attribute("class"), Some("changed"));
+        assert_eq!(nav.get().get_attribute("class"), None);
+        assert!(!changed.as_shared().unwrap().ptr_eq(&nav));
+    }
+}
@@ -4,12 +4,13 @@ A copy of this license can be found in the `licenses` directory at the root of t
 */
 
 use crate::{
-    attributes::IntoAttribute,
-    into_attribute_for_grouping_enum, into_grouping_union,
-    prelude::{Id, Style},
-    utility_enum,
+    attribute_enum,
+    attributes::{FromAttribute, IntoAttribute, IntoAttributes},
+    from_attribute_via_new, into_grouping_union,
+    prelude::Id,
+    sanitize::{SanitizePolicy, TrustedHtml},
+    url::SafeUrl,
 };
-use ammonia::clean;
 use std::{borrow::Cow, collections::HashMap, fmt::Display};
 
 use super::body::body_node::BodyNode;
@@ -27,69 +28,87 @@ use super::body::body_node::BodyNode;
 /// for further information.
 #[derive(Debug, Clone, Default)]
 #[must_use]
-pub struct A {
-    attrs: HashMap<Cow<'static, str>, Cow<'static, str>>,
-    text: Cow<'static, str>,
+pub struct A<'a> {
+    attrs: HashMap<Cow<'a, str>, Cow<'a, str>>,
+    pub(crate) text: Cow<'a, str>,
 }
 
-impl A {
+impl<'a> A<'a> {
     /// Creates a new [`A`] tag.
-    pub fn new() -> A {
+    pub fn new() -> Self {
         Default::default()
     }
 }
 
 /// Creates a new `A` tag – functionally equivalent to `A::new()` (but easier to
 /// type.)
-pub fn a() -> A {
+pub fn a<'a>() -> A<'a> {
     A::new()
 }
 
-impl A {
+impl<'a> A<'a> {
     /// Adds the supplied text to this node, overwriting the previously existing
     /// text (if text has already been added to the node).
     ///
-    /// This method sanitises the input (i.e. it escapes HTML);
-    /// this might not be what you want – if you are *absolutely certain* that
-    /// the text you are providing does not come from a potentially
-    /// malicious source (e.g. user-supplied text can contain script tags
-    /// which will execute unwanted code) you can use `text_unsanitized` which
-    /// is identical to this method, except for that it does not sanitise the
-    /// inputted text (and is thus slightly faster).
+    /// This method escapes the input (so any HTML in it is displayed as it
+    /// is, rather than being interpreted). If the text is HTML which should be
+    /// rendered (e.g. user-supplied rich text), use `html` instead, which
+    /// sanitises it. If you are *absolutely certain* that the HTML you are
+    /// providing does not come from a potentially malicious source (e.g.
+    /// user-supplied text can contain script tags which will execute unwanted
+    /// code) you can use `text_unsanitized`, which adds it as it is.
     pub fn text<S>(mut self, text: S) -> Self
     where
-        S: Into<Cow<'static, str>>,
+        S: Into<Cow<'a, str>>,
     {
-        self.text = clean(&text.into()).into();
+        self.text = crate::utils::escape_text(text.into());
+        self
+    }
+
+    /// Adds the supplied HTML to this node (overwriting any existing text),
+    /// sanitising it first (using the policy set with `SanitizePolicy::scope`,
+    /// or `ammonia`'s default policy).
+    pub fn html<S>(mut self, html: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.text = crate::sanitize::clean(&html.into()).into();
+        self
+    }
+
+    /// Adds the supplied HTML to this node (in the same way as `html`),
+    /// sanitising it according to the provided policy.
+    pub fn html_with_policy<S>(mut self, html: S, policy: &SanitizePolicy) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.text = policy.clean(&html.into()).into();
         self
     }
 
     /// Adds the supplied text to this node, overwriting the previously existing
     /// text (if text has already been added to the node).
     ///
-    /// WARNING: Do not (under any circumstances) use this method with unescaped
-    /// user-supplied text. It will be rendered and poses a major security
-    /// threat to your application. If in doubt, use the `text` method
-    /// instead of this one (the risk is much lower that way).
-    pub fn text_unsanitized<S>(mut self, text: S) -> Self
-    where
-        S: Into<Cow<'static, str>>,
-    {
-        self.text = text.into();
+    /// The HTML is not sanitized, so it must be a [`TrustedHtml`] (create
+    /// one with `TrustedHtml::dangerously_trust` if you are *absolutely
+    /// certain* that the HTML does not contain any user-supplied content).
+    pub fn text_unsanitized(mut self, html: TrustedHtml<'a>) -> Self {
+        self.text = html.into();
         self
     }
 
     /// Adds an attribute to this node. This method takes one argument which
-    /// must implement `Into<AAttr>`.
+    /// must implement `Into<AAttr<'a>>`.
     pub fn attribute<I>(mut self, attribute: I) -> Self
     where
-        I: Into<AAttr>,
+        I: Into<AAttr<'a>>,
     {
-        let res = attribute.into().into_attribute();
-        self.attrs.insert(res.0, res.1);
+        crate::utils::insert_attributes(&mut self.attrs, attribute.into().into_attributes());
         self
     }
 
+    crate::define_attribute_helper_fns!(AAttr<'a>);
+
     /// Attach a new `href` attribute to this tag.
     ///
     /// ```rust
@@ -102,9 +121,9 @@ impl A {
     /// # use malvolio::prelude::*;
     /// A::new().attribute(Href::new("https://example.com"));
     /// ```
-    pub fn href<C>(self, href: C) -> Self
+    pub fn href<U>(self, href: U) -> Self
     where
-        C: Into<Cow<'static, str>>,
+        U: Into<SafeUrl<'a>>,
     {
         self.attribute(Href::new(href))
     }
@@ -123,28 +142,21 @@ impl A {
     /// ```
     pub fn id<C>(self, id: C) -> Self
     where
-        C: Into<Cow<'static, str>>,
+        C: Into<Cow<'a, str>>,
     {
         self.attribute(Id::new(id))
     }
 
     /// Read an attribute that has been set.
-    pub fn read_attribute(&self, attribute: &'static str) -> Option<&Cow<'static, str>> {
+    pub fn read_attribute(&self, attribute: &str) -> Option<&Cow<'a, str>> {
         self.attrs.get(attribute)
     }
 }
 
-impl Display for A {
+impl Display for A<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str("<a")?;
-        for attr in &self.attrs {
-            f.write_str(" ")?;
-            attr.0.fmt(f)?;
-            f.write_str("=\"")?;
-            attr.1.fmt(f)?;
-            f.write_str("\"")?;
-        }
-        f.write_str("\"")?;
+        crate::utils::write_attributes(&self.attrs, f)?;
         f.write_str(">")?;
         self.text.fmt(f)?;
         f.write_str("</a>")
@@ -153,23 +165,16 @@ impl Display for A {
 
 into_grouping_union!(A, BodyNode);
 
-utility_enum!(
+attribute_enum!(
     /// An attribute for the A tag.
-    #[allow(missing_docs)]
     pub enum AAttr {
-        Href(Href),
-        Download(Download),
-        Target(Target),
-        Id(Id),
-        Style(Style),
+        Href<'a>,
+        Download<'a>,
+        Target,
     }
 );
 
-into_grouping_union!(Id, AAttr);
-
-into_grouping_union!(Style, AAttr);
-
-into_attribute_for_grouping_enum!(AAttr, Href, Download, Target, Id, Style);
+crate::impl_attributes!(A);
 
 /// The "href" attribute (currently only usable with the `<a>` tags, but support
 /// for other tags is planned – if you need support now, feel free – and
@@ -178,53 +183,73 @@ into_attribute_for_grouping_enum!(AAttr, Href, Download, Target, Id, Style);
 /// See the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/a#attr-href)
 /// for further information.
 #[derive(Debug, Clone)]
-pub struct Href(Cow<'static, str>);
+pub struct Href<'a>(Cow<'a, str>);
 
-impl Href {
-    /// Create a new `Href` attribute. This method accepts any item that
-    /// implements `Into<Cow<'static, str>>` (which includes `String` and
-    /// `&str`).
-    pub fn new<C>(value: C) -> Self
+impl<'a> Href<'a> {
+    /// Create a new `Href` attribute. This method accepts a [`SafeUrl`], or
+    /// any string (which is checked with `SafeUrl::sanitize`, so URLs which
+    /// are not allowed, such as `javascript:` URLs, are replaced).
+    pub fn new<U>(url: U) -> Self
     where
-        C: Into<Cow<'static, str>>,
+        U: Into<SafeUrl<'a>>,
     {
-        Self(value.into())
+        Self(url.into().into())
     }
-}
 
-into_grouping_union!(Href, AAttr);
+    /// Returns the value of this attribute.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
 
 /// The download attribute.
 ///
 /// See the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/a#attr-download)
 /// for further information.
 #[derive(Debug, Clone)]
-pub struct Download(Cow<'static, str>);
+pub struct Download<'a>(Cow<'a, str>);
 
-impl Download {
+impl<'a> Download<'a> {
     /// Create a new attribute from the provided value.
     pub fn new<C>(value: C) -> Self
     where
-        C: Into<Cow<'static, str>>,
+        C: Into<Cow<'a, str>>,
     {
         Self(value.into())
     }
+
+    /// Returns the value of this attribute.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
 }
 
-impl IntoAttribute for Download {
-    fn into_attribute(self) -> (Cow<'static, str>, Cow<'static, str>) {
+impl<'a> IntoAttribute<'a> for Download<'a> {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
         ("download".into(), self.0)
     }
 }
 
-into_grouping_union!(Download, AAttr);
+from_attribute_via_new!(Download, "download");
 
-impl IntoAttribute for Href {
-    fn into_attribute(self) -> (Cow<'static, str>, Cow<'static, str>) {
+impl<'a> IntoAttribute<'a> for Href<'a> {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
         ("href".into(), self.0)
     }
 }
 
+impl FromAttribute for Href<'static> {
+    const NAME: &'static str = "href";
+
+    fn from_attribute(value: &str) -> Option<Self> {
+        // the value may have been set without being checked (for example
+        // with `raw_attribute`), so it is checked again
+        SafeUrl::parse(value.to_string())
+            .ok()
+            .map(|url| Self(url.into()))
+    }
+}
+
 /// The "target" attribute for a link.
 ///
 /// See the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/a#attr-target)
@@ -235,10 +260,8 @@ pub enum Target {
     Blank,
 }
 
-into_grouping_union!(Target, AAttr);
-
-impl IntoAttribute for Target {
-    fn into_attribute(self) -> (Cow<'static, str>, Cow<'static, str>) {
+impl<'a> IntoAttribute<'a> for Target {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
         (
             "target".into(),
             match self {
@@ -247,6 +270,17 @@ impl IntoAttribute for Target {
         )
     }
 }
+
+impl FromAttribute for Target {
+    const NAME: &'static str = "target";
+
+    fn from_attribute(value: &str) -> Option<Self> {
+        match value {
+            "_blank" => Some(Target::Blank),
+            _ => None,
+        }
+    }
+}
 #[cfg(test)]
 mod test {
     use crate::prelude::*;
@@ -264,4 +298,16 @@ mod test {
         assert_eq!(a.attr("target").unwrap(), "_blank");
         assert_eq!(a.attr("download").unwrap(), "some-download");
     }
+
+    #[test]
+    fn test_a_is_well_formed() {
+        assert_eq!(
+            A::new()
+                .attribute(Href::new("/home"))
+                .text("Home")
+                .to_string(),
+            "<a href=\"/home\">Home</a>"
+        );
+        assert_eq!(A::new().to_string(), "<a></a>");
+    }
 }
@@ -3,7 +3,11 @@ This source code file is distributed subject to the terms of the Mozilla Public
 A copy of this license can be found in the `licenses` directory at the root of this project.
 */
 use crate::{
-    enum_display,
+    enum_attributes, enum_display,
+    fragment::Fragment,
+    lazy::Lazy,
+    raw::Raw,
+    shared::Shared,
     tags::{
         a::A,
         br::Br,
@@ -23,37 +27,161 @@ use crate::{
 
 utility_enum!(
     #[allow(missing_docs)]
-
     /// A node which can be mounted to the <body> tag (or any of its children).
-    pub enum BodyNode {
-        H1(H1),
-        H2(H2),
-        H3(H3),
-        H4(H4),
-        H5(H5),
-        H6(H6),
-        P(P),
-        Text(Text),
-        Form(Form),
-        Br(Br),
-        Div(Div),
-        A(A),
-        Input(Input),
-        Label(Label),
-        Select(Select),
-        NoScript(NoScript),
-        Img(Img),
+    pub enum BodyNode<'a> {
+        H1(H1<'a>),
+        H2(H2<'a>),
+        H3(H3<'a>),
+        H4(H4<'a>),
+        H5(H5<'a>),
+        H6(H6<'a>),
+        P(P<'a>),
+        Text(Text<'a>),
+        Form(Form<'a>),
+        Br(Br<'a>),
+        Div(Div<'a>),
+        A(A<'a>),
+        Input(Input<'a>),
+        Label(Label<'a>),
+        Select(Select<'a>),
+        NoScript(NoScript<'a>),
+        Img(Img<'a>),
+        Fragment(Fragment<'a>),
+        Lazy(Lazy<'a>),
+        Shared(Shared<'a>),
+        Raw(Raw<'a>),
     }
 );
 
 enum_display!(
     BodyNode, H1, H2, H3, H4, H5, H6, P, Br, Text, Form, Div, A, Input, Select, NoScript, Img,
-    Label
+    Label, Fragment, Lazy, Shared, Raw
+);
+
+enum_attributes!(
+    BodyNode, H1, H2, H3, H4, H5, H6, P, Br, Form, Div, A, Input, Select, NoScript, Img, Label,
+    Shared; Text, Fragment, Lazy, Raw
 );
 
+impl<'a> BodyNode<'a> {
+    /// Returns the name of the tag (e.g. `"div"`), or `None` if this is a text
+    /// node, a fragment, a lazy node, a shared node or a raw node.
+    pub fn tag_name(&self) -> Option<&'static str> {
+        Some(match self {
+            Self::H1(_) => "h1",
+            Self::H2(_) => "h2",
+            Self::H3(_) => "h3",
+            Self::H4(_) => "h4",
+            Self::H5(_) => "h5",
+            Self::H6(_) => "h6",
+            Self::P(_) => "p",
+            Self::Text(_) => return None,
+            Self::Form(_) => "form",
+            Self::Br(_) => "br",
+            Self::Div(_) => "div",
+            Self::A(_) => "a",
+            Self::Input(_) => "input",
+            Self::Label(_) => "label",
+            Self::Select(_) => "select",
+            Self::NoScript(_) => "noscript",
+            Self::Img(_) => "img",
+            Self::Fragment(_) | Self::Lazy(_) | Self::Shared(_) | Self::Raw(_) => return None,
+        })
+    }
+
+    /// Returns the text of this node (as it will be rendered, so any HTML
+    /// special characters will have been escaped), or `None` if the node does
+    /// not contain any text.
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            Self::H1(h1) => Some(&h1.text),
+            Self::H2(h2) => Some(&h2.text),
+            Self::H3(h3) => Some(&h3.text),
+            Self::H4(h4) => Some(&h4.text),
+            Self::H5(h5) => Some(&h5.text),
+            Self::H6(h6) => Some(&h6.text),
+            Self::P(p) => Some(&p.text),
+            Self::Text(text) => Some(&text.text),
+            Self::A(a) => Some(&a.text),
+            Self::Label(label) => Some(&label.text),
+            Self::NoScript(noscript) => Some(&noscript.text),
+            Self::Form(_)
+            | Self::Br(_)
+            | Self::Div(_)
+            | Self::Input(_)
+            | Self::Select(_)
+            | Self::Img(_)
+            | Self::Fragment(_)
+            | Self::Lazy(_)
+            | Self::Shared(_)
+            | Self::Raw(_) => None,
+        }
+    }
+
+    /// Returns the children of this node (this is empty for nodes which cannot
+    /// have any children, and for lazy nodes, which are only constructed when
+    /// they are rendered). The only child of a shared node is the node which
+    /// it contains.
+    pub fn children(&self) -> &[BodyNode<'a>] {
+        match self {
+            Self::P(p) => p.child_nodes(),
+            Self::Form(form) => form.child_nodes(),
+            Self::Div(div) => div.child_nodes(),
+            Self::Fragment(fragment) => fragment.child_nodes(),
+            Self::Shared(shared) => std::slice::from_ref(shared.get()),
+            _ => &[],
+        }
+    }
+
+    /// Returns a mutable reference to the children of this node, or `None` if
+    /// this node cannot have any children (or is a lazy or shared node).
+    pub fn children_mut(&mut self) -> Option<&mut Vec<BodyNode<'a>>> {
+        match self {
+            Self::P(p) => Some(p.child_nodes_mut()),
+            Self::Form(form) => Some(form.child_nodes_mut()),
+            Self::Div(div) => Some(div.child_nodes_mut()),
+            Self::Fragment(fragment) => Some(fragment.child_nodes_mut()),
+            _ => None,
+        }
+    }
+}
+
+/// `None` is converted into an empty [`Fragment`] (so nothing is added when it is
+/// passed to `child`).
+///
+/// ```
+/// # use malvolio::prelude::*;
+/// let error: Option<&str> = None;
+/// let div = Div::new().child(error.map(P::with_text)).child(Some(H1::new("Title")));
+/// assert_eq!(div.child_nodes().len(), 1);
+/// ```
+impl<'a, T> From<Option<T>> for BodyNode<'a>
+where
+    T: Into<BodyNode<'a>>,
+{
+    fn from(node: Option<T>) -> Self {
+        match node {
+            Some(node) => node.into(),
+            None => Fragment::new().into(),
+        }
+    }
+}
+
+/// A list of nodes is converted into a [`Fragment`] (so each of the nodes is
+/// added when it is passed to `child`). Iterators can be collected into a
+/// [`Fragment`] directly.
+impl<'a, T> From<Vec<T>> for BodyNode<'a>
+where
+    T: Into<BodyNode<'a>>,
+{
+    fn from(nodes: Vec<T>) -> Self {
+        Fragment::new().children(nodes).into()
+    }
+}
+
 #[allow(missing_docs)]
-impl BodyNode {
-    pub fn as_h1(&self) -> Option<&H1> {
+impl<'a> BodyNode<'a> {
+    pub fn as_h1(&self) -> Option<&H1<'a>> {
         if let Self::H1(v) = self {
             Some(v)
         } else {
@@ -61,7 +189,7 @@ impl BodyNode {
         }
     }
 
-    pub fn as_h2(&self) -> Option<&H2> {
+    pub fn as_h2(&self) -> Option<&H2<'a>> {
         if let Self::H2(v) = self {
             Some(v)
         } else {
@@ -69,7 +197,7 @@ impl BodyNode {
         }
     }
 
-    pub fn as_h3(&self) -> Option<&H3> {
+    pub fn as_h3(&self) -> Option<&H3<'a>> {
         if let Self::H3(v) = self {
             Some(v)
         } else {
@@ -77,7 +205,7 @@ impl BodyNode {
         }
     }
 
-    pub fn as_h4(&self) -> Option<&H4> {
+    pub fn as_h4(&self) -> Option<&H4<'a>> {
         if let Self::H4(v) = self {
             Some(v)
         } else {
@@ -85,7 +213,7 @@ impl BodyNode {
         }
     }
 
-    pub fn as_h5(&self) -> Option<&H5> {
+    pub fn as_h5(&self) -> Option<&H5<'a>> {
         if let Self::H5(v) = self {
             Some(v)
         } else {
@@ -93,7 +221,7 @@ impl BodyNode {
         }
     }
 
-    pub fn as_h6(&self) -> Option<&H6> {
+    pub fn as_h6(&self) -> Option<&H6<'a>> {
         if let Self::H6(v) = self {
             Some(v)
         } else {
@@ -101,7 +229,7 @@ impl BodyNode {
         }
     }
 
-    pub fn as_p(&self) -> Option<&P> {
+    pub fn as_p(&self) -> Option<&P<'a>> {
         if let Self::P(v) = self {
             Some(v)
         } else {
@@ -109,7 +237,7 @@ impl BodyNode {
         }
     }
 
-    pub fn as_text(&self) -> Option<&Text> {
+    pub fn as_text(&self) -> Option<&Text<'a>> {
         if let Self::Text(v) = self {
             Some(v)
         } else {
@@ -117,7 +245,7 @@ impl BodyNode {
         }
     }
 
-    pub fn as_form(&self) -> Option<&Form> {
+    pub fn as_form(&self) -> Option<&Form<'a>> {
         if let Self::Form(v) = self {
             Some(v)
         } else {
@@ -125,7 +253,7 @@ impl BodyNode {
         }
     }
 
-    pub fn as_br(&self) -> Option<&Br> {
+    pub fn as_br(&self) -> Option<&Br<'a>> {
         if let Self::Br(v) = self {
             Some(v)
         } else {
@@ -133,7 +261,7 @@ impl BodyNode {
         }
     }
 
-    pub fn as_div(&self) -> Option<&Div> {
+    pub fn as_div(&self) -> Option<&Div<'a>> {
         if let Self::Div(v) = self {
             Some(v)
         } else {
@@ -141,7 +269,7 @@ impl BodyNode {
         }
     }
 
-    pub fn as_a(&self) -> Option<&A> {
+    pub fn as_a(&self) -> Option<&A<'a>> {
         if let Self::A(v) = self {
             Some(v)
         } else {
@@ -149,7 +277,7 @@ impl BodyNode {
         }
     }
 
-    pub fn as_input(&self) -> Option<&Input> {
+    pub fn as_input(&self) -> Option<&Input<'a>> {
         if let Self::Input(v) = self {
             Some(v)
         } else {
@@ -157,7 +285,7 @@ impl BodyNode {
         }
     }
 
-    pub fn as_label(&self) -> Option<&Label> {
+    pub fn as_label(&self) -> Option<&Label<'a>> {
         if let Self::Label(v) = self {
             Some(v)
         } else {
@@ -165,7 +293,7 @@ impl BodyNode {
         }
     }
 
-    pub fn as_select(&self) -> Option<&Select> {
+    pub fn as_select(&self) -> Option<&Select<'a>> {
         if let Self::Select(v) = self {
             Some(v)
         } else {
@@ -173,7 +301,7 @@ impl BodyNode {
         }
     }
 
-    pub fn as_no_script(&self) -> Option<&NoScript> {
+    pub fn as_no_script(&self) -> Option<&NoScript<'a>> {
         if let Self::NoScript(v) = self {
             Some(v)
         } else {
@@ -181,11 +309,43 @@ impl BodyNode {
         }
     }
 
-    pub fn as_img(&self) -> Option<&Img> {
+    pub fn as_img(&self) -> Option<&Img<'a>> {
         if let Self::Img(v) = self {
             Some(v)
         } else {
             None
         }
     }
+
+    pub fn as_fragment(&self) -> Option<&Fragment<'a>> {
+        if let Self::Fragment(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_lazy(&self) -> Option<&Lazy<'a>> {
+        if let Self::Lazy(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_shared(&self) -> Option<&Shared<'a>> {
+        if let Self::Shared(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_raw(&self) -> Option<&Raw<'a>> {
+        if let Self::Raw(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
 }
@@ -6,8 +6,8 @@ use std::{borrow::Cow, collections::HashMap, fmt::Display};
 
 use self::body_node::BodyNode;
 use super::headings::{H1, H2, H3, H4, H5, H6};
-use crate::attributes::IntoAttribute;
-use crate::{into_attribute_for_grouping_enum, into_grouping_union, prelude::Style, utility_enum};
+use crate::attribute_enum;
+use crate::attributes::IntoAttributes;
 
 /// Contains the `BodyNode` enum.
 pub mod body_node;
@@ -16,54 +16,54 @@ pub mod body_node;
 
 /// The `<body>` tag.
 #[must_use]
-pub struct Body {
-    children: Vec<BodyNode>,
-    attrs: HashMap<Cow<'static, str>, Cow<'static, str>>,
+pub struct Body<'a> {
+    children: Vec<BodyNode<'a>>,
+    attrs: HashMap<Cow<'a, str>, Cow<'a, str>>,
 }
 
-impl Body {
+impl<'a> Body<'a> {
     /// Creates a new [`Body`] tag.
-    pub fn new() -> Body {
+    pub fn new() -> Self {
         Default::default()
     }
 }
 
 /// Creates a new `Body` tag – functionally equivalent to `Body::new()` (but
 /// shorter to type.)
-pub fn body() -> Body {
+pub fn body<'a>() -> Body<'a> {
     Body::new()
 }
 
-utility_enum! {
-    pub enum BodyAttr {
-        /// Add a "style" attribute to this item.
-        Style(Style),
-    }
-}
+attribute_enum!(
+    pub enum BodyAttr {}
+);
 
-into_grouping_union!(Style, BodyAttr);
-into_attribute_for_grouping_enum!(BodyAttr, Style);
+crate::impl_attributes!(Body);
 
-impl Body {
+impl<'a> Body<'a> {
     /// Attach multiple children to this tag, from an iterator of items
-    /// implementing `Into<BodyNode>`
+    /// implementing `Into<BodyNode<'a>>`
     pub fn children<I, C>(mut self, children: I) -> Self
     where
-        C: Into<BodyNode>,
+        C: Into<BodyNode<'a>>,
         I: IntoIterator<Item = C>,
     {
-        self.children
-            .extend(children.into_iter().map(Into::into).collect::<Vec<_>>());
+        crate::utils::extend_children(&mut self.children, children);
         self
     }
     /// Attach a single child to this tag.
     pub fn child<C>(mut self, child: C) -> Self
     where
-        C: Into<BodyNode>,
+        C: Into<BodyNode<'a>>,
     {
-        self.children.push(child.into());
+        crate::utils::push_child(&mut self.children, child.into());
         self
     }
+
+    crate::define_child_node_accessors!(BodyNode<'a>);
+
+    crate::define_conditional_child_fns!(BodyNode<'a>);
+
     /// Apply a function to this tag.
     pub fn map<F>(self, mapping: F) -> Self
     where
@@ -74,14 +74,15 @@ impl Body {
     /// Add a new attribute to this tag.
     pub fn attribute<A>(mut self, attribute: A) -> Self
     where
-        A: Into<BodyAttr>,
+        A: Into<BodyAttr<'a>>,
     {
-        let (a, b) = attribute.into().into_attribute();
-        self.attrs.insert(a, b);
+        crate::utils::insert_attributes(&mut self.attrs, attribute.into().into_attributes());
         self
     }
+
+    crate::define_attribute_helper_fns!(BodyAttr<'a>);
     /// Read an attribute that has been set
-    pub fn read_attribute(&self, attribute: &'static str) -> Option<&Cow<'static, str>> {
+    pub fn read_attribute(&self, attribute: &str) -> Option<&Cow<'a, str>> {
         self.attrs.get(attribute)
     }
     /// Attach a new `H1` instance to this class. Note that this method only
@@ -103,7 +104,7 @@ impl Body {
     /// ```
     pub fn h1<C>(self, c: C) -> Self
     where
-        C: Into<H1>,
+        C: Into<H1<'a>>,
     {
         self.child(c.into())
     }
@@ -126,7 +127,7 @@ impl Body {
     /// ```
     pub fn h2<C>(self, c: C) -> Self
     where
-        C: Into<H2>,
+        C: Into<H2<'a>>,
     {
         self.child(c.into())
     }
@@ -149,7 +150,7 @@ impl Body {
     /// ```
     pub fn h3<C>(self, c: C) -> Self
     where
-        C: Into<H3>,
+        C: Into<H3<'a>>,
     {
         self.child(c.into())
     }
@@ -172,7 +173,7 @@ impl Body {
     /// ```
     pub fn h4<C>(self, c: C) -> Self
     where
-        C: Into<H4>,
+        C: Into<H4<'a>>,
     {
         self.child(c.into())
     }
@@ -195,7 +196,7 @@ impl Body {
     /// ```
     pub fn h5<C>(self, c: C) -> Self
     where
-        C: Into<H5>,
+        C: Into<H5<'a>>,
     {
         self.child(c.into())
     }
@@ -218,22 +219,16 @@ impl Body {
     /// ```
     pub fn h6<C>(self, c: C) -> Self
     where
-        C: Into<H6>,
+        C: Into<H6<'a>>,
     {
         self.child(c.into())
     }
 }
 
-impl Display for Body {
+impl Display for Body<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str("<body")?;
-        for attr in &self.attrs {
-            f.write_str(" ")?;
-            attr.0.fmt(f)?;
-            f.write_str("=\"")?;
-            attr.1.fmt(f)?;
-            f.write_str("\"")?;
-        }
+        crate::utils::write_attributes(&self.attrs, f)?;
         f.write_str(">")?;
         for node in &self.children {
             node.fmt(f)?;
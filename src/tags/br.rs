@@ -2,40 +2,85 @@
 This source code file is distributed subject to the terms of the Mozilla Public License v2.0.
 A copy of this license can be found in the `licenses` directory at the root of this project.
 */
-use std::fmt::Display;
+use std::{borrow::Cow, collections::HashMap, fmt::Display};
 
-use crate::into_grouping_union_without_lifetimes;
+use crate::{
+    attribute_enum, attributes::IntoAttributes, into_grouping_union, utils::write_attributes,
+};
 
 use super::body::body_node::BodyNode;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Default, Clone)]
 
 /// A new line.
 ///
 /// ```
 /// # use malvolio::prelude::*;
-/// Div::new().child(Br);
+/// Div::new().child(br());
 /// ```
 ///
 /// See the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/br) for more
 /// info.
 #[must_use]
-pub struct Br;
+pub struct Br<'a> {
+    attrs: HashMap<Cow<'a, str>, Cow<'a, str>>,
+}
+
+impl<'a> Br<'a> {
+    /// Creates a new [`Br`] tag.
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+/// Creates a new `Br` tag – functionally equivalent to `Br::new()` (but
+/// easier to type.)
+pub fn br<'a>() -> Br<'a> {
+    Br::new()
+}
+
+impl<'a> Br<'a> {
+    /// Attach an attribute to this tag.
+    pub fn attribute<A>(mut self, attribute: A) -> Self
+    where
+        A: Into<BrAttr<'a>>,
+    {
+        crate::utils::insert_attributes(&mut self.attrs, attribute.into().into_attributes());
+        self
+    }
 
-impl Display for Br {
+    crate::define_attribute_helper_fns!(BrAttr<'a>);
+
+    crate::define_raw_attribute_fn!();
+
+    /// Read an attribute that has been set.
+    pub fn read_attribute(&self, attribute: &str) -> Option<&Cow<'a, str>> {
+        self.attrs.get(attribute)
+    }
+}
+
+impl Display for Br<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str("<br/>")
+        f.write_str("<br")?;
+        write_attributes(&self.attrs, f)?;
+        f.write_str("/>")
     }
 }
 
-into_grouping_union_without_lifetimes!(Br, BodyNode);
+into_grouping_union!(Br, BodyNode);
+
+attribute_enum!(
+    pub enum BrAttr {}
+);
+
+crate::impl_attributes!(Br);
 
 #[cfg(test)]
 mod test {
     use crate::prelude::*;
     #[test]
     fn test_br() {
-        let document = Br.to_string();
+        let document = br().to_string();
         let document = scraper::Html::parse_document(&document);
         let br = scraper::Selector::parse("br").unwrap();
         document.select(&br).next().unwrap();
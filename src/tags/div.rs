@@ -5,11 +5,11 @@ A copy of this license can be found in the `licenses` directory at the root of t
 use std::{borrow::Cow, collections::HashMap, fmt::Display};
 
 use crate::{
-    attributes::{common::Class, IntoAttribute},
-    prelude::{Style, H1, H2, H3, H4, H5, H6},
+    attributes::IntoAttributes,
+    prelude::{H1, H2, H3, H4, H5, H6},
 };
 
-use crate::{into_attribute_for_grouping_enum, into_grouping_union, prelude::Id, utility_enum};
+use crate::{attribute_enum, into_grouping_union};
 
 use super::body::body_node::BodyNode;
 
@@ -19,25 +19,25 @@ use super::body::body_node::BodyNode;
 ///
 /// See the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/div)
 /// for further information.
-pub struct Div {
-    pub(crate) children: Vec<BodyNode>,
-    pub(crate) attrs: HashMap<Cow<'static, str>, Cow<'static, str>>,
+pub struct Div<'a> {
+    pub(crate) children: Vec<BodyNode<'a>>,
+    pub(crate) attrs: HashMap<Cow<'a, str>, Cow<'a, str>>,
 }
 
-impl Div {
+impl<'a> Div<'a> {
     /// Creates a new [`Div`] tag.
-    pub fn new() -> Div {
+    pub fn new() -> Self {
         Default::default()
     }
 }
 
 /// Creates a new `<div>` tag – functionally equivalent to `Div::new()` (but
 /// easier to type.)
-pub fn div() -> Div {
+pub fn div<'a>() -> Div<'a> {
     Div::new()
 }
 
-impl Div {
+impl<'a> Div<'a> {
     /// Attaches zero or more children to a `Div`. This method accepts anything
     /// which can be turned into an iterator of `BodyNode`'s (i.e. most tags
     /// that you would expect to be able to use).
@@ -63,22 +63,25 @@ impl Div {
     pub fn children<C, D>(mut self, children: C) -> Self
     where
         C: IntoIterator<Item = D>,
-        D: Into<BodyNode>,
+        D: Into<BodyNode<'a>>,
     {
-        self.children
-            .extend(children.into_iter().map(Into::into).collect::<Vec<_>>());
+        crate::utils::extend_children(&mut self.children, children);
         self
     }
 
     /// Add a single child to the `Div` in question.
     pub fn child<C>(mut self, child: C) -> Self
     where
-        C: Into<BodyNode>,
+        C: Into<BodyNode<'a>>,
     {
-        self.children.push(child.into());
+        crate::utils::push_child(&mut self.children, child.into());
         self
     }
 
+    crate::define_child_node_accessors!(BodyNode<'a>);
+
+    crate::define_conditional_child_fns!(BodyNode<'a>);
+
     /// Allows you to apply a custom function to this `Div`. This function is
     /// useful if you want to modify this tag according to some state
     /// captured from the environment.
@@ -110,20 +113,23 @@ impl Div {
     }
 
     /// Attach a single attribute to a `Div`. This will overwrite the existing
-    /// attribute, if it has already been defined.
+    /// attribute, if it has already been defined (apart from `Class` and
+    /// `Style`, which are merged with the existing values – use
+    /// `replace_attribute` to overwrite them).
     pub fn attribute<A>(mut self, attribute: A) -> Self
     where
-        A: Into<DivAttr>,
+        A: Into<DivAttr<'a>>,
     {
-        let (a, b) = attribute.into().into_attribute();
-        self.attrs.insert(a, b);
+        crate::utils::insert_attributes(&mut self.attrs, attribute.into().into_attributes());
         self
     }
 
+    crate::define_attribute_helper_fns!(DivAttr<'a>);
+
     crate::define_raw_attribute_fn!();
 
     /// Read an attribute set on this function.
-    pub fn read_attribute(&self, attribute: &'static str) -> Option<&Cow<'static, str>> {
+    pub fn read_attribute(&self, attribute: &str) -> Option<&Cow<'a, str>> {
         self.attrs.get(attribute)
     }
 
@@ -147,7 +153,7 @@ impl Div {
     /// ```
     pub fn h1<C>(self, c: C) -> Self
     where
-        C: Into<H1>,
+        C: Into<H1<'a>>,
     {
         self.child(c.into())
     }
@@ -172,7 +178,7 @@ impl Div {
     /// ```
     pub fn h2<C>(self, c: C) -> Self
     where
-        C: Into<H2>,
+        C: Into<H2<'a>>,
     {
         self.child(c.into())
     }
@@ -197,7 +203,7 @@ impl Div {
     /// ```
     pub fn h3<C>(self, c: C) -> Self
     where
-        C: Into<H3>,
+        C: Into<H3<'a>>,
     {
         self.child(c.into())
     }
@@ -221,7 +227,7 @@ impl Div {
     /// ```
     pub fn h4<C>(self, c: C) -> Self
     where
-        C: Into<H4>,
+        C: Into<H4<'a>>,
     {
         self.child(c.into())
     }
@@ -245,7 +251,7 @@ impl Div {
     /// ```
     pub fn h5<C>(self, c: C) -> Self
     where
-        C: Into<H5>,
+        C: Into<H5<'a>>,
     {
         self.child(c.into())
     }
@@ -269,22 +275,16 @@ impl Div {
     /// ```
     pub fn h6<C>(self, c: C) -> Self
     where
-        C: Into<H6>,
+        C: Into<H6<'a>>,
     {
         self.child(c.into())
     }
 }
 
-impl Display for Div {
+impl Display for Div<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str("<div")?;
-        for attr in &self.attrs {
-            f.write_str(" ")?;
-            attr.0.fmt(f)?;
-            f.write_str("=\"")?;
-            attr.1.fmt(f)?;
-            f.write_str("\"")?;
-        }
+        crate::utils::write_attributes(&self.attrs, f)?;
         f.write_str(">")?;
         for node in &self.children {
             node.fmt(f)?;
@@ -295,22 +295,11 @@ impl Display for Div {
 
 into_grouping_union!(Div, BodyNode);
 
-utility_enum!(
-    #[allow(missing_docs)]
-    pub enum DivAttr {
-        Id(Id),
-        Class(Class),
-        Style(Style),
-    }
+attribute_enum!(
+    pub enum DivAttr {}
 );
 
-into_attribute_for_grouping_enum!(DivAttr, Id, Class, Style);
-
-into_grouping_union!(Id, DivAttr);
-
-into_grouping_union!(Class, DivAttr);
-
-into_grouping_union!(Style, DivAttr);
+crate::impl_attributes!(Div);
 
 #[cfg(test)]
 mod tests {
@@ -330,6 +319,54 @@ mod tests {
         assert_eq!(div.value().attr("class").unwrap(), "some-class");
         assert_eq!(div.value().attr("style").unwrap(), "font-family: Arial;");
     }
+    #[test]
+    fn test_conditional_children() {
+        let missing: Option<P> = None;
+        let div = Div::new()
+            .child(missing)
+            .child(Some(P::with_text("1")))
+            .child(vec![P::with_text("2"), P::with_text("3")])
+            .child(
+                (4..6)
+                    .map(|i| P::with_text(i.to_string()))
+                    .collect::<Fragment>(),
+            )
+            .child_if(false, || -> P { unreachable!() })
+            .child_if(true, || P::with_text("6"))
+            .children_if(false, || -> Vec<P> { unreachable!() })
+            .children_if(true, || vec![Some(P::with_text("7")), None]);
+        assert_eq!(
+            div.to_string(),
+            "<div><p>1</p><p>2</p><p>3</p><p>4</p><p>5</p><p>6</p><p>7</p></div>"
+        );
+        assert_eq!(div.child_nodes().len(), 7);
+    }
+
+    #[test]
+    fn test_borrowed_attributes() {
+        struct User {
+            id: String,
+            homepage: String,
+        }
+
+        fn card(user: &User) -> Div<'_> {
+            Div::new()
+                .attribute(Id::new(user.id.as_str()))
+                .child(A::new().attribute(Href::new(user.homepage.as_str())))
+        }
+
+        let user = User {
+            id: "user-1".to_string(),
+            homepage: "https://example.com".to_string(),
+        };
+        let div = card(&user);
+        assert!(matches!(div.read_attribute("id"), Some(Cow::Borrowed(_))));
+        assert_eq!(
+            div.to_string(),
+            "<div id=\"user-1\"><a href=\"https://example.com\"></a></div>"
+        );
+    }
+
     #[test]
     fn test_div_children() {
         let document = Div::default()
@@ -5,10 +5,11 @@ A copy of this license can be found in the `licenses` directory at the root of t
 use std::{borrow::Cow, collections::HashMap, fmt::Display};
 
 use crate::{
-    attributes::IntoAttribute,
-    into_attribute_for_grouping_enum, into_grouping_union,
-    prelude::{Style, H1, H2, H3, H4, H5, H6},
-    utility_enum,
+    attribute_enum,
+    attributes::{FromAttribute, IntoAttribute, IntoAttributes},
+    into_grouping_union,
+    prelude::{H1, H2, H3, H4, H5, H6},
+    url::SafeUrl,
 };
 
 use crate::tags::body::body_node::BodyNode;
@@ -29,57 +30,57 @@ use crate::tags::body::body_node::BodyNode;
 ///         .attribute(Placeholder::new("Username"))
 ///         .attribute(Name::new("username")),
 /// )
-/// .child(Br)
+/// .child(br())
 /// .child(
 ///     Input::new()
 ///         .attribute(Type::Email)
 ///         .attribute(Placeholder::new("Email"))
 ///         .attribute(Name::new("email")),
 /// )
-/// .child(Br)
+/// .child(br())
 /// .child(
 ///     Input::new()
 ///         .attribute(Type::Password)
 ///         .attribute(Placeholder::new("Password"))
 ///         .attribute(Name::new("password")),
 /// )
-/// .child(Br)
+/// .child(br())
 /// .child(
 ///     Input::new()
 ///         .attribute(Type::Password)
 ///         .attribute(Placeholder::new("Password confirmation"))
 ///         .attribute(Name::new("password_confirmation")),
 /// )
-/// .child(Br)
+/// .child(br())
 /// .child(
 ///     Input::new()
 ///         .attribute(Type::Submit)
 ///         .attribute(Value::new("Login!")),
 /// );
 /// ```
-pub struct Form {
-    pub(crate) children: Vec<BodyNode>,
-    pub(crate) attrs: HashMap<Cow<'static, str>, Cow<'static, str>>,
+pub struct Form<'a> {
+    pub(crate) children: Vec<BodyNode<'a>>,
+    pub(crate) attrs: HashMap<Cow<'a, str>, Cow<'a, str>>,
 }
 
-impl Form {
+impl<'a> Form<'a> {
     /// Creates a new [`Form`] tag.
-    pub fn new() -> Form {
+    pub fn new() -> Self {
         Default::default()
     }
 }
 
 /// Creates a new `Form` tag – functionally equivalent to `Form::new()` (but
 /// easier to type.)
-pub fn form() -> Form {
+pub fn form<'a>() -> Form<'a> {
     Form::new()
 }
 
-impl Form {
+impl<'a> Form<'a> {
     #[inline(always)]
     /// Add a number of children to a form. This method accepts a single
     /// argument which must implement `IntoIterator` (so for example a
-    /// `Vec`) where the item of the iterator implements `Into<BodyNode>`
+    /// `Vec`) where the item of the iterator implements `Into<BodyNode<'a>>`
     /// (any of the types in this crate which you would expect to go in the body
     /// of an HTML document should implement this).
     ///
@@ -92,14 +93,13 @@ impl Form {
     pub fn children<I, C>(mut self, children: I) -> Self
     where
         I: IntoIterator<Item = C>,
-        C: Into<BodyNode>,
+        C: Into<BodyNode<'a>>,
     {
-        self.children
-            .extend(children.into_iter().map(Into::into).collect::<Vec<_>>());
+        crate::utils::extend_children(&mut self.children, children);
         self
     }
     /// Add a single child to a form. This method accepts a single item
-    /// implementing `Into<BodyNode>`.
+    /// implementing `Into<BodyNode<'a>>`.
     /// ```
     /// # use malvolio::prelude::*;
     /// Form::new()
@@ -109,13 +109,18 @@ impl Form {
     #[inline(always)]
     pub fn child<C>(mut self, child: C) -> Self
     where
-        C: Into<BodyNode>,
+        C: Into<BodyNode<'a>>,
     {
-        self.children.push(child.into());
+        crate::utils::push_child(&mut self.children, child.into());
         self
     }
+
+    crate::define_child_node_accessors!(BodyNode<'a>);
+
+    crate::define_conditional_child_fns!(BodyNode<'a>);
+
     /// Add an attribute to the current form. This accepts any item implementing
-    /// `Into<FormAttr>` (which is all the members of the `FormAttr` enum).
+    /// `Into<FormAttr<'a>>` (which is all the members of the `FormAttr` enum).
     ///
     /// ```
     /// # use malvolio::prelude::*;
@@ -125,14 +130,15 @@ impl Form {
     /// ```
     pub fn attribute<A>(mut self, attr: A) -> Self
     where
-        A: Into<FormAttr>,
+        A: Into<FormAttr<'a>>,
     {
-        let res = attr.into().into_attribute();
-        self.attrs.insert(res.0, res.1);
+        crate::utils::insert_attributes(&mut self.attrs, attr.into().into_attributes());
         self
     }
+
+    crate::define_attribute_helper_fns!(FormAttr<'a>);
     /// Read an attribute that has been set
-    pub fn read_attribute(&self, attribute: &'static str) -> Option<&Cow<'static, str>> {
+    pub fn read_attribute(&self, attribute: &str) -> Option<&Cow<'a, str>> {
         self.attrs.get(attribute)
     }
 
@@ -155,7 +161,7 @@ impl Form {
     /// ```
     pub fn h1<C>(self, c: C) -> Self
     where
-        C: Into<H1>,
+        C: Into<H1<'a>>,
     {
         self.child(c.into())
     }
@@ -178,7 +184,7 @@ impl Form {
     /// ```
     pub fn h2<C>(self, c: C) -> Self
     where
-        C: Into<H2>,
+        C: Into<H2<'a>>,
     {
         self.child(c.into())
     }
@@ -201,7 +207,7 @@ impl Form {
     /// ```
     pub fn h3<C>(self, c: C) -> Self
     where
-        C: Into<H3>,
+        C: Into<H3<'a>>,
     {
         self.child(c.into())
     }
@@ -224,7 +230,7 @@ impl Form {
     /// ```
     pub fn h4<C>(self, c: C) -> Self
     where
-        C: Into<H4>,
+        C: Into<H4<'a>>,
     {
         self.child(c.into())
     }
@@ -247,7 +253,7 @@ impl Form {
     /// ```
     pub fn h5<C>(self, c: C) -> Self
     where
-        C: Into<H5>,
+        C: Into<H5<'a>>,
     {
         self.child(c.into())
     }
@@ -270,22 +276,16 @@ impl Form {
     /// ```
     pub fn h6<C>(self, c: C) -> Self
     where
-        C: Into<H6>,
+        C: Into<H6<'a>>,
     {
         self.child(c.into())
     }
 }
 
-impl Display for Form {
+impl Display for Form<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str("<form ")?;
-        for attr in &self.attrs {
-            f.write_str(" ")?;
-            attr.0.fmt(f)?;
-            f.write_str("=\"")?;
-            attr.1.fmt(f)?;
-            f.write_str("\"")?;
-        }
+        f.write_str("<form")?;
+        crate::utils::write_attributes(&self.attrs, f)?;
         f.write_str(">")?;
         for node in &self.children {
             node.fmt(f)?;
@@ -296,21 +296,15 @@ impl Display for Form {
 
 into_grouping_union!(Form, BodyNode);
 
-utility_enum!(
+attribute_enum!(
     /// A form attribute.
-    #[allow(missing_docs)]
     pub enum FormAttr {
-        Method(Method),
-        Action(Action),
-        Style(Style),
+        Method,
+        Action<'a>,
     }
 );
 
-into_attribute_for_grouping_enum!(FormAttr, Method, Action, Style);
-
-into_grouping_union!(Method, FormAttr);
-into_grouping_union!(Action, FormAttr);
-into_grouping_union!(Style, FormAttr);
+crate::impl_attributes!(Form);
 
 /// The "method" attribute for a form. See the
 /// [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/form#attr-method) for
@@ -323,8 +317,8 @@ pub enum Method {
     Get,
 }
 
-impl IntoAttribute for Method {
-    fn into_attribute(self) -> (Cow<'static, str>, Cow<'static, str>) {
+impl<'a> IntoAttribute<'a> for Method {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
         (
             "method".into(),
             match self {
@@ -336,29 +330,59 @@ impl IntoAttribute for Method {
     }
 }
 
+impl FromAttribute for Method {
+    const NAME: &'static str = "method";
+
+    fn from_attribute(value: &str) -> Option<Self> {
+        match value {
+            "post" => Some(Method::Post),
+            "get" => Some(Method::Get),
+            _ => None,
+        }
+    }
+}
+
 /// The "action" attribute for a form. See the
 /// [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/form#attr-action) for
 /// further details.
 #[derive(Debug, Clone)]
 
-pub struct Action(Cow<'static, str>);
+pub struct Action<'a>(Cow<'a, str>);
 
-impl Action {
-    /// Create a new "action" attribute.
-    pub fn new<S>(input: S) -> Self
+impl<'a> Action<'a> {
+    /// Create a new "action" attribute. This method accepts a [`SafeUrl`], or
+    /// any string (which is checked with `SafeUrl::sanitize`).
+    pub fn new<U>(url: U) -> Self
     where
-        S: Into<Cow<'static, str>>,
+        U: Into<SafeUrl<'a>>,
     {
-        Self(input.into())
+        Self(url.into().into())
+    }
+
+    /// Returns the value of this attribute.
+    pub fn as_str(&self) -> &str {
+        &self.0
     }
 }
 
-impl IntoAttribute for Action {
-    fn into_attribute(self) -> (Cow<'static, str>, Cow<'static, str>) {
+impl<'a> IntoAttribute<'a> for Action<'a> {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
         ("action".into(), self.0)
     }
 }
 
+impl FromAttribute for Action<'static> {
+    const NAME: &'static str = "action";
+
+    fn from_attribute(value: &str) -> Option<Self> {
+        // the value may have been set without being checked (for example
+        // with `raw_attribute`), so it is checked again
+        SafeUrl::parse(value.to_string())
+            .ok()
+            .map(|url| Self(url.into()))
+    }
+}
+
 #[cfg(test)]
 mod test_form {
     use crate::{
@@ -3,7 +3,7 @@ This source code file is distributed subject to the terms of the Mozilla Public
 A copy of this license can be found in the `licenses` directory at the root of this project.
 */
 use crate::{
-    enum_display,
+    enum_attributes, enum_display,
     tags::{meta::Meta, style::StyleTag, title::Title},
     utility_enum,
 };
@@ -11,11 +11,13 @@ use crate::{
 utility_enum!(
     #[allow(missing_docs)]
     /// A node which can be attached to the `<head>` tag.
-    pub enum HeadNode {
-        Title(Title),
-        Meta(Meta),
-        StyleTag(StyleTag),
+    pub enum HeadNode<'a> {
+        Title(Title<'a>),
+        Meta(Meta<'a>),
+        StyleTag(StyleTag<'a>),
     }
 );
 
 enum_display!(HeadNode, Title, Meta, StyleTag);
+
+enum_attributes!(HeadNode, Title, Meta, StyleTag);
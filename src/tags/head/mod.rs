@@ -3,9 +3,10 @@ This source code file is distributed subject to the terms of the Mozilla Public
 A copy of this license can be found in the `licenses` directory at the root of this project.
 */
 
-use std::fmt::Display;
+use std::{borrow::Cow, collections::HashMap, fmt::Display};
 
 use self::head_node::HeadNode;
+use crate::{attribute_enum, attributes::IntoAttributes, utils::write_attributes};
 
 /// Items which can be mounted to head.
 pub mod head_node;
@@ -13,28 +14,29 @@ pub mod head_node;
 #[derive(Default, Debug, Clone)]
 #[must_use]
 /// The `<head>` tag.
-pub struct Head {
-    children: Vec<HeadNode>,
+pub struct Head<'a> {
+    children: Vec<HeadNode<'a>>,
+    attrs: HashMap<Cow<'a, str>, Cow<'a, str>>,
 }
 
-impl Head {
+impl<'a> Head<'a> {
     /// Creates a new [`Head`] tag.
-    pub fn new() -> Head {
+    pub fn new() -> Self {
         Default::default()
     }
 }
 
 /// Creates a new `Head` tag – functionally equivalent to `Head::new()` (but
 /// easier to type.)
-pub fn head() -> Head {
+pub fn head<'a>() -> Head<'a> {
     Head::new()
 }
 
-impl Head {
+impl<'a> Head<'a> {
     /// Add a number of children to this `<head>` tag from an iterator.
     pub fn children<I, C>(mut self, children: I) -> Self
     where
-        C: Into<HeadNode>,
+        C: Into<HeadNode<'a>>,
         I: IntoIterator<Item = C>,
     {
         self.children
@@ -44,16 +46,46 @@ impl Head {
     /// Add a single child to this `<head>` tag.
     pub fn child<C>(mut self, child: C) -> Self
     where
-        C: Into<HeadNode>,
+        C: Into<HeadNode<'a>>,
     {
         self.children.push(child.into());
         self
     }
+
+    crate::define_child_node_accessors!(HeadNode<'a>);
+
+    crate::define_conditional_child_fns!(HeadNode<'a>);
+
+    /// Add an attribute to this `<head>` tag.
+    pub fn attribute<A>(mut self, attribute: A) -> Self
+    where
+        A: Into<HeadAttr<'a>>,
+    {
+        crate::utils::insert_attributes(&mut self.attrs, attribute.into().into_attributes());
+        self
+    }
+
+    crate::define_attribute_helper_fns!(HeadAttr<'a>);
+
+    crate::define_raw_attribute_fn!();
+
+    /// Read an attribute that has been set.
+    pub fn read_attribute(&self, attribute: &str) -> Option<&Cow<'a, str>> {
+        self.attrs.get(attribute)
+    }
 }
 
-impl Display for Head {
+attribute_enum!(
+    pub enum HeadAttr {}
+);
+
+crate::impl_attributes!(Head);
+
+impl Display for Head<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str("<head>")?;
+        f.write_str("<head")?;
+        write_attributes(&self.attrs, f)?;
+        f.write_str(">")?;
         for child in &self.children {
             child.fmt(f)?;
         }
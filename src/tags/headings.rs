@@ -5,11 +5,7 @@ A copy of this license can be found in the `licenses` directory at the root of t
 
 use std::{borrow::Cow, collections::HashMap};
 
-use crate::{
-    heading_display, impl_of_heading_new_fn, into_attribute_for_grouping_enum, into_grouping_union,
-    prelude::{Class, Id, Style},
-    utility_enum,
-};
+use crate::{attribute_enum, heading_display, impl_of_heading_new_fn, into_grouping_union};
 
 use super::body::body_node::BodyNode;
 
@@ -21,9 +17,9 @@ use super::body::body_node::BodyNode;
 /// [MDN's page on this](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/Heading_Elements)
 /// for further information.
 #[must_use]
-pub struct H1 {
-    text: Cow<'static, str>,
-    attrs: HashMap<Cow<'static, str>, Cow<'static, str>>,
+pub struct H1<'a> {
+    pub(crate) text: Cow<'a, str>,
+    attrs: HashMap<Cow<'a, str>, Cow<'a, str>>,
 }
 
 impl_of_heading_new_fn!(H1, h1);
@@ -40,9 +36,9 @@ heading_display!(H1);
 /// [MDN's page on this](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/Heading_Elements)
 /// for further information.
 #[must_use]
-pub struct H2 {
-    text: Cow<'static, str>,
-    attrs: HashMap<Cow<'static, str>, Cow<'static, str>>,
+pub struct H2<'a> {
+    pub(crate) text: Cow<'a, str>,
+    attrs: HashMap<Cow<'a, str>, Cow<'a, str>>,
 }
 
 impl_of_heading_new_fn!(H2, h2);
@@ -59,9 +55,9 @@ heading_display!(H2);
 /// [MDN's page on this](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/Heading_Elements)
 /// for further information.
 #[must_use]
-pub struct H3 {
-    text: Cow<'static, str>,
-    attrs: HashMap<Cow<'static, str>, Cow<'static, str>>,
+pub struct H3<'a> {
+    pub(crate) text: Cow<'a, str>,
+    attrs: HashMap<Cow<'a, str>, Cow<'a, str>>,
 }
 
 impl_of_heading_new_fn!(H3, h3);
@@ -78,9 +74,9 @@ heading_display!(H3);
 /// [MDN's page on this](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/Heading_Elements)
 /// for further information.
 #[must_use]
-pub struct H4 {
-    text: Cow<'static, str>,
-    attrs: HashMap<Cow<'static, str>, Cow<'static, str>>,
+pub struct H4<'a> {
+    pub(crate) text: Cow<'a, str>,
+    attrs: HashMap<Cow<'a, str>, Cow<'a, str>>,
 }
 
 impl_of_heading_new_fn!(H4, h4);
@@ -97,9 +93,9 @@ heading_display!(H4);
 /// [MDN's page on this](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/Heading_Elements)
 /// for further information.
 #[must_use]
-pub struct H5 {
-    text: Cow<'static, str>,
-    attrs: HashMap<Cow<'static, str>, Cow<'static, str>>,
+pub struct H5<'a> {
+    pub(crate) text: Cow<'a, str>,
+    attrs: HashMap<Cow<'a, str>, Cow<'a, str>>,
 }
 
 impl_of_heading_new_fn!(H5, h5);
@@ -116,9 +112,9 @@ heading_display!(H5);
 /// [MDN's page on this](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/Heading_Elements)
 /// for further information.
 #[must_use]
-pub struct H6 {
-    text: Cow<'static, str>,
-    attrs: HashMap<Cow<'static, str>, Cow<'static, str>>,
+pub struct H6<'a> {
+    pub(crate) text: Cow<'a, str>,
+    attrs: HashMap<Cow<'a, str>, Cow<'a, str>>,
 }
 
 impl_of_heading_new_fn!(H6, h6);
@@ -127,23 +123,11 @@ into_grouping_union!(H6, BodyNode);
 
 heading_display!(H6);
 
-utility_enum!(
+attribute_enum!(
     /// An attribute for a heading tag.
-    #[allow(missing_docs)]
     #[must_use]
-    pub enum HeadingAttr {
-        Class(Class),
-        Id(Id),
-        Style(Style),
-    }
+    pub enum HeadingAttr {}
 );
-into_attribute_for_grouping_enum!(HeadingAttr, Class, Id, Style);
-
-into_grouping_union!(Class, HeadingAttr);
-
-into_grouping_union!(Id, HeadingAttr);
-
-into_grouping_union!(Style, HeadingAttr);
 
 #[test]
 fn test_headings() {
@@ -3,30 +3,35 @@ This source code file is distributed subject to the terms of the Mozilla Public
 A copy of this license can be found in the `licenses` directory at the root of this project.
 */
 
-use std::fmt::Display;
+use std::{borrow::Cow, collections::HashMap, fmt::Display};
 
 use super::{body::Body, head::Head};
+use crate::{attribute_enum, attributes::IntoAttributes, utils::write_attributes};
 
 #[derive(Clone, Debug, Default)]
 
 /// Construct a HTML document. If you are trying to render to a string, this is
 /// what you want to use.
 #[must_use]
-pub struct Html {
-    head: Head,
-    body: Body,
+pub struct Html<'a> {
+    pub(crate) head: Head<'a>,
+    pub(crate) body: Body<'a>,
+    attrs: HashMap<Cow<'a, str>, Cow<'a, str>>,
 }
 
 /// Creates a new `Html` tag – functionally equivalent to `Html::new()` (but
 /// easier to type.)
-pub fn html() -> Html {
+pub fn html<'a>() -> Html<'a> {
     Html::new()
 }
 
-impl Display for Html {
+impl Display for Html<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        crate::ids::record_on_render(self);
         f.write_str("<!DOCTYPE html>")?;
-        f.write_str("<html>")?;
+        f.write_str("<html")?;
+        write_attributes(&self.attrs, f)?;
+        f.write_str(">")?;
         self.head.fmt(f)?;
         self.body.fmt(f)?;
         f.write_str("</html>")?;
@@ -34,7 +39,7 @@ impl Display for Html {
     }
 }
 
-impl Html {
+impl<'a> Html<'a> {
     /// Create a new `Html` tag. Note that this is exactly the same as
     /// `Html::default()`, but it is a few characters shorter, so is
     /// provided as a convenience method.
@@ -43,14 +48,44 @@ impl Html {
     }
 
     /// Attach a `<head>` tag to this `Html` instance.
-    pub fn head(mut self, head: Head) -> Self {
+    pub fn head(mut self, head: Head<'a>) -> Self {
         self.head = head;
         self
     }
 
     /// Attach a new `<body>` tag to this `Html` instance.
-    pub fn body(mut self, body: Body) -> Self {
+    pub fn body(mut self, body: Body<'a>) -> Self {
         self.body = body;
         self
     }
+
+    /// Attach an attribute to the `<html>` tag (for example the `lang`
+    /// attribute, which specifies the language of the document).
+    ///
+    /// ```
+    /// # use malvolio::prelude::*;
+    /// Html::new().attribute(Lang::new("en"));
+    /// ```
+    pub fn attribute<A>(mut self, attribute: A) -> Self
+    where
+        A: Into<HtmlAttr<'a>>,
+    {
+        crate::utils::insert_attributes(&mut self.attrs, attribute.into().into_attributes());
+        self
+    }
+
+    crate::define_attribute_helper_fns!(HtmlAttr<'a>);
+
+    crate::define_raw_attribute_fn!();
+
+    /// Read an attribute that has been set.
+    pub fn read_attribute(&self, attribute: &str) -> Option<&Cow<'a, str>> {
+        self.attrs.get(attribute)
+    }
 }
+
+attribute_enum!(
+    pub enum HtmlAttr {}
+);
+
+crate::impl_attributes!(Html);
@@ -1,121 +1,143 @@
 use std::{borrow::Cow, collections::HashMap, fmt::Display};
 
 use crate::{
-    attributes::IntoAttribute, into_attribute_for_grouping_enum, into_grouping_union, utility_enum,
+    attribute_enum,
+    attributes::{FromAttribute, IntoAttribute, IntoAttributes},
+    from_attribute_via_new, into_grouping_union,
+    url::SafeUrl,
 };
 
+use super::body::body_node::BodyNode;
+
 #[derive(Debug, Default, Clone)]
 
 /// The `<img>` tag.
 #[must_use]
-pub struct Img {
-    attrs: HashMap<Cow<'static, str>, Cow<'static, str>>,
+pub struct Img<'a> {
+    attrs: HashMap<Cow<'a, str>, Cow<'a, str>>,
 }
 
-impl Img {
+impl<'a> Img<'a> {
     /// Creates a new [`Img`] tag.
-    pub fn new() -> Img {
+    pub fn new() -> Self {
         Default::default()
     }
 }
 
 /// Creates a new `Img` tag – functionally equivalent to `Img::new()` (but
 /// easier to type.)
-pub fn img() -> Img {
+pub fn img<'a>() -> Img<'a> {
     Img::new()
 }
 
-impl Display for Img {
+impl Display for Img<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str("<img")?;
-        for attr in &self.attrs {
-            f.write_str(" ")?;
-            attr.0.fmt(f)?;
-            f.write_str("=\"")?;
-            attr.1.fmt(f)?;
-            f.write_str("\"")?;
-        }
-        f.write_str("\"")?;
+        crate::utils::write_attributes(&self.attrs, f)?;
         f.write_str("/>")
     }
 }
 
-impl Img {
+into_grouping_union!(Img, BodyNode);
+
+impl<'a> Img<'a> {
     /// Attach an attribute to the `<img>` tag in question.
     pub fn attribute<A>(mut self, attribute: A) -> Self
     where
-        A: Into<ImgAttr>,
+        A: Into<ImgAttr<'a>>,
     {
-        let res = attribute.into().into_attribute();
-        self.attrs.insert(res.0, res.1);
+        crate::utils::insert_attributes(&mut self.attrs, attribute.into().into_attributes());
         self
     }
 
+    crate::define_attribute_helper_fns!(ImgAttr<'a>);
+
     /// Read an attribute that has been set.
-    pub fn read_attribute(&self, attribute: &'static str) -> Option<&Cow<'static, str>> {
+    pub fn read_attribute(&self, attribute: &str) -> Option<&Cow<'a, str>> {
         self.attrs.get(attribute)
     }
 }
 
-utility_enum!(
+attribute_enum!(
     pub enum ImgAttr {
-        /// The `alt` attribute.
-        Alt(Alt),
-        /// The `src` attribute.
-        Src(Src),
+        Alt<'a>,
+        Src<'a>,
     }
 );
 
-into_attribute_for_grouping_enum!(ImgAttr, Alt, Src);
+crate::impl_attributes!(Img);
 
 #[derive(Debug, Clone)]
 /// The `alt` attribute.
-pub struct Alt {
-    value: Cow<'static, str>,
+pub struct Alt<'a> {
+    value: Cow<'a, str>,
 }
 
-into_grouping_union!(Alt, ImgAttr);
-
-impl Alt {
+impl<'a> Alt<'a> {
     /// Construct a new instance of this attribute.
     pub fn new<C>(c: C) -> Self
     where
-        C: Into<Cow<'static, str>>,
+        C: Into<Cow<'a, str>>,
     {
         Self { value: c.into() }
     }
+
+    /// Returns the value of this attribute.
+    pub fn as_str(&self) -> &str {
+        &self.value
+    }
 }
 
-impl IntoAttribute for Alt {
-    fn into_attribute(self) -> (Cow<'static, str>, Cow<'static, str>) {
+impl<'a> IntoAttribute<'a> for Alt<'a> {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
         ("alt".into(), self.value)
     }
 }
 
+from_attribute_via_new!(Alt, "alt");
+
 #[derive(Debug, Clone)]
 /// The `src` attribute.
-pub struct Src {
-    src: Cow<'static, str>,
+pub struct Src<'a> {
+    src: Cow<'a, str>,
 }
 
-into_grouping_union!(Src, ImgAttr);
-
-impl Src {
-    /// Construct a new instance of this attribute.
-    pub fn new<C>(c: C) -> Self
+impl<'a> Src<'a> {
+    /// Construct a new instance of this attribute. This method accepts a
+    /// [`SafeUrl`], or any string (which is checked with `SafeUrl::sanitize`).
+    pub fn new<U>(url: U) -> Self
     where
-        C: Into<Cow<'static, str>>,
+        U: Into<SafeUrl<'a>>,
     {
-        Self { src: c.into() }
+        Self {
+            src: url.into().into(),
+        }
+    }
+
+    /// Returns the value of this attribute.
+    pub fn as_str(&self) -> &str {
+        &self.src
     }
 }
 
-impl IntoAttribute for Src {
-    fn into_attribute(self) -> (Cow<'static, str>, Cow<'static, str>) {
+impl<'a> IntoAttribute<'a> for Src<'a> {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
         ("src".into(), self.src)
     }
 }
 
+impl FromAttribute for Src<'static> {
+    const NAME: &'static str = "src";
+
+    fn from_attribute(value: &str) -> Option<Self> {
+        // the value may have been set without being checked (for example
+        // with `raw_attribute`), so it is checked again
+        SafeUrl::parse(value.to_string())
+            .ok()
+            .map(|url| Self { src: url.into() })
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::prelude::*;
@@ -136,4 +158,15 @@ mod test {
             "An animated picture of a cat doing some humorous task."
         );
     }
+
+    #[test]
+    fn test_img_is_well_formed() {
+        assert_eq!(
+            Img::new()
+                .attribute(Src::new("cat.jpeg"))
+                .attribute(Alt::new("A cat"))
+                .to_string(),
+            "<img alt=\"A cat\" src=\"cat.jpeg\"/>"
+        );
+    }
 }
@@ -3,10 +3,9 @@ This source code file is distributed subject to the terms of the Mozilla Public
 A copy of this license can be found in the `licenses` directory at the root of this project.
 */
 use crate::{
-    attributes::IntoAttribute,
-    into_attribute_for_grouping_enum, into_grouping_union,
-    prelude::{Class, Id, Style},
-    utility_enum,
+    attribute_enum,
+    attributes::{common::IdRef, FromAttribute, IntoAttribute, IntoAttributes},
+    from_attribute_via_new, into_grouping_union,
 };
 
 use std::{borrow::Cow, collections::HashMap, fmt::Display};
@@ -20,52 +19,47 @@ use super::body::body_node::BodyNode;
 /// See the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/input)
 /// for further information.
 #[must_use]
-pub struct Input {
-    attrs: HashMap<Cow<'static, str>, Cow<'static, str>>,
+pub struct Input<'a> {
+    attrs: HashMap<Cow<'a, str>, Cow<'a, str>>,
 }
 
 /// Creates a new [`Input`] tag.
-pub fn input() -> Input {
+pub fn input<'a>() -> Input<'a> {
     Input::new()
 }
 
-impl Input {
+impl<'a> Input<'a> {
     /// Creates a new [`Input`] tag.
-    pub fn new() -> Input {
+    pub fn new() -> Self {
         Default::default()
     }
 }
 
-impl Display for Input {
+impl Display for Input<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str("<input")?;
-        for attr in &self.attrs {
-            f.write_str(" ")?;
-            attr.0.fmt(f)?;
-            f.write_str("=\"")?;
-            attr.1.fmt(f)?;
-            f.write_str("\"")?;
-        }
+        crate::utils::write_attributes(&self.attrs, f)?;
         f.write_str("/>")
     }
 }
 
 into_grouping_union!(Input, BodyNode);
 
-impl Input {
+impl<'a> Input<'a> {
     #[inline(always)]
     /// Attach a new attribute to this type.
     pub fn attribute<C>(mut self, c: C) -> Self
     where
-        C: Into<InputAttr>,
+        C: Into<InputAttr<'a>>,
     {
-        let (a, b) = c.into().into_attribute();
-        self.attrs.insert(a, b);
+        crate::utils::insert_attributes(&mut self.attrs, c.into().into_attributes());
         self
     }
 
+    crate::define_attribute_helper_fns!(InputAttr<'a>);
+
     /// Read an attribute that has been set
-    pub fn read_attribute(&self, attribute: &'static str) -> Option<&Cow<'static, str>> {
+    pub fn read_attribute(&self, attribute: &str) -> Option<&Cow<'a, str>> {
         self.attrs.get(attribute)
     }
 
@@ -78,29 +72,18 @@ impl Input {
     }
 }
 
-utility_enum!(
+attribute_enum!(
     /// The possible attributes which can be attached to an input item.
-    #[allow(missing_docs)]
     pub enum InputAttr {
-        Type(Type),
-        Name(Name),
-        Placeholder(Placeholder),
-        Id(Id),
-        Class(Class),
-        Value(Value),
-        Style(Style),
+        Type,
+        Name<'a>,
+        Placeholder<'a>,
+        Value<'a>,
+        List<'a>,
     }
 );
 
-into_attribute_for_grouping_enum!(InputAttr, Type, Name, Placeholder, Id, Class, Value, Style);
-
-into_grouping_union!(Id, InputAttr);
-into_grouping_union!(Class, InputAttr);
-into_grouping_union!(Value, InputAttr);
-into_grouping_union!(Style, InputAttr);
-into_grouping_union!(Name, InputAttr);
-into_grouping_union!(Type, InputAttr);
-into_grouping_union!(Placeholder, InputAttr);
+crate::impl_attributes!(Input);
 
 /// The `type` attribute for an input.
 ///
@@ -119,8 +102,8 @@ pub enum Type {
     Checkbox,
 }
 
-impl IntoAttribute for Type {
-    fn into_attribute(self) -> (Cow<'static, str>, Cow<'static, str>) {
+impl<'a> IntoAttribute<'a> for Type {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
         (
             "type".into(),
             match self {
@@ -138,28 +121,53 @@ impl IntoAttribute for Type {
     }
 }
 
+impl FromAttribute for Type {
+    const NAME: &'static str = "type";
+
+    fn from_attribute(value: &str) -> Option<Self> {
+        match value {
+            "text" => Some(Type::Text),
+            "email" => Some(Type::Email),
+            "password" => Some(Type::Password),
+            "submit" => Some(Type::Submit),
+            "textarea" => Some(Type::Textarea),
+            "hidden" => Some(Type::Hidden),
+            "datetime-local" => Some(Type::DateTimeLocal),
+            "checkbox" => Some(Type::Checkbox),
+            _ => None,
+        }
+    }
+}
+
 /// The `name` attribute for an input.
 ///
 /// See the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/input#attr-name)
 /// for further information.
 #[derive(Debug, Clone)]
 
-pub struct Name(Cow<'static, str>);
+pub struct Name<'a>(Cow<'a, str>);
 
-impl IntoAttribute for Name {
-    fn into_attribute(self) -> (Cow<'static, str>, Cow<'static, str>) {
+impl<'a> IntoAttribute<'a> for Name<'a> {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
         ("name".into(), self.0)
     }
 }
 
-impl Name {
+from_attribute_via_new!(Name, "name");
+
+impl<'a> Name<'a> {
     /// Create a new instance of this attribute with the specified value.
     pub fn new<S>(s: S) -> Self
     where
-        S: Into<Cow<'static, str>>,
+        S: Into<Cow<'a, str>>,
     {
         Self(s.into())
     }
+
+    /// Returns the value of this attribute.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
 }
 
 /// The "placeholder" attribute for an input field.
@@ -167,22 +175,29 @@ impl Name {
 /// See the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/input#attr-placeholder)
 /// for further information.
 #[derive(Debug, Clone)]
-pub struct Placeholder(Cow<'static, str>);
+pub struct Placeholder<'a>(Cow<'a, str>);
 
-impl IntoAttribute for Placeholder {
-    fn into_attribute(self) -> (Cow<'static, str>, Cow<'static, str>) {
+impl<'a> IntoAttribute<'a> for Placeholder<'a> {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
         ("placeholder".into(), self.0)
     }
 }
 
-impl Placeholder {
+from_attribute_via_new!(Placeholder, "placeholder");
+
+impl<'a> Placeholder<'a> {
     /// Create a new instance of this attribute with the specified value.
     pub fn new<S>(s: S) -> Self
     where
-        S: Into<Cow<'static, str>>,
+        S: Into<Cow<'a, str>>,
     {
         Self(s.into())
     }
+
+    /// Returns the value of this attribute.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
 }
 
 /// The "value" attribute for an input field.
@@ -190,24 +205,77 @@ impl Placeholder {
 /// See the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/input#attr-value)
 /// for further information.
 #[derive(Debug, Clone)]
-pub struct Value(Cow<'static, str>);
+pub struct Value<'a>(Cow<'a, str>);
 
-impl Value {
+impl<'a> Value<'a> {
     /// Create a new instance of this attribute with the specified value.
     pub fn new<S>(s: S) -> Self
     where
-        S: Into<Cow<'static, str>>,
+        S: Into<Cow<'a, str>>,
     {
         Self(s.into())
     }
+
+    /// Returns the value of this attribute.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
 }
 
-impl IntoAttribute for Value {
-    fn into_attribute(self) -> (Cow<'static, str>, Cow<'static, str>) {
+impl<'a> IntoAttribute<'a> for Value<'a> {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
         ("value".into(), self.0)
     }
 }
 
+from_attribute_via_new!(Value, "value");
+
+/// The "list" attribute, which refers to a `<datalist>` of suggested values
+/// for the input.
+///
+/// See the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/input#attr-list)
+/// for further information.
+#[derive(Debug, Clone)]
+pub struct List<'a>(IdRef<'a>);
+
+impl<'a> List<'a> {
+    /// Create a new instance of this attribute, referring to the `<datalist>`
+    /// with the provided id.
+    pub fn new(id: &IdRef<'a>) -> Self {
+        Self(id.clone())
+    }
+
+    /// Create a new instance of this attribute, referring to the `<datalist>` with
+    /// the specified id **without an [`IdRef`]** (so a misspelled id is only
+    /// found when the document is checked with
+    /// [`IdRegistry`](crate::ids::IdRegistry)).
+    pub fn new_unchecked<C>(id: C) -> Self
+    where
+        C: Into<Cow<'a, str>>,
+    {
+        Self(IdRef::new(id.into()))
+    }
+
+    /// Returns the id of the `<datalist>`.
+    pub fn id(&self) -> &IdRef<'a> {
+        &self.0
+    }
+}
+
+impl<'a> IntoAttribute<'a> for List<'a> {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
+        ("list".into(), self.0.into())
+    }
+}
+
+impl FromAttribute for List<'static> {
+    const NAME: &'static str = "list";
+
+    fn from_attribute(value: &str) -> Option<Self> {
+        Some(Self(IdRef::new(value.to_string().into())))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::prelude::*;
@@ -6,7 +6,11 @@ use std::{borrow::Cow, collections::HashMap};
 
 use super::body::body_node::BodyNode;
 
-use crate::{heading_display, impl_of_heading_new_fn, into_grouping_union};
+use crate::{
+    attribute_enum,
+    attributes::{common::IdRef, FromAttribute, IntoAttribute},
+    heading_display, impl_of_heading_new_fn, into_grouping_union,
+};
 
 #[derive(Debug, Clone)]
 
@@ -15,17 +19,70 @@ use crate::{heading_display, impl_of_heading_new_fn, into_grouping_union};
 /// See the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/label)
 /// for further information.
 #[must_use]
-pub struct Label {
-    text: Cow<'static, str>,
-    attrs: HashMap<Cow<'static, str>, Cow<'static, str>>,
+pub struct Label<'a> {
+    pub(crate) text: Cow<'a, str>,
+    attrs: HashMap<Cow<'a, str>, Cow<'a, str>>,
 }
 
-impl_of_heading_new_fn!(Label, label);
+impl_of_heading_new_fn!(Label, label, LabelAttr<'a>);
 
 heading_display!(Label);
 
 into_grouping_union!(Label, BodyNode);
 
+attribute_enum!(
+    /// The attributes which can be attached to a label.
+    pub enum LabelAttr {
+        For<'a>,
+    }
+);
+
+/// The `for` attribute, which associates a label with the form control (such
+/// as an `<input>`) that it labels.
+///
+/// See the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/HTML/Attributes/for)
+/// for further information.
+#[derive(Debug, Clone)]
+pub struct For<'a>(IdRef<'a>);
+
+impl<'a> For<'a> {
+    /// Create a new `For` attribute, referring to the form control with the
+    /// provided id.
+    pub fn new(id: &IdRef<'a>) -> Self {
+        Self(id.clone())
+    }
+
+    /// Create a new instance of this attribute, referring to the form control with
+    /// the specified id **without an [`IdRef`]** (so a misspelled id is only
+    /// found when the document is checked with
+    /// [`IdRegistry`](crate::ids::IdRegistry)).
+    pub fn new_unchecked<C>(id: C) -> Self
+    where
+        C: Into<Cow<'a, str>>,
+    {
+        Self(IdRef::new(id.into()))
+    }
+
+    /// Returns the id of the form control.
+    pub fn id(&self) -> &IdRef<'a> {
+        &self.0
+    }
+}
+
+impl<'a> IntoAttribute<'a> for For<'a> {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
+        ("for".into(), self.0.into())
+    }
+}
+
+impl FromAttribute for For<'static> {
+    const NAME: &'static str = "for";
+
+    fn from_attribute(value: &str) -> Option<Self> {
+        Some(Self(IdRef::new(value.to_string().into())))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::prelude::*;
@@ -5,7 +5,9 @@ A copy of this license can be found in the `licenses` directory at the root of t
 use std::{borrow::Cow, collections::HashMap, fmt::Display};
 
 use crate::{
-    attributes::IntoAttribute, into_attribute_for_grouping_enum, into_grouping_union, utility_enum,
+    attribute_enum,
+    attributes::{FromAttribute, IntoAttribute, IntoAttributes},
+    from_attribute_via_new, into_grouping_union,
 };
 
 use super::head::head_node::HeadNode;
@@ -18,69 +20,62 @@ use super::head::head_node::HeadNode;
 /// See [MDN's page on this](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/meta) for
 /// further information.
 #[must_use]
-pub struct Meta {
-    attrs: HashMap<Cow<'static, str>, Cow<'static, str>>,
+pub struct Meta<'a> {
+    attrs: HashMap<Cow<'a, str>, Cow<'a, str>>,
 }
 
-impl Meta {
+impl<'a> Meta<'a> {
     /// Creates a new [`Meta`] tag.
-
-    pub fn new() -> Meta {
+    pub fn new() -> Self {
         Default::default()
     }
 }
 
 /// Creates a new `Meta` tag – functionally equivalent to `Meta::new()` (but
 /// easier to type.)
-pub fn meta() -> Meta {
+pub fn meta<'a>() -> Meta<'a> {
     Meta::new()
 }
 
-impl Meta {
+impl<'a> Meta<'a> {
     #[inline(always)]
     /// Add an attribute to this meta tag.
     pub fn attribute<A>(mut self, attr: A) -> Self
     where
-        A: Into<MetaAttr>,
+        A: Into<MetaAttr<'a>>,
     {
-        let (a, b) = attr.into().into_attribute();
-        self.attrs.insert(a, b);
+        crate::utils::insert_attributes(&mut self.attrs, attr.into().into_attributes());
         self
     }
 
+    crate::define_attribute_helper_fns!(MetaAttr<'a>);
+
     /// Read an attribute that has been set
-    pub fn read_attribute(&self, attribute: &'static str) -> Option<&Cow<'static, str>> {
+    pub fn read_attribute(&self, attribute: &str) -> Option<&Cow<'a, str>> {
         self.attrs.get(attribute)
     }
 
     crate::define_raw_attribute_fn!();
 }
 
-impl Display for Meta {
+impl Display for Meta<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str("<meta")?;
-        for attr in &self.attrs {
-            f.write_str(" ")?;
-            attr.0.fmt(f)?;
-            f.write_str("=\"")?;
-            attr.1.fmt(f)?;
-            f.write_str("\"")?;
-        }
+        crate::utils::write_attributes(&self.attrs, f)?;
         f.write_str("/>")
     }
 }
 
 into_grouping_union!(Meta, HeadNode);
 
-utility_enum!(
-    #[allow(missing_docs)]
+attribute_enum!(
     pub enum MetaAttr {
-        Content(Content),
-        MetaName(MetaName),
+        Content<'a>,
+        MetaName,
     }
 );
 
-into_attribute_for_grouping_enum!(MetaAttr, Content, MetaName);
+crate::impl_attributes!(Meta);
 
 /// The "name" attribute for meta tags. This is called `MetaName` to
 /// disambiguate it from other tags.
@@ -106,8 +101,8 @@ pub enum MetaName {
     Viewport,
 }
 
-impl IntoAttribute for MetaName {
-    fn into_attribute(self) -> (Cow<'static, str>, Cow<'static, str>) {
+impl<'a> IntoAttribute<'a> for MetaName {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
         (
             "name".into(),
             match self {
@@ -119,31 +114,46 @@ impl IntoAttribute for MetaName {
     }
 }
 
-into_grouping_union!(MetaName, MetaAttr);
+impl FromAttribute for MetaName {
+    const NAME: &'static str = "name";
+
+    fn from_attribute(value: &str) -> Option<Self> {
+        match value {
+            "charset" => Some(MetaName::Charset),
+            "viewport" => Some(MetaName::Viewport),
+            _ => None,
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 
 /// The "content" attribute for a `<meta>` tag.
-pub struct Content(Cow<'static, str>);
+pub struct Content<'a>(Cow<'a, str>);
 
-impl Content {
+impl<'a> Content<'a> {
     /// Create a new "content" attribute, which can then be applied to a meta
     /// tag.
     pub fn new<C>(c: C) -> Self
     where
-        C: Into<Cow<'static, str>>,
+        C: Into<Cow<'a, str>>,
     {
         Self(c.into())
     }
+
+    /// Returns the value of this attribute.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
 }
 
-impl IntoAttribute for Content {
-    fn into_attribute(self) -> (Cow<'static, str>, Cow<'static, str>) {
+impl<'a> IntoAttribute<'a> for Content<'a> {
+    fn into_attribute(self) -> (Cow<'a, str>, Cow<'a, str>) {
         ("content".into(), self.0)
     }
 }
 
-into_grouping_union!(Content, MetaAttr);
+from_attribute_via_new!(Content, "content");
 
 #[cfg(test)]
 mod test {
@@ -1,7 +1,12 @@
 use crate::prelude::BodyNode;
-use std::{borrow::Cow, fmt::Display};
+use std::{borrow::Cow, collections::HashMap, fmt::Display};
 
-use crate::into_grouping_union;
+use crate::{
+    attribute_enum,
+    attributes::IntoAttributes,
+    into_grouping_union,
+    utils::{escape_escapable_raw_text, escape_text, write_attributes},
+};
 
 #[derive(Debug, Clone)]
 
@@ -11,30 +16,75 @@ use crate::into_grouping_union;
 /// See [MDN's page on this](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/noscript) for
 /// further information.
 #[must_use]
-pub struct NoScript {
-    text: Cow<'static, str>,
+pub struct NoScript<'a> {
+    pub(crate) text: Cow<'a, str>,
+    attrs: HashMap<Cow<'a, str>, Cow<'a, str>>,
 }
 
 /// Creates a new `NoScript` tag – functionally equivalent to
 /// `NoScript::new(<text>)` (but easier to type.)
-pub fn noscript(text: impl Into<Cow<'static, str>>) -> NoScript {
+pub fn noscript<'a>(text: impl Into<Cow<'a, str>>) -> NoScript<'a> {
     NoScript::new(text)
 }
 
-impl NoScript {
-    /// Construct a new `<noscript>` tag.
+impl<'a> NoScript<'a> {
+    /// Construct a new `<noscript>` tag containing some text, escaping it
+    /// first (so that any HTML in the text is displayed as it is, rather than
+    /// being interpreted).
     pub fn new<T>(text: T) -> Self
     where
-        T: Into<Cow<'static, str>>,
+        T: Into<Cow<'a, str>>,
     {
-        Self { text: text.into() }
+        Self {
+            text: escape_text(text.into()),
+            attrs: HashMap::new(),
+        }
+    }
+
+    /// Construct a new `<noscript>` tag containing some HTML, sanitizing it
+    /// first (using the policy set with `SanitizePolicy::scope`, or
+    /// `ammonia`'s default policy).
+    pub fn html(html: &str) -> Self {
+        Self {
+            text: crate::sanitize::clean(html).into(),
+            attrs: HashMap::new(),
+        }
+    }
+
+    /// Attach an attribute to this tag.
+    pub fn attribute<A>(mut self, attribute: A) -> Self
+    where
+        A: Into<NoScriptAttr<'a>>,
+    {
+        crate::utils::insert_attributes(&mut self.attrs, attribute.into().into_attributes());
+        self
+    }
+
+    crate::define_attribute_helper_fns!(NoScriptAttr<'a>);
+
+    crate::define_raw_attribute_fn!();
+
+    /// Read an attribute that has been set.
+    pub fn read_attribute(&self, attribute: &str) -> Option<&Cow<'a, str>> {
+        self.attrs.get(attribute)
     }
 }
 
-impl Display for NoScript {
+attribute_enum!(
+    pub enum NoScriptAttr {}
+);
+
+crate::impl_attributes!(NoScript);
+
+impl Display for NoScript<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str("<noscript>")?;
-        f.write_str(&self.text)?;
+        f.write_str("<noscript")?;
+        write_attributes(&self.attrs, f)?;
+        f.write_str(">")?;
+        // with scripting enabled, the contents are not parsed as HTML, so
+        // would otherwise end at the first `</noscript`, even if it is inside
+        // an attribute value
+        f.write_str(&escape_escapable_raw_text(&self.text, "noscript"))?;
         f.write_str("</noscript>")
     }
 }
@@ -44,6 +94,10 @@ into_grouping_union!(NoScript, BodyNode);
 #[cfg(test)]
 mod test {
     use super::NoScript;
+    use crate::{
+        prelude::*,
+        utils::{escape_text, fuzz_strings},
+    };
 
     #[test]
     fn test_noscript() {
@@ -62,4 +116,34 @@ mod test {
             "No Javascript :)"
         );
     }
+
+    #[test]
+    fn test_contents_can_not_end_the_tag() {
+        let noscript = NoScript::html("<a title=\"</noscript><img src=x onerror=alert(1)>\">a</a>");
+        assert_eq!(
+            noscript.to_string(),
+            "<noscript><a title=\"&lt;/noscript><img src=x onerror=alert(1)>\" \
+             rel=\"noopener noreferrer\">a</a></noscript>"
+        );
+
+        let elements = scraper::Selector::parse("*").unwrap();
+        let selector = scraper::Selector::parse("noscript").unwrap();
+        for text in fuzz_strings() {
+            for noscript in [NoScript::new(text.as_str()), NoScript::html(&text)] {
+                let document = Body::new().child(noscript);
+                let document = scraper::Html::parse_document(&document.to_string());
+                // `<html>`, `<head>`, `<body>` and `<noscript>`
+                assert_eq!(document.select(&elements).count(), 4, "{:?}", text);
+            }
+            let document = Body::new().child(NoScript::new(text.as_str()));
+            let document = scraper::Html::parse_document(&document.to_string());
+            let parsed = document.select(&selector).next().unwrap();
+            // scraper parses the document with scripting enabled, so the
+            // contents are not decoded
+            assert_eq!(
+                parsed.text().collect::<String>(),
+                escape_text(text.as_str().into())
+            );
+        }
+    }
 }
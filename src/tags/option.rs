@@ -4,12 +4,12 @@ A copy of this license can be found in the `licenses` directory at the root of t
 */
 
 use crate::{
-    into_attribute_for_grouping_enum, into_grouping_union, prelude::Id, utility_enum,
+    attribute_enum,
+    sanitize::{SanitizePolicy, TrustedHtml},
     utils::write_attributes,
 };
 
-use crate::attributes::IntoAttribute;
-use ammonia::clean;
+use crate::attributes::IntoAttributes;
 use std::{borrow::Cow, collections::HashMap, fmt::Display};
 
 use super::input::{Name, Value};
@@ -21,80 +21,100 @@ use super::input::{Name, Value};
 /// See [MDN's page on this](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/option) for
 /// further information.
 #[must_use]
-pub struct SelectOption {
-    attrs: HashMap<Cow<'static, str>, Cow<'static, str>>,
-    text: Cow<'static, str>,
+pub struct SelectOption<'a> {
+    attrs: HashMap<Cow<'a, str>, Cow<'a, str>>,
+    text: Cow<'a, str>,
 }
 
-impl SelectOption {
+impl<'a> SelectOption<'a> {
     /// Creates a new [`SelectOption`] tag.
-    pub fn new() -> SelectOption {
+    pub fn new() -> Self {
         Default::default()
     }
 }
 
 /// Creates a new `SelectOption` tag – functionally equivalent to
 /// `SelectOption::new()` (but easier to type.)
-pub fn select_option() -> SelectOption {
+pub fn select_option<'a>() -> SelectOption<'a> {
     Default::default()
 }
 
-impl SelectOption {
+impl<'a> SelectOption<'a> {
     /// Adds the supplied text to this node, overwriting the previously existing
     /// text (if text has already been added to the node).
     ///
-    /// This method sanitises the input (i.e. it escapes HTML);
-    /// this might not be what you want – if you are *absolutely certain* that
-    /// the text you are providing does not come from a potentially
-    /// malicious source (e.g. user-supplied text can contain script tags
-    /// which will execute unwanted code) you can use `text_unsanitized` which
-    /// is identical to this method, except for that it does not sanitise the
-    /// inputted text (and is thus slightly faster).
+    /// This method escapes the input (so any HTML in it is displayed as it
+    /// is, rather than being interpreted). If the text is HTML which should be
+    /// rendered (e.g. user-supplied rich text), use `html` instead, which
+    /// sanitises it. If you are *absolutely certain* that the HTML you are
+    /// providing does not come from a potentially malicious source (e.g.
+    /// user-supplied text can contain script tags which will execute unwanted
+    /// code) you can use `text_unsanitized`, which adds it as it is.
     pub fn text<S>(mut self, text: S) -> Self
     where
-        S: Into<Cow<'static, str>>,
+        S: Into<Cow<'a, str>>,
     {
-        self.text = clean(&text.into()).into();
+        self.text = crate::utils::escape_text(text.into());
+        self
+    }
+
+    /// Adds the supplied HTML to this node (overwriting any existing text),
+    /// sanitising it first (using the policy set with `SanitizePolicy::scope`,
+    /// or `ammonia`'s default policy).
+    pub fn html<S>(mut self, html: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.text = crate::sanitize::clean(&html.into()).into();
+        self
+    }
+
+    /// Adds the supplied HTML to this node (in the same way as `html`),
+    /// sanitising it according to the provided policy.
+    pub fn html_with_policy<S>(mut self, html: S, policy: &SanitizePolicy) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.text = policy.clean(&html.into()).into();
         self
     }
 
     /// Adds the supplied text to this node, overwriting the previously existing
     /// text (if text has already been added to the node).
     ///
-    /// WARNING: Do not (under any circumstances) use this method with unescaped
-    /// user-supplied text. It will be rendered and poses a major security
-    /// threat to your application. If in doubt, use the `text` method
-    /// instead of this one (the risk is much lower that way).
-    pub fn text_unsanitized<S>(mut self, text: S) -> Self
-    where
-        S: Into<Cow<'static, str>>,
-    {
-        self.text = text.into();
+    /// The HTML is not sanitized, so it must be a [`TrustedHtml`] (create
+    /// one with `TrustedHtml::dangerously_trust` if you are *absolutely
+    /// certain* that the HTML does not contain any user-supplied content).
+    pub fn text_unsanitized(mut self, html: TrustedHtml<'a>) -> Self {
+        self.text = html.into();
         self
     }
 
     /// Attach a new attribute to this type. Note that this will overwrite
-    /// existing values for the attribute, if one has been provided.
+    /// existing values for the attribute, if one has been provided (apart from
+    /// `Class` and `Style`, which are merged with the existing values – use
+    /// `replace_attribute` to overwrite them).
     pub fn attribute<A>(mut self, attr: A) -> Self
     where
-        A: Into<SelectOptionAttr>,
+        A: Into<SelectOptionAttr<'a>>,
     {
-        let (a, b) = attr.into().into_attribute();
-        self.attrs.insert(a, b);
+        crate::utils::insert_attributes(&mut self.attrs, attr.into().into_attributes());
         self
     }
 
+    crate::define_attribute_helper_fns!(SelectOptionAttr<'a>);
+
     crate::define_raw_attribute_fn!();
 
     /// Read an attribute that has been set
-    pub fn read_attribute(&self, attribute: &'static str) -> Option<&Cow<'static, str>> {
+    pub fn read_attribute(&self, attribute: &str) -> Option<&Cow<'a, str>> {
         self.attrs.get(attribute)
     }
 }
 
-impl Display for SelectOption {
+impl Display for SelectOption<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str("<option ")?;
+        f.write_str("<option")?;
         write_attributes(&self.attrs, f)?;
         f.write_str(">")?;
         self.text.fmt(f)?;
@@ -102,18 +122,12 @@ impl Display for SelectOption {
     }
 }
 
-utility_enum!(
-    /// An attribute for the `<select>` tag.
-    #[allow(missing_docs)]
+attribute_enum!(
+    /// An attribute for the `<option>` tag.
     pub enum SelectOptionAttr {
-        Value(Value),
-        Id(Id),
-        Name(Name),
+        Value<'a>,
+        Name<'a>,
     }
 );
 
-into_attribute_for_grouping_enum!(SelectOptionAttr, Value, Id, Name);
-
-into_grouping_union!(Value, SelectOptionAttr);
-into_grouping_union!(Id, SelectOptionAttr);
-into_grouping_union!(Name, SelectOptionAttr);
+crate::impl_attributes!(SelectOption);
@@ -4,16 +4,14 @@ A copy of this license can be found in the `licenses` directory at the root of t
 */
 use std::{borrow::Cow, collections::HashMap, fmt::Display};
 
-use ammonia::clean;
-
 use super::body::body_node::BodyNode;
 
 use crate::{
-    attributes::IntoAttribute,
-    into_attribute_for_grouping_enum, into_grouping_union,
-    prelude::{Class, Id},
+    attribute_enum,
+    attributes::IntoAttributes,
+    into_grouping_union,
+    sanitize::{SanitizePolicy, TrustedHtml},
     text::Text,
-    utility_enum,
     utils::write_attributes,
 };
 
@@ -23,23 +21,23 @@ use crate::{
 /// info.
 #[derive(Default, Debug, Clone)]
 #[must_use]
-pub struct P {
-    pub(crate) attrs: HashMap<Cow<'static, str>, Cow<'static, str>>,
-    pub(crate) text: Cow<'static, str>,
-    pub(crate) children: Vec<BodyNode>,
+pub struct P<'a> {
+    pub(crate) attrs: HashMap<Cow<'a, str>, Cow<'a, str>>,
+    pub(crate) text: Cow<'a, str>,
+    pub(crate) children: Vec<BodyNode<'a>>,
 }
 
 /// Creates a new `P` tag – functionally equivalent to `P::new()` (but easier to
 /// type.)
-pub fn p() -> P {
+pub fn p<'a>() -> P<'a> {
     P::default()
 }
 
 into_grouping_union!(P, BodyNode);
 
-impl Display for P {
+impl Display for P<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str("<p ")?;
+        f.write_str("<p")?;
         write_attributes(&self.attrs, f)?;
         f.write_str(">")?;
         self.text.fmt(f)?;
@@ -50,100 +48,135 @@ impl Display for P {
     }
 }
 
-impl P {
-    /// Create a new paragraph with the provided text, sanitising it first.
-    pub fn new(text: impl AsRef<str>) -> Self {
+impl<'a> P<'a> {
+    /// Create a new paragraph with the provided text, escaping it first.
+    pub fn new(text: impl Into<Cow<'a, str>>) -> Self {
         Self::with_text(text)
     }
 
     /// A method to construct a paragraph containing the supplied text. This
-    /// will sanitise the text provided beforehand.
+    /// will escape the text provided beforehand (so that any HTML in it is
+    /// displayed as it is, rather than being interpreted).
     pub fn with_text<S>(text: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        Self {
+            text: crate::utils::escape_text(text.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Construct a paragraph from some HTML (e.g. user-supplied rich text),
+    /// sanitising it first (using the policy set with `SanitizePolicy::scope`,
+    /// or `ammonia`'s default policy).
+    pub fn with_html<S>(html: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        Self {
+            text: crate::sanitize::clean(html.as_ref()).into(),
+            ..Default::default()
+        }
+    }
+
+    /// Construct a paragraph from some HTML, sanitising it according to the
+    /// provided policy.
+    pub fn with_policy<S>(text: S, policy: &SanitizePolicy) -> Self
     where
         S: AsRef<str>,
     {
         Self {
-            text: clean(text.as_ref()).into(),
+            text: policy.clean(text.as_ref()).into(),
             ..Default::default()
         }
     }
 
-    /// Create a new `<p>` tag, without sanitising the text first.
-    pub fn new_unchecked(text: impl Into<Cow<'static, str>>) -> Self {
+    /// Create a new `<p>` tag from some HTML, without sanitising it first (see
+    /// [`TrustedHtml`]).
+    pub fn new_unchecked(html: TrustedHtml<'a>) -> Self {
         Self {
-            text: text.into(),
+            text: html.into(),
             ..Default::default()
         }
     }
 
     /// Attach a new child to this tag.
-    pub fn child(mut self, child: impl Into<BodyNode>) -> Self {
-        self.children.push(child.into());
+    pub fn child(mut self, child: impl Into<BodyNode<'a>>) -> Self {
+        crate::utils::push_child(&mut self.children, child.into());
         self
     }
 
     /// Add new children to this tag from an iterator.
-    pub fn children(mut self, children: impl IntoIterator<Item = BodyNode>) -> Self {
-        self.children.extend(children);
+    pub fn children<I, C>(mut self, children: I) -> Self
+    where
+        I: IntoIterator<Item = C>,
+        C: Into<BodyNode<'a>>,
+    {
+        crate::utils::extend_children(&mut self.children, children);
         self
     }
 
+    crate::define_child_node_accessors!(BodyNode<'a>);
+
+    crate::define_conditional_child_fns!(BodyNode<'a>);
+
     /// Adds the supplied text to this node, overwriting the previously existing
     /// text (if text has already been added to the node).
     ///
-    /// This method sanitises the input (i.e. it escapes HTML);
-    /// this might not be what you want – if you are *absolutely certain* that
-    /// the text you are providing does not come from a potentially
-    /// malicious source (e.g. user-supplied text can contain script tags
-    /// which will execute unwanted code) you can use `text_unsanitized` which
-    /// is identical to this method, except for that it does not sanitise the
-    /// inputted text (and is thus slightly faster).
+    /// This method escapes the input (so any HTML in it is displayed as it
+    /// is, rather than being interpreted). If the text is HTML which should be
+    /// rendered (e.g. user-supplied rich text), use `html` instead, which
+    /// sanitises it. If you are *absolutely certain* that the HTML you are
+    /// providing does not come from a potentially malicious source (e.g.
+    /// user-supplied text can contain script tags which will execute unwanted
+    /// code) you can use `text_unsanitized`, which adds it as it is.
     pub fn text<S>(self, text: S) -> Self
     where
-        S: Into<Cow<'static, str>>,
+        S: Into<Cow<'a, str>>,
     {
         self.child(BodyNode::Text(Text::new(text.into())))
     }
 
+    /// Adds the supplied HTML to this node (as a text node), sanitising it
+    /// first (using the policy set with `SanitizePolicy::scope`, or `ammonia`'s
+    /// default policy).
+    pub fn html<S>(self, html: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.child(BodyNode::Text(Text::html(html.into())))
+    }
+
     /// Adds the supplied text to this node, overwriting the previously existing
     /// text (if text has already been added to the node).
     ///
-    /// WARNING: Do not (under any circumstances) use this method with unescaped
-    /// user-supplied text. It will be rendered and poses a major security
-    /// threat to your application. If in doubt, use the `text` method
-    /// instead of this one (the risk is much lower that way).
-    pub fn text_unsanitized<S>(self, text: S) -> Self
-    where
-        S: Into<Cow<'static, str>>,
-    {
-        self.child(BodyNode::Text(Text::new_unchecked(text.into())))
+    /// The HTML is not sanitized, so it must be a [`TrustedHtml`] (create
+    /// one with `TrustedHtml::dangerously_trust` if you are *absolutely
+    /// certain* that the HTML does not contain any user-supplied content).
+    pub fn text_unsanitized(self, html: TrustedHtml<'a>) -> Self {
+        self.child(BodyNode::Text(Text::new_unchecked(html)))
     }
 
     /// Set the specified attribute on this `P` tag.
-    pub fn attribute(mut self, attr: impl Into<PAttr>) -> Self {
-        let (key, value) = attr.into().into_attribute();
-        self.attrs.insert(key, value);
+    pub fn attribute(mut self, attr: impl Into<PAttr<'a>>) -> Self {
+        crate::utils::insert_attributes(&mut self.attrs, attr.into().into_attributes());
         self
     }
 
+    crate::define_attribute_helper_fns!(PAttr<'a>);
+
     /// Read an attribute from this tag, if it exists.
-    pub fn read_attribute(&self, key: impl Into<Cow<'static, str>>) -> Option<&Cow<'static, str>> {
+    pub fn read_attribute(&self, key: impl Into<Cow<'a, str>>) -> Option<&Cow<'a, str>> {
         self.attrs.get(&key.into())
     }
 }
 
-utility_enum! {
-    #[allow(missing_docs)]
-    pub enum PAttr {
-        Id(Id),
-        Class(Class)
-    }
-}
-
-into_attribute_for_grouping_enum!(PAttr, Id, Class);
+attribute_enum!(
+    pub enum PAttr {}
+);
 
-into_grouping_union!(Id, PAttr);
-into_grouping_union!(Class, PAttr);
+crate::impl_attributes!(P);
 
 #[cfg(test)]
 mod test {
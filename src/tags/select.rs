@@ -5,14 +5,9 @@ A copy of this license can be found in the `licenses` directory at the root of t
 
 use std::{borrow::Cow, collections::HashMap, fmt::Display};
 
-use crate::{
-    into_attribute_for_grouping_enum, into_grouping_union,
-    prelude::{Class, Id},
-    utility_enum,
-    utils::write_attributes,
-};
+use crate::{attribute_enum, into_grouping_union, utils::write_attributes};
 
-use crate::attributes::IntoAttribute;
+use crate::attributes::IntoAttributes;
 
 use super::{body::body_node::BodyNode, input::Name, option::SelectOption};
 
@@ -23,29 +18,29 @@ use super::{body::body_node::BodyNode, input::Name, option::SelectOption};
 /// See [MDN's page on this](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/select) for
 /// further information.
 #[must_use]
-pub struct Select {
-    attrs: HashMap<Cow<'static, str>, Cow<'static, str>>,
-    children: Vec<SelectOption>,
+pub struct Select<'a> {
+    attrs: HashMap<Cow<'a, str>, Cow<'a, str>>,
+    children: Vec<SelectOption<'a>>,
 }
 
-impl Select {
+impl<'a> Select<'a> {
     /// Creates a new [`Select`] tag.
-    pub fn new() -> Select {
+    pub fn new() -> Self {
         Default::default()
     }
 }
 
 /// Creates a new `Select` tag – functionally equivalent to `Select::new()` (but
 /// easier to type.)
-pub fn select() -> Select {
+pub fn select<'a>() -> Select<'a> {
     Select::new()
 }
 
-impl Select {
+impl<'a> Select<'a> {
     /// Add a number of children to a `<select>` tag.
     pub fn children<I, C>(mut self, children: I) -> Self
     where
-        C: Into<SelectOption>,
+        C: Into<SelectOption<'a>>,
         I: IntoIterator<Item = C>,
     {
         self.children
@@ -56,35 +51,40 @@ impl Select {
     /// Add a single child to a `<select>` tag.
     pub fn child<C>(mut self, child: C) -> Self
     where
-        C: Into<SelectOption>,
+        C: Into<SelectOption<'a>>,
     {
         self.children.push(child.into());
         self
     }
 
+    crate::define_child_node_accessors!(SelectOption<'a>);
+
+    crate::define_conditional_child_fns!(SelectOption<'a>);
+
     /// Add an attribute to the select in question.
     pub fn attribute<A>(mut self, attr: A) -> Self
     where
-        A: Into<SelectAttr>,
+        A: Into<SelectAttr<'a>>,
     {
-        let (a, b) = attr.into().into_attribute();
-        self.attrs.insert(a, b);
+        crate::utils::insert_attributes(&mut self.attrs, attr.into().into_attributes());
         self
     }
 
+    crate::define_attribute_helper_fns!(SelectAttr<'a>);
+
     crate::define_raw_attribute_fn!();
 
     /// Read an attribute that has been set
-    pub fn read_attribute(&self, attribute: &'static str) -> Option<&Cow<'static, str>> {
+    pub fn read_attribute(&self, attribute: &str) -> Option<&Cow<'a, str>> {
         self.attrs.get(attribute)
     }
 }
 
 into_grouping_union!(Select, BodyNode);
 
-impl Display for Select {
+impl Display for Select<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str("<select ")?;
+        f.write_str("<select")?;
         write_attributes(&self.attrs, f)?;
         f.write_str(">")?;
         for child in &self.children {
@@ -94,17 +94,10 @@ impl Display for Select {
     }
 }
 
-utility_enum!(
-    #[allow(missing_docs)]
+attribute_enum!(
     pub enum SelectAttr {
-        Name(Name),
-        Class(Class),
-        Id(Id),
+        Name<'a>,
     }
 );
 
-into_attribute_for_grouping_enum!(SelectAttr, Name, Class, Id);
-
-into_grouping_union!(Name, SelectAttr);
-into_grouping_union!(Class, SelectAttr);
-into_grouping_union!(Id, SelectAttr);
+crate::impl_attributes!(Select);
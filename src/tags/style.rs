@@ -1,39 +1,110 @@
-use crate::into_grouping_union;
+#[cfg(feature = "csp")]
+use crate::csp::write_inline_attributes;
 use crate::tags::head::head_node::HeadNode;
-use std::{borrow::Cow, fmt::Display};
+#[cfg(not(feature = "csp"))]
+use crate::utils::write_attributes as write_inline_attributes;
+use crate::{
+    attribute_enum, attributes::IntoAttributes, into_grouping_union, utils::escape_raw_text,
+};
+use std::{borrow::Cow, collections::HashMap, fmt::Display};
 
 /// The `<style>` tag, useful for embedding CSS styling inside HTML documents.
 ///
+/// A `StyleTag` can also be created from a [`crate::css::Stylesheet`] (using
+/// `StyleTag::from`).
+///
 /// See [MDN's page on this](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/meta) for
 /// further information.
 #[derive(Debug, Clone)]
 #[must_use]
-pub struct StyleTag {
-    text: Cow<'static, str>,
+pub struct StyleTag<'a> {
+    text: Cow<'a, str>,
+    attrs: HashMap<Cow<'a, str>, Cow<'a, str>>,
 }
 
 /// Creates a new `Style` tag – functionally equivalent to `Style::new()` (but
 /// easier to type.)
-pub fn style(text: impl Into<Cow<'static, str>>) -> StyleTag {
+pub fn style<'a>(text: impl Into<Cow<'a, str>>) -> StyleTag<'a> {
     StyleTag::new(text)
 }
 
-impl StyleTag {
+impl<'a> StyleTag<'a> {
     /// Create a new style tag.
     pub fn new<C>(c: C) -> Self
     where
-        C: Into<Cow<'static, str>>,
+        C: Into<Cow<'a, str>>,
+    {
+        Self {
+            text: c.into(),
+            attrs: HashMap::new(),
+        }
+    }
+
+    /// Attach an attribute to this tag.
+    pub fn attribute<A>(mut self, attribute: A) -> Self
+    where
+        A: Into<StyleTagAttr<'a>>,
     {
-        Self { text: c.into() }
+        crate::utils::insert_attributes(&mut self.attrs, attribute.into().into_attributes());
+        self
+    }
+
+    crate::define_attribute_helper_fns!(StyleTagAttr<'a>);
+
+    crate::define_raw_attribute_fn!();
+
+    /// Read an attribute that has been set.
+    pub fn read_attribute(&self, attribute: &str) -> Option<&Cow<'a, str>> {
+        self.attrs.get(attribute)
+    }
+
+    /// The contents of the tag, as they are rendered (i.e. with any `</style`
+    /// end tags escaped, so that the CSS can not end the tag early).
+    pub(crate) fn contents(&self) -> Cow<'_, str> {
+        escape_raw_text(&self.text, "style")
     }
 }
 
-impl Display for StyleTag {
+attribute_enum!(
+    pub enum StyleTagAttr {}
+);
+
+crate::impl_attributes!(StyleTag);
+
+impl Display for StyleTag<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str("<style>")?;
-        f.write_str(&self.text)?;
+        f.write_str("<style")?;
+        write_inline_attributes(&self.attrs, f)?;
+        f.write_str(">")?;
+        f.write_str(&self.contents())?;
         f.write_str("</style>")
     }
 }
 
 into_grouping_union!(StyleTag, HeadNode);
+
+#[cfg(test)]
+mod test {
+    use crate::{prelude::*, utils::fuzz_strings};
+
+    #[test]
+    fn test_contents_can_not_end_the_tag() {
+        let style = StyleTag::new("a::after { content: \"</STYLE><script>\"; }");
+        assert_eq!(
+            style.to_string(),
+            "<style>a::after { content: \"<\\/STYLE><script>\"; }</style>"
+        );
+
+        let elements = scraper::Selector::parse("*").unwrap();
+        let selector = scraper::Selector::parse("style").unwrap();
+        for text in fuzz_strings() {
+            let style = StyleTag::new(text.as_str());
+            let document = Html::new().head(Head::new().child(style.clone()));
+            let document = scraper::Html::parse_document(&document.to_string());
+            // `<html>`, `<head>`, `<style>` and `<body>`
+            assert_eq!(document.select(&elements).count(), 4, "{:?}", text);
+            let parsed = document.select(&selector).next().unwrap();
+            assert_eq!(parsed.text().collect::<String>(), style.contents());
+        }
+    }
+}
@@ -2,9 +2,12 @@
 This source code file is distributed subject to the terms of the Mozilla Public License v2.0.
 A copy of this license can be found in the `licenses` directory at the root of this project.
 */
-use std::{borrow::Cow, collections::HashMap};
+use std::{borrow::Cow, collections::HashMap, fmt::Display};
 
-use crate::{heading_display, impl_of_heading_new_fn, into_grouping_union};
+use crate::{
+    impl_of_heading_new_fn, into_grouping_union,
+    utils::{escape_escapable_raw_text, write_attributes},
+};
 
 use super::head::head_node::HeadNode;
 
@@ -15,13 +18,49 @@ use super::head::head_node::HeadNode;
 /// See the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/title) for more
 /// info.
 #[must_use]
-pub struct Title {
-    text: Cow<'static, str>,
-    attrs: HashMap<Cow<'static, str>, Cow<'static, str>>,
+pub struct Title<'a> {
+    text: Cow<'a, str>,
+    attrs: HashMap<Cow<'a, str>, Cow<'a, str>>,
 }
 
 impl_of_heading_new_fn!(Title, title);
 
-heading_display!(Title);
+impl Display for Title<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("<Title")?;
+        write_attributes(&self.attrs, f)?;
+        f.write_str(">")?;
+        // the title ends at the first `</title`, even inside (sanitized) HTML
+        f.write_str(&escape_escapable_raw_text(&self.text, "title"))?;
+        f.write_str("</Title>")
+    }
+}
 
 into_grouping_union!(Title, HeadNode);
+
+#[cfg(test)]
+mod test {
+    use crate::{prelude::*, utils::fuzz_strings};
+
+    #[test]
+    fn test_contents_can_not_end_the_tag() {
+        let title = Title::new_unchecked(TrustedHtml::dangerously_trust("a</title><b>"));
+        assert_eq!(title.to_string(), "<Title>a&lt;/title><b></Title>");
+
+        let elements = scraper::Selector::parse("*").unwrap();
+        let selector = scraper::Selector::parse("title").unwrap();
+        for text in fuzz_strings() {
+            let trusted = Title::new_unchecked(TrustedHtml::dangerously_trust(text.as_str()));
+            for title in [Title::new(&text), Title::html(&text), trusted] {
+                let document = Html::new().head(Head::new().child(title));
+                let document = scraper::Html::parse_document(&document.to_string());
+                // `<html>`, `<head>`, `<title>` and `<body>`
+                assert_eq!(document.select(&elements).count(), 4, "{:?}", text);
+            }
+            let document = Html::new().head(Head::new().child(Title::new(&text)));
+            let document = scraper::Html::parse_document(&document.to_string());
+            let parsed = document.select(&selector).next().unwrap();
+            assert_eq!(parsed.text().collect::<String>(), text);
+        }
+    }
+}
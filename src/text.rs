@@ -2,23 +2,23 @@
 This source code file is distributed subject to the terms of the Mozilla Public License v2.0.
 A copy of this license can be found in the `licenses` directory at the root of this project.
 */
-use std::{borrow::Cow, collections::HashMap, fmt::Display};
+use std::{borrow::Cow, fmt::Display};
 
-use crate::{impl_of_heading_new_fn, into_grouping_union, tags::body::body_node::BodyNode};
+use crate::{impl_of_text_new_fn, into_grouping_union, tags::body::body_node::BodyNode};
 
 #[derive(Default, Debug, Clone)]
 #[must_use]
-/// A text node.
-pub struct Text {
-    text: Cow<'static, str>,
-    attrs: HashMap<Cow<'static, str>, Cow<'static, str>>,
+/// A text node. Text nodes can not have any attributes (as they are not
+/// rendered as a tag).
+pub struct Text<'a> {
+    pub(crate) text: Cow<'a, str>,
 }
 
-impl_of_heading_new_fn!(Text, text);
+impl_of_text_new_fn!(Text, text);
 
 into_grouping_union!(Text, BodyNode);
 
-impl Display for Text {
+impl Display for Text<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.text.fmt(f)
     }
@@ -26,13 +26,57 @@ impl Display for Text {
 #[cfg(test)]
 mod test_sanitize {
     use super::Text;
+    use crate::sanitize::TrustedHtml;
 
     #[test]
     fn test_unsanitized() {
         let text = Text::new("<script>alert(\"hello\")</script>");
         assert_ne!(&text.to_string(), "<script>alert(\"hello\")</script>");
 
-        let text = Text::new_unchecked("<script>alert(\"hello\")</script>");
+        let text = Text::new_unchecked(TrustedHtml::dangerously_trust(
+            "<script>alert(\"hello\")</script>",
+        ));
         assert_eq!(&text.to_string(), "<script>alert(\"hello\")</script>");
     }
+
+    #[test]
+    fn test_escaping() {
+        use std::borrow::Cow;
+
+        use crate::prelude::*;
+
+        let text = Text::new("a < b && c > \"d\"");
+        assert_eq!(text.to_string(), "a &lt; b &amp;&amp; c &gt; &quot;d&quot;");
+        let text = Text::html("<b>a</b> < b<script>alert(1)</script>");
+        assert_eq!(text.to_string(), "<b>a</b> &lt; b");
+
+        let a = A::new().text("plain text");
+        assert!(matches!(a.text, Cow::Borrowed("plain text")));
+        // text is only copied if it needs to be escaped
+        let owned = String::from("plain text");
+        assert!(matches!(Text::new(owned.as_str()).text, Cow::Borrowed(_)));
+        assert!(matches!(H1::new(owned.as_str()).text, Cow::Borrowed(_)));
+        assert!(matches!(
+            P::with_text(owned.as_str()).text,
+            Cow::Borrowed(_)
+        ));
+        assert!(matches!(Text::new("a < b").text, Cow::Owned(_)));
+        assert_eq!(
+            P::with_text("<br>").text("<br>").html("<br>").to_string(),
+            "<p>&lt;br&gt;&lt;br&gt;<br></p>"
+        );
+    }
+
+    #[test]
+    fn test_text_has_no_attributes() {
+        use crate::{
+            attributes::{Attributes, CannotHaveAttributes},
+            prelude::*,
+        };
+
+        let mut node = BodyNode::from(Text::new("text"));
+        assert_eq!(node.set_attribute(Id::new("x")), Err(CannotHaveAttributes));
+        assert_eq!(node.get_attribute("id"), None);
+        assert_eq!(node.to_string(), "text");
+    }
 }
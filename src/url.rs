@@ -0,0 +1,383 @@
+/*
+This source code file is distributed subject to the terms of the Mozilla Public License v2.0.
+A copy of this license can be found in the `licenses` directory at the root of this project.
+*/
+use std::{borrow::Cow, fmt::Display};
+
+/// The schemes which are allowed by [`SafeUrl::parse`].
+pub const DEFAULT_SCHEMES: &[&str] = &["http", "https", "mailto", "tel"];
+
+/// The URL which is used in place of a URL which is not allowed (following
+/// the specification, browsers do nothing when a link to it is followed).
+pub const INNOCUOUS_URL: &str = "about:invalid#malvolio";
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[must_use]
+/// A URL which is safe to use in an attribute such as `href` (i.e. one which
+/// can not be used to run a script, such as a `javascript:` URL).
+///
+/// A URL is safe if it is relative (e.g. `/about` or `//example.com`), or if
+/// its scheme is one of the allowed schemes ([`DEFAULT_SCHEMES`], unless
+/// [`SafeUrl::parse_with_schemes`] is used). Any characters which are not
+/// allowed in a URL (such as spaces) are percent-encoded.
+///
+/// ```
+/// # use malvolio::prelude::*;
+/// let url = SafeUrl::parse("/search").unwrap().query("q", "cats & dogs");
+/// assert_eq!(url.as_str(), "/search?q=cats+%26+dogs");
+/// assert!(SafeUrl::parse("javascript:alert(1)").is_err());
+/// ```
+///
+/// The URL-carrying attributes (`Href`, `Src` and `Action`) accept anything
+/// which can be converted into a `SafeUrl`. Strings are converted with
+/// [`SafeUrl::sanitize`], which replaces URLs that are not allowed with
+/// [`INNOCUOUS_URL`]:
+///
+/// ```
+/// # use malvolio::prelude::*;
+/// let a = A::new().attribute(Href::new("javascript:alert(1)"));
+/// assert_eq!(a.to_string(), "<a href=\"about:invalid#malvolio\"></a>");
+///
+/// // if you are sure that a URL is safe, it can be used without being checked
+/// let a = A::new().attribute(Href::new(SafeUrl::dangerously_trust("javascript:void(0)")));
+/// assert_eq!(a.to_string(), "<a href=\"javascript:void(0)\"></a>");
+/// ```
+pub struct SafeUrl<'a>(Cow<'a, str>);
+
+impl<'a> SafeUrl<'a> {
+    /// Checks that the URL is relative, or uses one of the [`DEFAULT_SCHEMES`],
+    /// percent-encoding any characters which are not allowed in a URL.
+    pub fn parse<S>(url: S) -> Result<Self, UrlError>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        Self::parse_with_schemes(url, DEFAULT_SCHEMES)
+    }
+
+    /// Checks that the URL is relative, or uses one of the provided schemes
+    /// (which should be in lowercase), percent-encoding any characters which
+    /// are not allowed in a URL.
+    pub fn parse_with_schemes<S>(url: S, schemes: &[&str]) -> Result<Self, UrlError>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let url = strip(url.into());
+        match scheme(&url) {
+            Some(scheme)
+                if !schemes
+                    .iter()
+                    .any(|allowed| scheme.eq_ignore_ascii_case(allowed)) =>
+            {
+                Err(UrlError::DisallowedScheme(scheme.to_string()))
+            }
+            _ => Ok(Self(percent_encode(url))),
+        }
+    }
+
+    /// Checks the URL (in the same way as [`SafeUrl::parse`]), replacing it
+    /// with [`INNOCUOUS_URL`] if it is not allowed.
+    pub fn sanitize<S>(url: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        Self::parse(url).unwrap_or_else(|_| Self(INNOCUOUS_URL.into()))
+    }
+
+    /// Uses the URL as it is, **without checking it**. Only use this for URLs
+    /// which you are certain are safe (i.e. ones which do not contain any
+    /// user-supplied content).
+    pub fn dangerously_trust<S>(url: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        Self(url.into())
+    }
+
+    /// Adds a parameter to the query string of the URL (before the fragment,
+    /// if the URL has one). The key and the value are encoded in the same way
+    /// as a HTML form would encode them.
+    pub fn query(self, key: &str, value: &str) -> Self {
+        let mut url = self.0.into_owned();
+        let fragment = url.find('#').map(|index| url.split_off(index));
+        url.push(if url.contains('?') { '&' } else { '?' });
+        encode_query_component(key, &mut url);
+        url.push('=');
+        encode_query_component(value, &mut url);
+        url.extend(fragment);
+        Self(url.into())
+    }
+
+    /// Returns the scheme of the URL (e.g. `"https"`), or `None` if the URL is
+    /// relative.
+    pub fn scheme(&self) -> Option<&str> {
+        scheme(&self.0)
+    }
+
+    /// Returns the URL.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the URL.
+    pub fn into_inner(self) -> Cow<'a, str> {
+        self.0
+    }
+}
+
+impl<'a> From<&'a str> for SafeUrl<'a> {
+    fn from(url: &'a str) -> Self {
+        Self::sanitize(url)
+    }
+}
+
+impl<'a> From<String> for SafeUrl<'a> {
+    fn from(url: String) -> Self {
+        Self::sanitize(url)
+    }
+}
+
+impl<'a> From<Cow<'a, str>> for SafeUrl<'a> {
+    fn from(url: Cow<'a, str>) -> Self {
+        Self::sanitize(url)
+    }
+}
+
+impl<'a> From<SafeUrl<'a>> for Cow<'a, str> {
+    fn from(url: SafeUrl<'a>) -> Self {
+        url.0
+    }
+}
+
+impl Display for SafeUrl<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// An error which can occur when checking a [`SafeUrl`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UrlError {
+    /// The URL uses a scheme which is not allowed (such as `javascript`).
+    DisallowedScheme(String),
+}
+
+impl Display for UrlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UrlError::DisallowedScheme(scheme) => {
+                write!(f, "URLs with the scheme `{}` are not allowed", scheme)
+            }
+        }
+    }
+}
+
+impl std::error::Error for UrlError {}
+
+/// Removes the characters which browsers ignore when they parse a URL (leading
+/// and trailing spaces and control characters, and tabs and newlines
+/// anywhere), so that they can not be used to disguise the scheme (e.g.
+/// `"java\tscript:"`).
+fn strip(url: Cow<'_, str>) -> Cow<'_, str> {
+    let ignored = |c: char| c <= ' ';
+    let trimmed = url.trim_matches(ignored);
+    if trimmed.len() == url.len() && !url.contains(['\t', '\n', '\r']) {
+        return url;
+    }
+    trimmed
+        .chars()
+        .filter(|c| !matches!(c, '\t' | '\n' | '\r'))
+        .collect::<String>()
+        .into()
+}
+
+/// Returns the scheme of the URL, or `None` if it is relative.
+fn scheme(url: &str) -> Option<&str> {
+    let end = url.find([':', '/', '?', '#'])?;
+    let scheme = &url[..end];
+    let valid = url[end..].starts_with(':')
+        && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    valid.then_some(scheme)
+}
+
+/// Returns true if the character can appear in a URL without being
+/// percent-encoded.
+fn is_url_char(c: char) -> bool {
+    c.is_ascii_alphanumeric()
+        || matches!(
+            c,
+            '-' | '.'
+                | '_'
+                | '~'
+                | ':'
+                | '/'
+                | '?'
+                | '#'
+                | '['
+                | ']'
+                | '@'
+                | '!'
+                | '$'
+                | '&'
+                | '\''
+                | '('
+                | ')'
+                | '*'
+                | '+'
+                | ','
+                | ';'
+                | '='
+        )
+}
+
+/// Percent-encodes the characters which are not allowed in a URL (leaving
+/// existing percent-encoded characters alone, so that encoding a URL twice
+/// does not change it).
+fn percent_encode(url: Cow<'_, str>) -> Cow<'_, str> {
+    let bytes = url.as_bytes();
+    let is_escape = |index: usize| {
+        bytes.len() > index + 2
+            && bytes[index + 1].is_ascii_hexdigit()
+            && bytes[index + 2].is_ascii_hexdigit()
+    };
+    let needs_encoding = url
+        .char_indices()
+        .any(|(index, c)| !(is_url_char(c) || (c == '%' && is_escape(index))));
+    if !needs_encoding {
+        return url;
+    }
+    let mut encoded = String::with_capacity(url.len() + 16);
+    for (index, c) in url.char_indices() {
+        if is_url_char(c) || (c == '%' && is_escape(index)) {
+            encoded.push(c);
+        } else {
+            push_encoded(c, &mut encoded);
+        }
+    }
+    encoded.into()
+}
+
+/// Encodes part of a query string (as `application/x-www-form-urlencoded`).
+fn encode_query_component(component: &str, out: &mut String) {
+    for c in component.chars() {
+        match c {
+            ' ' => out.push('+'),
+            c if c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '*') => out.push(c),
+            c => push_encoded(c, out),
+        }
+    }
+}
+
+fn push_encoded(c: char, out: &mut String) {
+    let mut buf = [0; 4];
+    for byte in c.encode_utf8(&mut buf).bytes() {
+        out.push_str(&format!("%{:02X}", byte));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SafeUrl, UrlError, INNOCUOUS_URL};
+    use crate::{attributes::Attributes, prelude::*};
+
+    #[test]
+    fn test_schemes() {
+        for url in [
+            "/about",
+            "about",
+            "../a:b",
+            "?q=1",
+            "#top",
+            "//example.com/a",
+            "https://example.com",
+            "HTTP://example.com",
+            "mailto:someone@example.com",
+        ] {
+            assert_eq!(SafeUrl::parse(url).unwrap().as_str(), url);
+        }
+        for url in [
+            "javascript:alert(1)",
+            "JavaScript:alert(1)",
+            " javascript:alert(1)",
+            "java\tscript:alert(1)",
+            "java\nscript:alert(1)",
+            "\u{1}javascript:alert(1)",
+            "data:text/html,<script>alert(1)</script>",
+            "vbscript:msgbox",
+        ] {
+            assert!(
+                matches!(SafeUrl::parse(url), Err(UrlError::DisallowedScheme(_))),
+                "{:?}",
+                url
+            );
+            assert_eq!(SafeUrl::sanitize(url).as_str(), INNOCUOUS_URL);
+        }
+        let url = SafeUrl::parse_with_schemes("ftp://example.com", &["ftp"]).unwrap();
+        assert_eq!(url.scheme(), Some("ftp"));
+        assert!(SafeUrl::parse_with_schemes("https://example.com", &["ftp"]).is_err());
+    }
+
+    #[test]
+    fn test_encoding() {
+        let url = SafeUrl::parse("/a b/\"<ü>\"?x=%20&y=%zz").unwrap();
+        assert_eq!(url.as_str(), "/a%20b/%22%3C%C3%BC%3E%22?x=%20&y=%25zz");
+        assert_eq!(SafeUrl::parse(url.as_str()).unwrap(), url);
+
+        let url = SafeUrl::parse("https://example.com/search#results")
+            .unwrap()
+            .query("q", "a&b=c d")
+            .query("lang", "ü");
+        assert_eq!(
+            url.as_str(),
+            "https://example.com/search?q=a%26b%3Dc+d&lang=%C3%BC#results"
+        );
+    }
+
+    #[test]
+    fn test_attributes() {
+        let form = Form::new()
+            .attribute(Action::new("javascript:alert(1)"))
+            .child(Img::new().attribute(Src::new("/cat picture.png")));
+        assert_eq!(
+            form.to_string(),
+            "<form action=\"about:invalid#malvolio\"><img src=\"/cat%20picture.png\"/></form>"
+        );
+        let a = A::new().href("https://example.com");
+        assert_eq!(a.read::<Href>().unwrap().as_str(), "https://example.com");
+        assert_eq!(
+            form.child_nodes()[0].read::<Src>().unwrap().as_str(),
+            "/cat%20picture.png"
+        );
+    }
+
+    #[test]
+    fn test_reading_unchecked_attributes() {
+        use crate::attributes::Attributes;
+
+        // attribute values can be replaced without being checked
+        fn replace<'a, T: Attributes<'a>>(mut node: T, key: &str, value: &'a str) -> T {
+            *node.attribute_mut(key).unwrap() = value.into();
+            node
+        }
+
+        let a = replace(A::new().href("/"), "href", "javascript:alert(1)");
+        assert!(a.read::<Href>().is_none());
+        let img = replace(
+            Img::new().attribute(Src::new("/")),
+            "src",
+            "javascript:alert(1)",
+        );
+        assert!(img.read::<Src>().is_none());
+        let form = replace(
+            Form::new().attribute(Action::new("/")),
+            "action",
+            " JavaScript:alert(1)",
+        );
+        assert!(form.read::<Action>().is_none());
+
+        let a = replace(A::new().href("/"), "href", "/a b");
+        assert_eq!(a.read::<Href>().unwrap().as_str(), "/a%20b");
+    }
+}
@@ -4,22 +4,297 @@ A copy of this license can be found in the `licenses` directory at the root of t
 */
 use std::{borrow::Cow, collections::HashMap};
 
+use crate::{css::Style, tags::body::body_node::BodyNode};
+
+/// Adds a child to a list of children, adding the nodes of a `Fragment`
+/// individually (rather than adding the fragment itself).
+pub fn push_child<'a>(children: &mut Vec<BodyNode<'a>>, child: BodyNode<'a>) {
+    match child {
+        BodyNode::Fragment(fragment) => children.extend(fragment.children),
+        child => children.push(child),
+    }
+}
+
+/// Adds a number of children to a list of children (see [`push_child`]).
+pub fn extend_children<'a, I, C>(children: &mut Vec<BodyNode<'a>>, new: I)
+where
+    I: IntoIterator<Item = C>,
+    C: Into<BodyNode<'a>>,
+{
+    for child in new {
+        push_child(children, child.into());
+    }
+}
+
+/// Returns the nodes as they will appear in the browser: the contents of
+/// fragments (including fragments which are inside shared nodes, or which are
+/// returned by lazy nodes) are added in place of the fragment itself, and lazy
+/// nodes are evaluated.
+pub(crate) fn flatten_children<'r, 'a>(nodes: &'r [BodyNode<'a>]) -> Vec<Cow<'r, BodyNode<'a>>> {
+    fn add<'r, 'a>(node: Cow<'r, BodyNode<'a>>, flat: &mut Vec<Cow<'r, BodyNode<'a>>>) {
+        match node {
+            Cow::Borrowed(BodyNode::Fragment(fragment)) => {
+                for child in fragment.child_nodes() {
+                    add(Cow::Borrowed(child), flat);
+                }
+            }
+            Cow::Owned(BodyNode::Fragment(fragment)) => {
+                for child in fragment.children {
+                    add(Cow::Owned(child), flat);
+                }
+            }
+            Cow::Borrowed(BodyNode::Lazy(lazy)) => add(Cow::Owned(lazy.evaluate()), flat),
+            Cow::Owned(BodyNode::Lazy(lazy)) => add(Cow::Owned(lazy.evaluate()), flat),
+            Cow::Borrowed(BodyNode::Shared(shared)) if !is_element(shared.get()) => {
+                add(Cow::Borrowed(shared.get()), flat)
+            }
+            Cow::Owned(BodyNode::Shared(shared)) if !is_element(shared.get()) => {
+                add(Cow::Owned(shared.get().clone()), flat)
+            }
+            node => flat.push(node),
+        }
+    }
+
+    let mut flat = vec![];
+    for node in nodes {
+        add(Cow::Borrowed(node), &mut flat);
+    }
+    flat
+}
+
+/// Returns true if the node is rendered as a single element (so that shared
+/// nodes containing it can be compared without looking inside them).
+pub(crate) fn is_element(node: &BodyNode<'_>) -> bool {
+    match node {
+        BodyNode::Shared(shared) => is_element(shared.get()),
+        node => node.tag_name().is_some(),
+    }
+}
+
+/// Inserts the provided attributes, merging the values of the `class` and
+/// `style` attributes with any existing value (rather than overwriting them).
+pub fn insert_attributes<'a>(
+    attrs: &mut HashMap<Cow<'a, str>, Cow<'a, str>>,
+    new: Vec<(Cow<'a, str>, Cow<'a, str>)>,
+) {
+    for (key, value) in new {
+        match attrs.get_mut(&key) {
+            Some(existing) if key == "class" => {
+                let mut classes = existing.split_whitespace().collect::<Vec<_>>();
+                for class in value.split_whitespace() {
+                    if !classes.contains(&class) {
+                        classes.push(class);
+                    }
+                }
+                *existing = classes.join(" ").into();
+            }
+            Some(existing) if key == "style" => {
+                let merged = Style::new(existing.as_ref())
+                    .merge(Style::new(value.as_ref()))
+                    .to_string();
+                *existing = merged.into();
+            }
+            _ => {
+                attrs.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Removes a single class from the `class` attribute (removing the attribute
+/// entirely if no classes are left).
+pub fn remove_class<'a>(attrs: &mut HashMap<Cow<'a, str>, Cow<'a, str>>, class: &str) {
+    if let Some(existing) = attrs.get_mut("class") {
+        let classes = existing
+            .split_whitespace()
+            .filter(|existing| *existing != class)
+            .collect::<Vec<_>>();
+        if classes.is_empty() {
+            attrs.remove("class");
+        } else {
+            *existing = classes.join(" ").into();
+        }
+    }
+}
+
+/// Escapes the characters which have a special meaning in HTML (`<`, `>`, `&`
+/// and `"`), so that the text is displayed as it is. The text is not copied if
+/// it does not contain any of these characters.
+pub fn escape_text(text: Cow<'_, str>) -> Cow<'_, str> {
+    if !text.contains(['<', '>', '&', '"']) {
+        return text;
+    }
+    let mut escaped = String::with_capacity(text.len() + 8);
+    for c in text.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped.into()
+}
+
+/// Escapes the contents of a raw text element (such as `<style>`), so that
+/// they can not end the element early. The contents of these elements are
+/// not decoded (so `&lt;` can not be used), so end tags for the element (e.g.
+/// `</style`, in any case) are written as `<\/style` instead.
+pub fn escape_raw_text<'t>(text: &'t str, tag: &str) -> Cow<'t, str> {
+    escape_end_tags(text, tag, "<\\/")
+}
+
+/// Escapes the contents of an escapable raw text element (such as `<title>`)
+/// or a `<noscript>` element (which is a raw text element when scripting is
+/// enabled, but otherwise contains HTML), so that they can not end the element
+/// early. The contents of these elements are decoded, so end tags for the
+/// element are written as (e.g.) `&lt;/title`.
+pub fn escape_escapable_raw_text<'t>(text: &'t str, tag: &str) -> Cow<'t, str> {
+    escape_end_tags(text, tag, "&lt;/")
+}
+
+/// Replaces the `</` at the start of every end tag for `tag` (matched without
+/// regard to case) with `replacement`.
+fn escape_end_tags<'t>(text: &'t str, tag: &str, replacement: &str) -> Cow<'t, str> {
+    let mut escaped = String::new();
+    let mut last = 0;
+    for (index, _) in text.match_indices("</") {
+        let name = text.as_bytes().get(index + 2..index + 2 + tag.len());
+        if name.is_some_and(|name| name.eq_ignore_ascii_case(tag.as_bytes())) {
+            escaped.push_str(&text[last..index]);
+            escaped.push_str(replacement);
+            last = index + 2;
+        }
+    }
+    if last == 0 {
+        return text.into();
+    }
+    escaped.push_str(&text[last..]);
+    escaped.into()
+}
+
 pub fn write_attributes(
-    attrs: &HashMap<Cow<'static, str>, Cow<'static, str>>,
+    attrs: &HashMap<Cow<'_, str>, Cow<'_, str>>,
     f: &mut std::fmt::Formatter<'_>,
 ) -> std::fmt::Result {
+    write_sorted_attributes(
+        attrs
+            .iter()
+            .map(|(key, value)| (key.as_ref(), value.as_ref())),
+        f,
+    )
+}
+
+/// Writes the attributes, sorted by name (so that the output is
+/// deterministic).
+pub fn write_sorted_attributes<'s>(
+    attrs: impl Iterator<Item = (&'s str, &'s str)>,
+    f: &mut std::fmt::Formatter<'_>,
+) -> std::fmt::Result {
+    let mut attrs = attrs.collect::<Vec<_>>();
+    attrs.sort_unstable_by_key(|(key, _)| *key);
     for (key, value) in attrs {
+        f.write_str(" ")?;
         f.write_str(key)?;
         f.write_str("=\"")?;
-        match value {
-            Cow::Borrowed(b) => {
-                f.write_str(b)?;
-            }
-            Cow::Owned(string) => {
-                f.write_str(string)?;
-            }
-        }
+        write_escaped_attribute_value(value, f)?;
         f.write_str("\"")?;
     }
     Ok(())
 }
+
+/// Writes the value of an attribute, escaping the characters which would
+/// otherwise allow the value to break out of the (double-quoted) attribute.
+fn write_escaped_attribute_value(value: &str, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let mut last = 0;
+    for (index, c) in value.char_indices() {
+        let escaped = match c {
+            '"' => "&quot;",
+            '&' => "&amp;",
+            _ => continue,
+        };
+        f.write_str(&value[last..index])?;
+        f.write_str(escaped)?;
+        last = index + c.len_utf8();
+    }
+    f.write_str(&value[last..])
+}
+
+/// Generates (deterministic) random strings made up of fragments which are
+/// likely to confuse a HTML parser, for checking that text can not escape the
+/// element which contains it.
+#[cfg(test)]
+pub fn fuzz_strings() -> impl Iterator<Item = String> {
+    const FRAGMENTS: &[&str] = &[
+        "</",
+        "<",
+        ">",
+        "/",
+        " ",
+        "\t",
+        "\n",
+        "=",
+        "\"",
+        "'",
+        "&",
+        "&lt;",
+        "\\",
+        "<!--",
+        "-->",
+        "<![CDATA[",
+        "]]>",
+        "style",
+        "STYLE",
+        "StYlE",
+        "title",
+        "TiTlE",
+        "noscript",
+        "NOSCRIPT",
+        "script",
+        "<script>alert(1)</script>",
+        "<img src=x onerror=alert(1)>",
+        "a",
+        "é",
+    ];
+    // xorshift, so that the tests are reproducible
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state as usize
+    };
+    (0..500).map(move |_| {
+        let len = next() % 16;
+        (0..len)
+            .map(|_| FRAGMENTS[next() % FRAGMENTS.len()])
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_attribute_values_are_escaped() {
+        assert_eq!(
+            Div::new()
+                .raw_attribute("title", "\" onclick=\"alert(1)")
+                .raw_attribute("lang", "a&amp;b")
+                .to_string(),
+            "<div lang=\"a&amp;amp;b\" title=\"&quot; onclick=&quot;alert(1)\"></div>"
+        );
+        for value in super::fuzz_strings() {
+            let document = Div::new()
+                .raw_attribute("title", value.as_str())
+                .to_string();
+            let document = scraper::Html::parse_fragment(&document);
+            let selector = scraper::Selector::parse("div").unwrap();
+            let div = document.select(&selector).next().unwrap().value();
+            assert_eq!(div.attrs().count(), 1, "{:?} added an attribute", value);
+            assert_eq!(div.attr("title"), Some(value.as_str()));
+        }
+    }
+}
@@ -0,0 +1,440 @@
+/*
+This source code file is distributed subject to the terms of the Mozilla Public License v2.0.
+A copy of this license can be found in the `licenses` directory at the root of this project.
+*/
+//! Checks that a document follows the [content models](https://html.spec.whatwg.org/multipage/dom.html#content-models)
+//! in the HTML specification (which say, for example, that a `<p>` may not
+//! contain a `<div>`).
+//!
+//! Malvolio does not stop you from building a document which breaks these
+//! rules (browsers will still display it, although often not in the way you
+//! might expect – a `<div>` inside a `<p>` ends the paragraph, for example),
+//! but [`Html::validate`] (as well as `Body::validate` and
+//! `BodyNode::validate`) can be used to find any problems, for example in a
+//! test:
+//!
+//! ```
+//! # use malvolio::prelude::*;
+//! use malvolio::validate::{Diagnostic, Problem};
+//!
+//! let body = Body::new().child(
+//!     Form::new().child(P::new("").child(Div::new()).child(Form::new())),
+//! );
+//! assert_eq!(
+//!     body.validate(),
+//!     vec![
+//!         Diagnostic {
+//!             path: vec![0, 0, 0],
+//!             problem: Problem::NotAllowedInside { tag: "div", parent: "p" }
+//!         },
+//!         Diagnostic {
+//!             path: vec![0, 0, 1],
+//!             problem: Problem::NotAllowedInside { tag: "form", parent: "p" }
+//!         },
+//!         Diagnostic {
+//!             path: vec![0, 0, 1],
+//!             problem: Problem::NestedForm { outer: vec![0] }
+//!         },
+//!     ]
+//! );
+//! ```
+//!
+//! Nodes are identified by their path, in the same way as in the
+//! [`diff`](crate::diff) module. `Shared` nodes are checked as if they were
+//! the node they contain, and `Lazy` nodes are constructed (and then checked);
+//! if either contains a `Fragment`, the fragment's nodes are counted as
+//! children of its parent (as they are when the document is rendered). Text
+//! (including text containing HTML) and the contents of `Raw` nodes are not
+//! checked.
+
+use std::fmt::Display;
+
+use crate::{
+    tags::{
+        body::{body_node::BodyNode, Body},
+        head::head_node::HeadNode,
+        html::Html,
+    },
+    utils::flatten_children,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A problem found while validating a document, and where it was found.
+pub struct Diagnostic {
+    /// The path of the node the problem was found at (the list of indices of
+    /// the children which need to be followed to get from the root to the
+    /// node). This is empty for problems with the `<head>`.
+    pub path: Vec<usize>,
+    /// The problem which was found.
+    pub problem: Problem,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A way in which a document can break the content models.
+pub enum Problem {
+    /// The node is not allowed inside its parent (for example, a `<p>` may
+    /// only contain phrasing content, so a `<div>` is not allowed inside it).
+    NotAllowedInside {
+        /// The name of the node's tag.
+        tag: &'static str,
+        /// The name of the parent's tag.
+        parent: &'static str,
+    },
+    /// A `<form>` inside another `<form>`.
+    NestedForm {
+        /// The path of the outer form.
+        outer: Vec<usize>,
+    },
+    /// The `<head>` does not contain a `<title>`.
+    MissingTitle,
+    /// The `<head>` contains more than one `<title>`.
+    MultipleTitles,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.problem {
+            Problem::NotAllowedInside { tag, parent } => {
+                write!(f, "`<{}>` is not allowed inside `<{}>`", tag, parent)?
+            }
+            Problem::NestedForm { outer } => write!(
+                f,
+                "`<form>` is not allowed inside the `<form>` at {:?}",
+                outer
+            )?,
+            Problem::MissingTitle => f.write_str("`<head>` does not contain a `<title>`")?,
+            Problem::MultipleTitles => f.write_str("`<head>` contains more than one `<title>`")?,
+        }
+        write!(f, " (at {:?})", self.path)
+    }
+}
+
+impl<'a> Html<'a> {
+    /// Checks the document against the content models, returning any problems
+    /// which were found (so an empty list means that the document is valid).
+    /// See the [module-level documentation](crate::validate) for details.
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let titles = self
+            .head
+            .child_nodes()
+            .iter()
+            .filter(|node| matches!(node, HeadNode::Title(_)))
+            .count();
+        let head = match titles {
+            0 => Some(Problem::MissingTitle),
+            1 => None,
+            _ => Some(Problem::MultipleTitles),
+        };
+        let mut diagnostics = head
+            .map(|problem| Diagnostic {
+                path: vec![],
+                problem,
+            })
+            .into_iter()
+            .collect::<Vec<_>>();
+        diagnostics.extend(self.body.validate());
+        diagnostics
+    }
+}
+
+impl<'a> Body<'a> {
+    /// Checks the contents of this tag against the content models, returning
+    /// any problems which were found.
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut validator = Validator::default();
+        validator.validate_children(self.child_nodes(), Context::default());
+        validator.diagnostics
+    }
+}
+
+impl<'a> BodyNode<'a> {
+    /// Checks this node (and its descendants) against the content models,
+    /// returning any problems which were found. Paths are relative to this
+    /// node.
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut validator = Validator::default();
+        validator.validate_node(self, Context::default());
+        validator.diagnostics
+    }
+}
+
+/// What is allowed at the current position in the tree.
+#[derive(Debug, Clone, Copy, Default)]
+struct Context {
+    /// The tag of the nearest ancestor which may only contain phrasing
+    /// content (if there is one).
+    phrasing_only: Option<&'static str>,
+    /// The length of the path of the nearest ancestor which is a form (if
+    /// there is one).
+    form: Option<usize>,
+}
+
+#[derive(Default)]
+struct Validator {
+    path: Vec<usize>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Validator {
+    fn validate_children(&mut self, children: &[BodyNode<'_>], context: Context) {
+        for (index, child) in flatten_children(children).iter().enumerate() {
+            self.path.push(index);
+            self.validate_node(child, context);
+            self.path.pop();
+        }
+    }
+
+    fn validate_node(&mut self, node: &BodyNode<'_>, mut context: Context) {
+        match node {
+            BodyNode::Shared(shared) => return self.validate_node(shared.get(), context),
+            BodyNode::Lazy(lazy) => return self.validate_node(&lazy.evaluate(), context),
+            _ => {}
+        }
+        if let (Some(parent), Some(tag)) = (context.phrasing_only, node.tag_name()) {
+            if !is_phrasing_content(node) {
+                self.report(Problem::NotAllowedInside { tag, parent });
+            }
+        }
+        match node {
+            BodyNode::P(_) => context.phrasing_only = Some("p"),
+            BodyNode::Div(_) => context.phrasing_only = None,
+            BodyNode::Form(_) => {
+                context.phrasing_only = None;
+                match context.form {
+                    Some(outer) => self.report(Problem::NestedForm {
+                        outer: self.path[..outer].to_vec(),
+                    }),
+                    None => context.form = Some(self.path.len()),
+                }
+            }
+            _ => {}
+        }
+        self.validate_children(node.children(), context);
+    }
+
+    fn report(&mut self, problem: Problem) {
+        self.diagnostics.push(Diagnostic {
+            path: self.path.clone(),
+            problem,
+        });
+    }
+}
+
+/// Returns true if the node is [phrasing content](https://html.spec.whatwg.org/multipage/dom.html#phrasing-content)
+/// (the text of a paragraph, and the elements inside it). Every variant is
+/// listed, so that new tags have to be classified. Nodes without a tag (such
+/// as `Fragment`s and `Raw` nodes) are not checked.
+fn is_phrasing_content(node: &BodyNode<'_>) -> bool {
+    match node {
+        BodyNode::H1(_)
+        | BodyNode::H2(_)
+        | BodyNode::H3(_)
+        | BodyNode::H4(_)
+        | BodyNode::H5(_)
+        | BodyNode::H6(_)
+        | BodyNode::P(_)
+        | BodyNode::Form(_)
+        | BodyNode::Div(_) => false,
+        BodyNode::Text(_)
+        | BodyNode::Br(_)
+        | BodyNode::A(_)
+        | BodyNode::Input(_)
+        | BodyNode::Label(_)
+        | BodyNode::Select(_)
+        | BodyNode::NoScript(_)
+        | BodyNode::Img(_) => true,
+        BodyNode::Shared(shared) => is_phrasing_content(shared.get()),
+        BodyNode::Fragment(_) | BodyNode::Lazy(_) | BodyNode::Raw(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Diagnostic, Problem};
+    use crate::prelude::*;
+    use crate::{tags::noscript::NoScript, text::Text};
+
+    #[test]
+    fn test_validate() {
+        let valid = Html::new()
+            .head(Head::new().child(Title::new("Title")))
+            .body(
+                Body::new()
+                    .child(H1::new("Heading"))
+                    .child(P::new("Text").child(A::new().text("link")).child(br()))
+                    .child(Form::new().child(Div::new().child(Input::new()))),
+            );
+        assert_eq!(valid.validate(), vec![]);
+
+        let form = Shared::new(Form::new());
+        let invalid = Html::new()
+            .head(Head::new().child(Title::new("a")).child(Title::new("b")))
+            .body(
+                Body::new()
+                    .child(Div::new().child(form.clone()))
+                    .child(Form::new().child(form))
+                    .child(P::new("").child(lazy(|| H2::new("Heading")))),
+            );
+        assert_eq!(
+            invalid.validate(),
+            vec![
+                Diagnostic {
+                    path: vec![],
+                    problem: Problem::MultipleTitles
+                },
+                Diagnostic {
+                    path: vec![1, 0],
+                    problem: Problem::NestedForm { outer: vec![1] }
+                },
+                Diagnostic {
+                    path: vec![2, 0],
+                    problem: Problem::NotAllowedInside {
+                        tag: "h2",
+                        parent: "p"
+                    }
+                },
+            ]
+        );
+        assert_eq!(
+            invalid.validate()[2].to_string(),
+            "`<h2>` is not allowed inside `<p>` (at [2, 0])"
+        );
+        assert_eq!(
+            Html::new().validate(),
+            vec![Diagnostic {
+                path: vec![],
+                problem: Problem::MissingTitle
+            }]
+        );
+    }
+
+    #[test]
+    fn test_flow_content_inside_p() {
+        let flow: Vec<BodyNode<'static>> = vec![
+            H1::new("").into(),
+            H2::new("").into(),
+            H3::new("").into(),
+            H4::new("").into(),
+            H5::new("").into(),
+            H6::new("").into(),
+            P::new("").into(),
+            Div::new().into(),
+            Form::new().into(),
+        ];
+        for node in flow {
+            let tag = node.tag_name().unwrap();
+            assert_eq!(
+                Body::new()
+                    .child(P::new("").child(Shared::new(node)))
+                    .validate(),
+                vec![Diagnostic {
+                    path: vec![0, 0],
+                    problem: Problem::NotAllowedInside { tag, parent: "p" }
+                }]
+            );
+        }
+
+        let phrasing = P::new("")
+            .child(Text::new("text"))
+            .child(Br::new())
+            .child(A::new().text("link"))
+            .child(Input::new())
+            .child(Label::new("label"))
+            .child(Select::new())
+            .child(NoScript::new("noscript"))
+            .child(Img::new());
+        assert_eq!(BodyNode::from(phrasing).validate(), vec![]);
+    }
+
+    #[test]
+    fn test_nested_form() {
+        let inner = Shared::new(Form::new());
+        let body = Body::new()
+            .child(Form::new())
+            .child(Form::new().child(Div::new().child(inner.clone())))
+            .child(inner);
+        assert_eq!(
+            body.validate(),
+            vec![Diagnostic {
+                path: vec![1, 0, 0],
+                problem: Problem::NestedForm { outer: vec![1] }
+            }]
+        );
+        // only the outermost form is reported
+        let body = Body::new().child(Form::new().child(Form::new().child(Form::new())));
+        assert_eq!(
+            body.validate()
+                .into_iter()
+                .map(|diagnostic| diagnostic.problem)
+                .collect::<Vec<_>>(),
+            vec![
+                Problem::NestedForm { outer: vec![0] },
+                Problem::NestedForm { outer: vec![0] }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_titles() {
+        let head = |titles: usize| {
+            Html::new().head(Head::new().children((0..titles).map(|_| Title::new("title"))))
+        };
+        assert_eq!(
+            head(0).validate(),
+            vec![Diagnostic {
+                path: vec![],
+                problem: Problem::MissingTitle
+            }]
+        );
+        assert_eq!(head(1).validate(), vec![]);
+        assert_eq!(
+            head(2).validate(),
+            vec![Diagnostic {
+                path: vec![],
+                problem: Problem::MultipleTitles
+            }]
+        );
+    }
+
+    #[test]
+    fn test_fragments_are_flattened() {
+        let fragment = || Fragment::new().child(Text::new("a")).child(Div::new());
+        let expected = |path| {
+            vec![Diagnostic {
+                path,
+                problem: Problem::NotAllowedInside {
+                    tag: "div",
+                    parent: "p",
+                },
+            }]
+        };
+        let body = Body::new().child(P::new("").child(Br::new()).child(lazy(fragment)));
+        assert_eq!(body.validate(), expected(vec![0, 2]));
+        let body = Body::new().child(P::new("").child(Shared::new(fragment())).child(Br::new()));
+        assert_eq!(body.validate(), expected(vec![0, 1]));
+        assert_eq!(
+            BodyNode::from(lazy(move || P::new("").child(lazy(fragment)))).validate(),
+            expected(vec![1])
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let diagnostic = |problem| Diagnostic {
+            path: vec![0, 1],
+            problem,
+        };
+        assert_eq!(
+            diagnostic(Problem::NestedForm { outer: vec![0] }).to_string(),
+            "`<form>` is not allowed inside the `<form>` at [0] (at [0, 1])"
+        );
+        assert_eq!(
+            diagnostic(Problem::MissingTitle).to_string(),
+            "`<head>` does not contain a `<title>` (at [0, 1])"
+        );
+        assert_eq!(
+            diagnostic(Problem::MultipleTitles).to_string(),
+            "`<head>` contains more than one `<title>` (at [0, 1])"
+        );
+    }
+}
@@ -0,0 +1,416 @@
+/*
+This source code file is distributed subject to the terms of the Mozilla Public License v2.0.
+A copy of this license can be found in the `licenses` directory at the root of this project.
+*/
+//! Traversal of a document after it has been built.
+//!
+//! There are two ways to walk a tree. The [`descendants`](BodyNode::descendants)
+//! iterators (available on [`Html`], [`Body`] and [`BodyNode`]) are the
+//! easiest way to find nodes:
+//!
+//! ```
+//! # use malvolio::prelude::*;
+//! use malvolio::attributes::Attributes;
+//!
+//! let document = Html::new().body(
+//!     Body::new()
+//!         .child(Img::new().attribute(Src::new("/a.png")))
+//!         .child(Div::new().child(Img::new().attribute(Src::new("/b.png")))),
+//! );
+//! let sources = document
+//!     .descendants()
+//!     .filter_map(BodyNode::as_img)
+//!     .filter_map(|img| img.get_attribute("src"))
+//!     .collect::<Vec<_>>();
+//! assert_eq!(sources, vec!["/a.png", "/b.png"]);
+//! ```
+//!
+//! For transformations, implement [`VisitorMut`] (or [`Visitor`], if you do not
+//! need to modify the tree). Every method has a default implementation which
+//! continues the traversal into the node's children (using the corresponding
+//! `walk_*` function), so you only need to override the methods for the nodes
+//! you are interested in.
+//!
+//! ```
+//! # use malvolio::prelude::*;
+//! use malvolio::{
+//!     attributes::Attributes,
+//!     visit::{walk_body_node_mut, VisitorMut},
+//! };
+//!
+//! struct RewriteLinks;
+//!
+//! impl<'a> VisitorMut<'a> for RewriteLinks {
+//!     fn visit_body_node_mut(&mut self, node: &mut BodyNode<'a>) {
+//!         if let BodyNode::A(a) = node {
+//!             if let Some(href) = a.attribute_mut("href") {
+//!                 *href = format!("https://example.com{}", href).into();
+//!             }
+//!         }
+//!         walk_body_node_mut(self, node);
+//!     }
+//! }
+//!
+//! let mut document = Html::new().body(Body::new().child(A::new().attribute(Href::new("/about"))));
+//! RewriteLinks.visit_html_mut(&mut document);
+//! assert!(document.to_string().contains("href=\"https://example.com/about\""));
+//! ```
+
+use crate::tags::{
+    body::{body_node::BodyNode, Body},
+    head::{head_node::HeadNode, Head},
+    html::Html,
+    option::SelectOption,
+};
+
+/// Visits the nodes of a document by reference.
+///
+/// See the [module-level documentation](self) for further details.
+pub trait Visitor<'a> {
+    /// Visit the `<html>` tag.
+    fn visit_html(&mut self, html: &Html<'a>) {
+        walk_html(self, html)
+    }
+
+    /// Visit the `<head>` tag.
+    fn visit_head(&mut self, head: &Head<'a>) {
+        walk_head(self, head)
+    }
+
+    /// Visit a child of the `<head>` tag.
+    fn visit_head_node(&mut self, _node: &HeadNode<'a>) {}
+
+    /// Visit the `<body>` tag.
+    fn visit_body(&mut self, body: &Body<'a>) {
+        walk_body(self, body)
+    }
+
+    /// Visit a node in the body of the document.
+    fn visit_body_node(&mut self, node: &BodyNode<'a>) {
+        walk_body_node(self, node)
+    }
+
+    /// Visit an `<option>` inside a `<select>` tag.
+    fn visit_select_option(&mut self, _option: &SelectOption<'a>) {}
+}
+
+/// Visit the `<head>` and `<body>` of a document.
+pub fn walk_html<'a, V>(visitor: &mut V, html: &Html<'a>)
+where
+    V: Visitor<'a> + ?Sized,
+{
+    visitor.visit_head(&html.head);
+    visitor.visit_body(&html.body);
+}
+
+/// Visit the children of a `<head>` tag.
+pub fn walk_head<'a, V>(visitor: &mut V, head: &Head<'a>)
+where
+    V: Visitor<'a> + ?Sized,
+{
+    for node in head.child_nodes() {
+        visitor.visit_head_node(node);
+    }
+}
+
+/// Visit the children of a `<body>` tag.
+pub fn walk_body<'a, V>(visitor: &mut V, body: &Body<'a>)
+where
+    V: Visitor<'a> + ?Sized,
+{
+    for node in body.child_nodes() {
+        visitor.visit_body_node(node);
+    }
+}
+
+/// Visit the children of a node (including the options of a `<select>`).
+pub fn walk_body_node<'a, V>(visitor: &mut V, node: &BodyNode<'a>)
+where
+    V: Visitor<'a> + ?Sized,
+{
+    if let BodyNode::Select(select) = node {
+        for option in select.child_nodes() {
+            visitor.visit_select_option(option);
+        }
+    }
+    for child in node.children() {
+        visitor.visit_body_node(child);
+    }
+}
+
+/// Visits the nodes of a document by mutable reference, allowing them to be
+/// modified (or replaced).
+///
+/// See the [module-level documentation](self) for further details.
+pub trait VisitorMut<'a> {
+    /// Visit the `<html>` tag.
+    fn visit_html_mut(&mut self, html: &mut Html<'a>) {
+        walk_html_mut(self, html)
+    }
+
+    /// Visit the `<head>` tag.
+    fn visit_head_mut(&mut self, head: &mut Head<'a>) {
+        walk_head_mut(self, head)
+    }
+
+    /// Visit a child of the `<head>` tag.
+    fn visit_head_node_mut(&mut self, _node: &mut HeadNode<'a>) {}
+
+    /// Visit the `<body>` tag.
+    fn visit_body_mut(&mut self, body: &mut Body<'a>) {
+        walk_body_mut(self, body)
+    }
+
+    /// Visit a node in the body of the document.
+    fn visit_body_node_mut(&mut self, node: &mut BodyNode<'a>) {
+        walk_body_node_mut(self, node)
+    }
+
+    /// Visit an `<option>` inside a `<select>` tag.
+    fn visit_select_option_mut(&mut self, _option: &mut SelectOption<'a>) {}
+}
+
+/// Visit the `<head>` and `<body>` of a document.
+pub fn walk_html_mut<'a, V>(visitor: &mut V, html: &mut Html<'a>)
+where
+    V: VisitorMut<'a> + ?Sized,
+{
+    visitor.visit_head_mut(&mut html.head);
+    visitor.visit_body_mut(&mut html.body);
+}
+
+/// Visit the children of a `<head>` tag.
+pub fn walk_head_mut<'a, V>(visitor: &mut V, head: &mut Head<'a>)
+where
+    V: VisitorMut<'a> + ?Sized,
+{
+    for node in head.child_nodes_mut() {
+        visitor.visit_head_node_mut(node);
+    }
+}
+
+/// Visit the children of a `<body>` tag.
+pub fn walk_body_mut<'a, V>(visitor: &mut V, body: &mut Body<'a>)
+where
+    V: VisitorMut<'a> + ?Sized,
+{
+    for node in body.child_nodes_mut() {
+        visitor.visit_body_node_mut(node);
+    }
+}
+
+/// Visit the children of a node (including the options of a `<select>`, and the
+/// node inside a [`Shared`](crate::shared::Shared) node, which is copied first if
+/// it is still being shared).
+pub fn walk_body_node_mut<'a, V>(visitor: &mut V, node: &mut BodyNode<'a>)
+where
+    V: VisitorMut<'a> + ?Sized,
+{
+    if let BodyNode::Select(select) = node {
+        for option in select.child_nodes_mut() {
+            visitor.visit_select_option_mut(option);
+        }
+    }
+    if let BodyNode::Shared(shared) = node {
+        visitor.visit_body_node_mut(shared.make_mut());
+    }
+    if let Some(children) = node.children_mut() {
+        for child in children {
+            visitor.visit_body_node_mut(child);
+        }
+    }
+}
+
+/// An iterator over the descendants of a node, in document order.
+///
+/// This is returned by `descendants` on [`Html`], [`Body`] and [`BodyNode`].
+#[derive(Debug, Clone)]
+pub struct Descendants<'r, 'a> {
+    stack: Vec<&'r BodyNode<'a>>,
+}
+
+impl<'r, 'a> Descendants<'r, 'a> {
+    fn new(nodes: &'r [BodyNode<'a>]) -> Self {
+        Self {
+            stack: nodes.iter().rev().collect(),
+        }
+    }
+}
+
+impl<'r, 'a> Iterator for Descendants<'r, 'a> {
+    type Item = &'r BodyNode<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.stack.extend(node.children().iter().rev());
+        Some(node)
+    }
+}
+
+impl<'a> Html<'a> {
+    /// Returns an iterator over every node in the body of this document, in
+    /// document order.
+    pub fn descendants(&self) -> Descendants<'_, 'a> {
+        self.body.descendants()
+    }
+}
+
+impl<'a> Body<'a> {
+    /// Returns an iterator over every node inside this tag, in document order.
+    pub fn descendants(&self) -> Descendants<'_, 'a> {
+        Descendants::new(self.child_nodes())
+    }
+}
+
+impl<'a> BodyNode<'a> {
+    /// Returns an iterator over every node inside this node (not including the
+    /// node itself), in document order.
+    pub fn descendants(&self) -> Descendants<'_, 'a> {
+        Descendants::new(self.children())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{walk_body_node, Visitor, VisitorMut};
+    use crate::{attributes::Attributes, prelude::*};
+
+    fn document() -> Html<'static> {
+        Html::new()
+            .head(
+                Head::new()
+                    .child(Title::new("Title"))
+                    .child(StyleTag::new("p { color: red; }")),
+            )
+            .body(
+                Body::new()
+                    .child(H1::new("Heading"))
+                    .child(
+                        Div::new()
+                            .child(A::new().attribute(Href::new("/one")).text("One"))
+                            .child(
+                                Form::new()
+                                    .child(Input::new().attribute(Type::Email))
+                                    .child(Select::new().child(SelectOption::new().text("a"))),
+                            ),
+                    )
+                    .child(A::new().attribute(Href::new("/two")).text("Two")),
+            )
+    }
+
+    #[test]
+    fn test_descendants() {
+        let document = document();
+        let hrefs = document
+            .descendants()
+            .filter_map(BodyNode::as_a)
+            .filter_map(|a| a.get_attribute("href"))
+            .collect::<Vec<_>>();
+        assert_eq!(hrefs, vec!["/one", "/two"]);
+        assert_eq!(document.descendants().count(), 7);
+        let div = document.descendants().find_map(BodyNode::as_div).unwrap();
+        assert_eq!(BodyNode::from(div.clone()).descendants().count(), 4);
+    }
+
+    #[test]
+    fn test_visitor() {
+        #[derive(Default)]
+        struct Counter {
+            nodes: usize,
+            options: usize,
+            head_nodes: usize,
+        }
+
+        impl<'a> Visitor<'a> for Counter {
+            fn visit_head_node(&mut self, _: &HeadNode<'a>) {
+                self.head_nodes += 1;
+            }
+
+            fn visit_body_node(&mut self, node: &BodyNode<'a>) {
+                self.nodes += 1;
+                walk_body_node(self, node);
+            }
+
+            fn visit_select_option(&mut self, _: &SelectOption<'a>) {
+                self.options += 1;
+            }
+        }
+
+        let mut counter = Counter::default();
+        counter.visit_html(&document());
+        assert_eq!(counter.nodes, 7);
+        assert_eq!(counter.options, 1);
+        assert_eq!(counter.head_nodes, 2);
+    }
+
+    #[test]
+    fn test_visitor_mut() {
+        struct AddNonce;
+
+        impl<'a> VisitorMut<'a> for AddNonce {
+            fn visit_head_node_mut(&mut self, node: &mut HeadNode<'a>) {
+                if let HeadNode::StyleTag(style) = node {
+                    style.set_attribute(Nonce::new("abc")).unwrap();
+                }
+            }
+
+            fn visit_body_node_mut(&mut self, node: &mut BodyNode<'a>) {
+                if let Some(children) = node.children_mut() {
+                    children.retain(|child| child.as_input().is_none());
+                }
+                super::walk_body_node_mut(self, node);
+            }
+        }
+
+        let mut document = document();
+        AddNonce.visit_html_mut(&mut document);
+        let document = scraper::Html::parse_document(&document.to_string());
+        let style = scraper::Selector::parse("style").unwrap();
+        let style = document.select(&style).next().unwrap();
+        assert_eq!(style.value().attr("nonce"), Some("abc"));
+        let input = scraper::Selector::parse("input").unwrap();
+        assert_eq!(document.select(&input).count(), 0);
+    }
+
+    #[test]
+    fn test_shared_and_lazy_nodes() {
+        let shared = Shared::new(Div::new().child(Input::new()).child(P::new("a")));
+        let mut body = Body::new()
+            .child(shared.clone())
+            .child(lazy(|| Div::new().child(Input::new())));
+
+        // shared nodes are visited (followed by the node they contain), but
+        // lazy nodes are not constructed
+        let kinds = body
+            .descendants()
+            .map(|node| match node {
+                BodyNode::Shared(_) => "shared",
+                BodyNode::Lazy(_) => "lazy",
+                node => node.tag_name().unwrap(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(kinds, ["shared", "div", "input", "p", "lazy"]);
+
+        // a `VisitorMut` changes a copy of the contents of shared nodes
+        struct RemoveInputs;
+
+        impl<'a> VisitorMut<'a> for RemoveInputs {
+            fn visit_body_node_mut(&mut self, node: &mut BodyNode<'a>) {
+                if let Some(children) = node.children_mut() {
+                    children.retain(|child| child.as_input().is_none());
+                }
+                super::walk_body_node_mut(self, node);
+            }
+        }
+
+        RemoveInputs.visit_body_mut(&mut body);
+        assert_eq!(body.descendants().filter_map(BodyNode::as_input).count(), 0);
+        assert_eq!(
+            body.to_string(),
+            "<body><div><p>a</p></div><div><input/></div></body>"
+        );
+        let copy = body.child_nodes()[0].as_shared().unwrap();
+        assert!(!copy.ptr_eq(&shared));
+        assert_eq!(shared.get().children().len(), 2);
+    }
+}